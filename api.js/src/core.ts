@@ -17,6 +17,11 @@ function transformCallback<T = unknown>(callback?: (response: T) => void, once =
     return window.__TAURI_INTERNALS__.transformCallback(callback, once);
 }
 
+/**
+ * Raw message received from the backend through a {@linkcode Channel}.
+ */
+type ChannelMessage<T> = { message: T; id: number } | { end: true; id: number };
+
 class Channel<T = unknown> {
     id: number;
     // @ts-expect-error field used by the IPC serializer
@@ -24,21 +29,26 @@ class Channel<T = unknown> {
     #onmessage: (response: T) => void = () => {
         // no-op
     };
+    #onclose: () => void = () => {
+        // no-op
+    };
     #nextMessageId = 0;
-    #pendingMessages: Record<string, T> = {};
+    #pendingMessages: Record<string, ChannelMessage<T>> = {};
 
     constructor() {
-        this.id = transformCallback(({ message, id }: { message: T; id: number }) => {
+        this.id = transformCallback((rawMessage: ChannelMessage<T>) => {
+            const id = rawMessage.id;
             // the id is used as a mechanism to preserve message order
             if (id === this.#nextMessageId) {
                 this.#nextMessageId = id + 1;
-                this.#onmessage(message);
+                this.#handle(rawMessage);
 
                 // process pending messages
                 const pendingMessageIds = Object.keys(this.#pendingMessages);
                 if (pendingMessageIds.length > 0) {
                     let nextId = id + 1;
-                    for (const pendingId of pendingMessageIds.sort()) {
+                    const sortedIds = pendingMessageIds.sort((a, b) => parseInt(a) - parseInt(b));
+                    for (const pendingId of sortedIds) {
                         // if we have the next message, process it
                         if (parseInt(pendingId) === nextId) {
                             // eslint-disable-next-line security/detect-object-injection
@@ -46,10 +56,11 @@ class Channel<T = unknown> {
                             // eslint-disable-next-line security/detect-object-injection
                             delete this.#pendingMessages[pendingId];
 
-                            this.#onmessage(message);
+                            this.#handle(message);
 
                             // move the id counter to the next message to check
                             nextId += 1;
+                            this.#nextMessageId = nextId;
                         } else {
                             // we do not have the next message, let's wait
                             break;
@@ -57,11 +68,21 @@ class Channel<T = unknown> {
                     }
                 }
             } else {
-                this.#pendingMessages[id.toString()] = message;
+                this.#pendingMessages[id.toString()] = rawMessage;
             }
         });
     }
 
+    #handle(rawMessage: ChannelMessage<T>): void {
+        if ('end' in rawMessage) {
+            // the backend closed the channel, no more messages will be delivered
+            Reflect.deleteProperty(window, `_${this.id}`);
+            this.#onclose();
+        } else {
+            this.#onmessage(rawMessage.message);
+        }
+    }
+
     set onmessage(handler: (response: T) => void) {
         this.#onmessage = handler;
     }
@@ -70,6 +91,19 @@ class Channel<T = unknown> {
         return this.#onmessage;
     }
 
+    /**
+     * Handler called when the backend closes the channel, after every message sent before it was delivered.
+     *
+     * @since 2.0.0
+     */
+    set onclose(handler: () => void) {
+        this.#onclose = handler;
+    }
+
+    get onclose(): () => void {
+        return this.#onclose;
+    }
+
     toJSON(): string {
         return `__CHANNEL__:${this.id}`;
     }
@@ -120,11 +154,87 @@ async function addPluginListener<T>(
  */
 type InvokeArgs = Record<string, unknown> | number[] | ArrayBuffer | Uint8Array;
 
+/**
+ * A codec of the command arguments and of the response.
+ *
+ * The backend supports `application/msgpack` with the `ipc-msgpack` Cargo feature
+ * and `application/cbor` with the `ipc-cbor` Cargo feature.
+ * @example
+ * ```typescript
+ * import { encode, decode } from '@msgpack/msgpack';
+ * import { invoke } from '@tauri-apps/api/core';
+ * const msgpack = { mimeType: 'application/msgpack', encode, decode };
+ * await invoke('upload', { data: new Uint8Array([1, 2, 3]) }, { codec: msgpack });
+ * ```
+ *
+ * @since 2.0.0
+ */
+interface InvokeCodec {
+    /** The mime type sent on the `Content-Type` and `Accept` headers. */
+    mimeType: string;
+    /** Encodes the command arguments. */
+    encode(args: unknown): Uint8Array;
+    /** Decodes the response and the rejections of the command. */
+    decode(bytes: Uint8Array): unknown;
+}
+
 /**
  * @since 2.0.0
  */
 interface InvokeOptions {
-    headers: Headers | Record<string, string>;
+    headers?: Headers | Record<string, string>;
+    /**
+     * Cancels the invoke when aborted, rejecting the promise with the signal's reason
+     * and aborting the command on the backend.
+     */
+    signal?: AbortSignal;
+    /**
+     * Encodes the arguments and decodes the response with this codec instead of JSON.
+     * Raw responses of the command are decoded as well.
+     */
+    codec?: InvokeCodec;
+}
+
+/**
+ * A structured error rejected by the backend.
+ *
+ * The errors produced by Tauri use the `ACL_DENIED`, `COMMAND_NOT_FOUND`, `PLUGIN_NOT_FOUND`,
+ * `INVALID_ARGS`, `STATE_NOT_MANAGED`, `STATE_INIT_FAILED`,
+ * `COMMAND_TIMEOUT` and `RESPONSE_ENCODING_FAILED` codes.
+ *
+ * @since 2.0.0
+ */
+interface IpcError {
+    code: string;
+    message: string;
+    data?: unknown;
+    /** The messages of the errors that caused this error, from the closest to the root cause. */
+    causes?: string[];
+}
+
+/**
+ * Checks whether an invoke rejection is an {@link IpcError}.
+ * @example
+ * ```typescript
+ * import { invoke, isIpcError } from '@tauri-apps/api/core';
+ * try {
+ *   await invoke('read_settings');
+ * } catch (e) {
+ *   if (isIpcError(e) && e.code === 'ACL_DENIED') {
+ *     console.error('missing permission', e.message);
+ *   }
+ * }
+ * ```
+ *
+ * @since 2.0.0
+ */
+function isIpcError(error: unknown): error is IpcError {
+    return (
+        typeof error === 'object' &&
+        error !== null &&
+        typeof (error as IpcError).code === 'string' &&
+        typeof (error as IpcError).message === 'string'
+    );
 }
 
 /**
@@ -224,6 +334,43 @@ export class Resource {
     }
 }
 
-export type { InvokeArgs, InvokeOptions };
+/**
+ * A resource of the resource table of the current webview.
+ *
+ * @since 2.0.0
+ */
+interface ResourceInfo {
+    /** The resource id. */
+    rid: number;
+    /** The name of the resource, the Rust type name by default. */
+    name: string;
+    /** The time the resource was created, in milliseconds since the Unix epoch. */
+    createdAt: number;
+    /** The estimated size of the resource in bytes. */
+    size: number;
+}
+
+/**
+ * Lists the resources of the current webview that were not closed yet.
+ * Useful to find leaked resources while debugging.
+ *
+ * Requires the `resources:allow-list` permission.
+ *
+ * @since 2.0.0
+ */
+async function listResources(): Promise<ResourceInfo[]> {
+    return invoke('plugin:resources|list');
+}
+
+export type { InvokeArgs, InvokeCodec, InvokeOptions, IpcError, ResourceInfo };
 
-export { transformCallback, Channel, PluginListener, addPluginListener, invoke, convertFileSrc };
+export {
+    transformCallback,
+    Channel,
+    PluginListener,
+    addPluginListener,
+    invoke,
+    isIpcError,
+    convertFileSrc,
+    listResources
+};
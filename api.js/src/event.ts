@@ -16,12 +16,18 @@ type EventTarget =
     | { kind: 'WebviewWindow'; label: string };
 
 interface Event<T> {
-    /** Event name */
+    /**
+     * Event name. When listening to a pattern, this is the name of the emitted event:
+     * `*` matches any characters except `:`, so `sync:*` matches `sync:done` but not `sync:file:done`,
+     * and `**` matches any characters, so `sync:**` matches both.
+     */
     event: EventName;
     /** Event identifier used to unlisten */
     id: number;
     /** Event payload */
     payload: T;
+    /** Identifier of the request when the event was emitted with `Manager::request`, see {@link reply} */
+    requestId?: number;
 }
 
 type EventCallback<T> = (event: Event<T>) => void;
@@ -57,6 +63,7 @@ enum TauriEvent {
     DROP = 'tauri://drop',
     DROP_OVER = 'tauri://drop-over',
     DROP_CANCELLED = 'tauri://drag-cancelled',
+    CAPABILITY_EXPIRED = 'tauri://capability-expired',
 }
 
 /**
@@ -88,7 +95,7 @@ async function _unlisten(event: string, eventId: number): Promise<void> {
  * unlisten();
  * ```
  *
- * @param event Event name. Must include only alphanumeric characters, `-`, `/`, `:` and `_`.
+ * @param event Event name or pattern like `sync:*`, see {@link Event.event | Event.event}. Must include only alphanumeric characters, `-`, `/`, `:`, `_` and `*`.
  * @param handler Event handler callback.
  * @param options Event listening options.
  * @returns A promise resolving to a function to unlisten to the event.
@@ -132,7 +139,7 @@ async function listen<T>(
  * unlisten();
  * ```
  *
- * @param event Event name. Must include only alphanumeric characters, `-`, `/`, `:` and `_`.
+ * @param event Event name or pattern like `sync:*`, see {@link Event.event | Event.event}. Must include only alphanumeric characters, `-`, `/`, `:`, `_` and `*`.
  * @param handler Event handler callback.
  * @param options Event listening options.
  * @returns A promise resolving to a function to unlisten to the event.
@@ -205,6 +212,36 @@ async function emitTo(
     });
 }
 
+/**
+ * Replies to an event emitted with `Manager::request` on the Rust side.
+ *
+ * Only the first reply is delivered to the requester,
+ * and replies from webviews the request was not sent to are ignored.
+ * Requires the `event:allow-reply` permission, which is not part of `event:default`.
+ *
+ * @example
+ * ```typescript
+ * import { listen, reply } from '@tauri-apps/api/event';
+ * await listen('get-theme', async (event) => {
+ *   await reply(event, 'dark');
+ * });
+ * ```
+ *
+ * @param event The request event.
+ * @param payload Reply payload.
+ *
+ * @since 2.0.0
+ */
+async function reply(event: Event<unknown>, payload?: unknown): Promise<void> {
+    if (event.requestId === undefined) {
+        throw new Error('the event is not a request');
+    }
+    await invoke('plugin:event|reply', {
+        requestId: event.requestId,
+        payload,
+    });
+}
+
 export type { Event, EventTarget, EventCallback, UnlistenFn, EventName, Options };
 
-export { listen, once, emit, emitTo, TauriEvent };
+export { listen, once, emit, emitTo, reply, TauriEvent };
@@ -195,7 +195,11 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
             },
             None => Default::default(),
         };
-        quote!(#assets)
+        if config.build.asset_pack {
+            assets.to_pack_tokens()?
+        } else {
+            quote!(#assets)
+        }
     };
 
     let out_dir = {
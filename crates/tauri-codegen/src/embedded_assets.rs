@@ -9,7 +9,13 @@ use std::{
     path::{Path, PathBuf},
 };
 use tauri_utils::config::PatternKind;
-use tauri_utils::{assets::AssetKey, config::DisabledCspModificationKind};
+use tauri_utils::{
+    assets::{
+        pack::{AssetPackWriter, ASSET_PACK_FILE_NAME},
+        AssetKey,
+    },
+    config::DisabledCspModificationKind,
+};
 use thiserror::Error;
 use walkdir::{DirEntry, WalkDir};
 
@@ -19,8 +25,8 @@ use brotli::enc::backward_references::BrotliEncoderParams;
 /// The subdirectory inside the target directory we want to place assets.
 const TARGET_PATH: &str = "tauri-codegen-assets";
 
-/// (key, (original filepath, compressed bytes))
-type Asset = (AssetKey, (PathBuf, PathBuf));
+/// (key, (original filepath, compressed bytes, content hash))
+type Asset = (AssetKey, (PathBuf, PathBuf, String));
 
 /// All possible errors while reading and compressing an [`EmbeddedAssets`] directory
 #[derive(Debug, Error)]
@@ -53,6 +59,12 @@ pub enum EmbeddedAssetsError {
     #[error("OUT_DIR env var is not set, do you have a build script?")]
     OutDir,
 
+    #[error("failed to write the asset pack to {path} because {error}")]
+    AssetPack {
+        path: PathBuf,
+        error: tauri_utils::assets::pack::Error,
+    },
+
     #[error("version error: {0}")]
     Version(#[from] semver::Error),
 }
@@ -68,7 +80,7 @@ pub enum EmbeddedAssetsError {
 /// the compressed assets in that application's binary.
 #[derive(Default)]
 pub struct EmbeddedAssets {
-    assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
+    assets: HashMap<AssetKey, (PathBuf, PathBuf, String)>,
     csp_hashes: CspHashes,
 }
 
@@ -260,7 +272,7 @@ impl EmbeddedAssets {
 
         struct CompressState {
             csp_hashes: CspHashes,
-            assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
+            assets: HashMap<AssetKey, (PathBuf, PathBuf, String)>,
         }
 
         let CompressState { assets, csp_hashes } = paths.into_iter().try_fold(
@@ -354,7 +366,7 @@ impl EmbeddedAssets {
         let out_path = if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
             out_dir.join(format!("{hash}.{ext}"))
         } else {
-            out_dir.join(hash)
+            out_dir.join(&hash)
         };
 
         // only compress and write to the file if it doesn't already exist.
@@ -389,17 +401,72 @@ impl EmbeddedAssets {
             }
         }
 
-        Ok((key, (path.into(), out_path)))
+        Ok((key, (path.into(), out_path, hash)))
+    }
+
+    /// Writes the assets to an asset pack in the target directory of the current profile, where the bundler picks it up,
+    /// and returns the code of the [`tauri_utils::assets::pack::PackedAssets`] reading it, with the pack hash pinned.
+    pub fn to_pack_tokens(&self) -> Result<TokenStream, EmbeddedAssetsError> {
+        let mut writer = AssetPackWriter::new();
+        let mut dependencies = TokenStream::new();
+        for (key, (input, output, _hash)) in &self.assets {
+            let contents =
+                std::fs::read(output).map_err(|error| EmbeddedAssetsError::AssetRead {
+                    path: output.clone(),
+                    error,
+                })?;
+            #[cfg(feature = "compression")]
+            let encoding = Some(tauri_utils::assets::AssetEncoding::Brotli);
+            #[cfg(not(feature = "compression"))]
+            let encoding = None;
+            writer.add(key, &contents, encoding);
+
+            // add original asset as a compiler dependency so the pinned hash is kept up to date
+            let input = input.display().to_string();
+            dependencies.append_all(quote!(
+                const _: &[u8] = include_bytes!(#input);
+            ));
+        }
+
+        let index = writer.index_mut();
+        index.script_hashes = self.csp_hashes.scripts.clone();
+        index.style_hashes = self.csp_hashes.styles.clone();
+        index.html_script_hashes = self
+            .csp_hashes
+            .inline_scripts
+            .iter()
+            .map(|(path, hashes)| (path.clone(), hashes.clone()))
+            .collect();
+
+        // the OUT_DIR is `target/<profile>/build/<crate>-<hash>/out`
+        let out_path = std::env::var("OUT_DIR")
+            .map(PathBuf::from)
+            .ok()
+            .and_then(|p| p.ancestors().nth(3).map(|p| p.join(ASSET_PACK_FILE_NAME)))
+            .ok_or(EmbeddedAssetsError::OutDir)?;
+        let hash = writer
+            .write(&out_path)
+            .map_err(|error| EmbeddedAssetsError::AssetPack {
+                path: out_path.clone(),
+                error,
+            })?;
+
+        Ok(quote! {{
+            #dependencies
+            ::tauri::utils::assets::pack::PackedAssets::new(#ASSET_PACK_FILE_NAME, [#(#hash),*])
+        }})
     }
 }
 
 impl ToTokens for EmbeddedAssets {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let mut assets = TokenStream::new();
-        for (key, (input, output)) in &self.assets {
+        let mut hashes = TokenStream::new();
+        for (key, (input, output, hash)) in &self.assets {
             let key: &str = key.as_ref();
             let input = input.display().to_string();
             let output = output.display().to_string();
+            hashes.append_all(quote!(#key => #hash,));
 
             // add original asset as a compiler dependency, rely on dead code elimination to clean it up
             assets.append_all(quote!(#key => {
@@ -435,6 +502,7 @@ impl ToTokens for EmbeddedAssets {
             #[allow(unused_imports)]
             use ::tauri::utils::assets::{CspHash, EmbeddedAssets, phf, phf::phf_map};
             EmbeddedAssets::new(phf_map! { #assets }, &[#global_hashes], phf_map! { #html_hashes })
+                .with_content_hashes(phf_map! { #hashes })
         }});
     }
 }
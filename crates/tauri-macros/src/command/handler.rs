@@ -80,3 +80,19 @@ impl From<Handler> for proc_macro::TokenStream {
         .into()
     }
 }
+
+impl Handler {
+    /// Generates the list of bindings of the commands, see [`generate_bindings!`](crate::generate_bindings).
+    pub fn into_bindings(self) -> proc_macro::TokenStream {
+        let (paths, attrs): (Vec<Path>, Vec<Vec<Attribute>>) = self
+            .command_defs
+            .into_iter()
+            .map(|def| (def.path, def.attrs))
+            .unzip();
+        let wrappers = self.wrappers;
+        quote::quote!(::std::vec![
+          #(#(#attrs)* #wrappers!(@bindings #paths),)*
+        ])
+        .into()
+    }
+}
@@ -2,15 +2,15 @@ use std::env::var;
 
 use heck::{ToLowerCamelCase, ToSnakeCase};
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
-use quote::{format_ident, quote, quote_spanned};
+use proc_macro2::{Group, Ident, Punct, Spacing, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
     ext::IdentExt,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
     spanned::Spanned,
-    Expr, ExprLit, FnArg, ItemFn, Lit, Meta, Pat, Token, Visibility,
+    Expr, ExprLit, FnArg, ItemFn, Lit, Meta, Pat, ReturnType, Token, Visibility,
 };
 
 enum WrapperAttributeKind {
@@ -34,6 +34,8 @@ struct WrapperAttributes {
     root: TokenStream2,
     execution_context: ExecutionContext,
     argument_case: ArgumentCase,
+    /// The command timeout in milliseconds.
+    timeout: Option<u64>,
 }
 
 impl Parse for WrapperAttributes {
@@ -42,6 +44,7 @@ impl Parse for WrapperAttributes {
             root: quote!(::tauri),
             execution_context: ExecutionContext::Blocking,
             argument_case: ArgumentCase::Camel,
+            timeout: None,
         };
 
         let attrs = Punctuated::<WrapperAttributeKind, Token![,]>::parse_terminated(input)?;
@@ -68,6 +71,20 @@ impl Parse for WrapperAttributes {
                                 }
                             };
                         }
+                    } else if v.path.is_ident("timeout") {
+                        if let Expr::Lit(ExprLit {
+                            lit: Lit::Str(s),
+                            attrs: _,
+                        }) = v.value
+                        {
+                            wrapper_attributes.timeout =
+                                Some(parse_duration(&s.value()).ok_or_else(|| {
+                                    syn::Error::new(
+                                        s.span(),
+                                        "expected a duration such as \"500ms\", \"30s\", \"5m\" or \"1h\"",
+                                    )
+                                })?);
+                        }
                     } else if v.path.is_ident("root") {
                         if let Expr::Lit(ExprLit {
                             lit: Lit::Str(s),
@@ -88,7 +105,7 @@ impl Parse for WrapperAttributes {
                 WrapperAttributeKind::Meta(Meta::Path(_)) => {
                     return Err(syn::Error::new(
                         input.span(),
-                        "unexpected input, expected one of `rename_all`, `root`, `async`",
+                        "unexpected input, expected one of `rename_all`, `root`, `timeout`, `async`",
                     ));
                 }
                 WrapperAttributeKind::Async => {
@@ -101,6 +118,20 @@ impl Parse for WrapperAttributes {
     }
 }
 
+/// Parses a duration with a `ms`, `s`, `m` or `h` suffix into milliseconds.
+fn parse_duration(duration: &str) -> Option<u64> {
+    let unit_start = duration.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = duration.split_at(unit_start);
+    let multiplier = match unit {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60 * 1000,
+        "h" => 60 * 60 * 1000,
+        _ => return None,
+    };
+    value.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
 /// The execution context of the command.
 enum ExecutionContext {
     Async,
@@ -130,6 +161,14 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
 
     if function.sig.asyncness.is_some() {
         attrs.execution_context = ExecutionContext::Async;
+    } else if attrs.timeout.is_some() {
+        // a blocking function cannot be interrupted
+        return syn::Error::new(
+            function.sig.fn_token.span(),
+            "the `timeout` attribute is only supported on `async fn` commands",
+        )
+        .into_compile_error()
+        .into();
     }
 
     // macros used with `pub use my_macro;` need to be exported with `#[macro_export]`
@@ -220,6 +259,8 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
         acl,
     } = invoke;
 
+    let bindings = bindings(&plugin_name, &function, &attrs);
+
     let root = attrs.root;
 
     let kind = match attrs.execution_context {
@@ -269,6 +310,10 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
 
             #body
         }};
+          // the argument and return types of the command, used to generate its bindings
+          (@bindings $path:path) => {{
+            #bindings
+          }};
       }
 
       // allow the macro to be resolved with the same path as the command function
@@ -294,16 +339,21 @@ fn body_async(
         resolver,
         acl,
     } = invoke;
-    parse_args(plugin_name, function, message, acl, attributes).map(|args| {
+    let maybe_timeout = attributes.timeout.map(|timeout| {
+        quote!(let #resolver = #resolver.with_timeout(::core::time::Duration::from_millis(#timeout));)
+    });
+    parse_args(plugin_name, function, message, acl, attributes, true).map(|args| {
         #[cfg(feature = "tracing")]
         quote! {
           use tracing::Instrument;
 
+          #maybe_timeout
+
           let span = tracing::debug_span!("ipc::request::run");
           #resolver.respond_async_serialized(async move {
             let result = $path(#(#args?),*);
             let kind = (&result).async_kind();
-            kind.future(result).await
+            kind.future(result, &#message).await
           }
           .instrument(span));
           return true;
@@ -311,10 +361,11 @@ fn body_async(
 
         #[cfg(not(feature = "tracing"))]
         quote! {
+          #maybe_timeout
           #resolver.respond_async_serialized(async move {
             let result = $path(#(#args?),*);
             let kind = (&result).async_kind();
-            kind.future(result).await
+            kind.future(result, &#message).await
           });
           return true;
         }
@@ -337,7 +388,7 @@ fn body_blocking(
         resolver,
         acl,
     } = invoke;
-    let args = parse_args(plugin_name, function, message, acl, attributes)?;
+    let args = parse_args(plugin_name, function, message, acl, attributes, false)?;
 
     // the body of a `match` to early return any argument that wasn't successful in parsing.
     let match_body = quote!({
@@ -367,6 +418,7 @@ fn parse_args(
     message: &Ident,
     acl: &Ident,
     attributes: &WrapperAttributes,
+    is_async: bool,
 ) -> syn::Result<Vec<TokenStream2>> {
     function
         .sig
@@ -380,12 +432,15 @@ fn parse_args(
                 message,
                 acl,
                 attributes,
+                is_async,
             )
         })
         .collect()
 }
 
 /// Transform a [`FnArg`] into a command argument.
+///
+/// The arguments of async commands wait for the initialization of the states they use.
 fn parse_arg(
     plugin_name: &TokenStream2,
     command: &Ident,
@@ -393,7 +448,29 @@ fn parse_arg(
     message: &Ident,
     acl: &Ident,
     attributes: &WrapperAttributes,
+    is_async: bool,
 ) -> syn::Result<TokenStream2> {
+    let key = parse_arg_key(arg, attributes.argument_case)?;
+
+    let root = &attributes.root;
+
+    let item = quote!(#root::ipc::CommandItem {
+      plugin: #plugin_name,
+      name: stringify!(#command),
+      key: #key,
+      message: &#message,
+      acl: &#acl,
+    });
+
+    Ok(if is_async {
+        quote!(#root::ipc::private::command_arg_async(#item).await)
+    } else {
+        quote!(#root::ipc::CommandArg::from_command(#item))
+    })
+}
+
+/// The key used to read the argument from the invoke payload.
+fn parse_arg_key(arg: &FnArg, argument_case: ArgumentCase) -> syn::Result<String> {
     // we have no use for self arguments
     let mut arg = match arg {
         FnArg::Typed(arg) => arg.pat.as_ref().clone(),
@@ -427,7 +504,7 @@ fn parse_arg(
         ));
     }
 
-    match attributes.argument_case {
+    match argument_case {
         ArgumentCase::Camel => {
             key = key.to_lower_camel_case();
         }
@@ -436,15 +513,92 @@ fn parse_arg(
         }
     }
 
+    Ok(key)
+}
+
+/// Generates the [`CommandBindings`] of the command.
+///
+/// Arguments and return types that depend on the function generics or use `impl Trait` are skipped.
+/// The kind of each type is resolved by autoref specialization on its `PhantomData`,
+/// so `Result` aliases are unwrapped and arguments that cannot be deserialized are left out as injected by Tauri.
+///
+/// [`CommandBindings`]: https://docs.rs/tauri/*/tauri/ipc/bindings/struct.CommandBindings.html
+fn bindings(
+    plugin_name: &TokenStream2,
+    function: &ItemFn,
+    attributes: &WrapperAttributes,
+) -> TokenStream2 {
     let root = &attributes.root;
+    let command = &function.sig.ident;
+    let generator = format_ident!("__tauri_generator__");
 
-    Ok(quote!(#root::ipc::CommandArg::from_command(
-      #root::ipc::CommandItem {
-        plugin: #plugin_name,
-        name: stringify!(#command),
-        key: #key,
-        message: &#message,
-        acl: &#acl,
-      }
-    )))
+    let generics = function
+        .sig
+        .generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect::<Vec<_>>();
+
+    let args = function.sig.inputs.iter().filter_map(|arg| {
+        let FnArg::Typed(typed) = arg else {
+            return None;
+        };
+        let key = parse_arg_key(arg, attributes.argument_case)
+            .ok()
+            .filter(|key| !key.is_empty())?;
+        let ty = bindings_type(typed.ty.to_token_stream(), &generics)?;
+        Some(quote!((#key, (&&&::core::marker::PhantomData::<#ty>).schema(#generator))))
+    });
+
+    let returns = match &function.sig.output {
+        ReturnType::Default => Some(quote!(())),
+        ReturnType::Type(_, ty) => bindings_type(ty.to_token_stream(), &generics),
+    }
+    .map(|ty| quote!((&&&::core::marker::PhantomData::<#ty>).returns_schema(#generator)))
+    .unwrap_or_else(|| quote!(::core::option::Option::None));
+
+    quote!(
+      #[allow(unused_imports)]
+      use #root::ipc::bindings::private::*;
+      command_bindings(
+        stringify!(#command),
+        #plugin_name,
+        |#generator: &mut SchemaGenerator| ::std::vec![#(#args),*],
+        |#generator: &mut SchemaGenerator| #returns,
+      )
+    )
+}
+
+/// Prepares a type to be used outside of the command function, erasing its lifetimes and resolving `crate` paths.
+///
+/// Returns `None` if the type depends on the function generics or uses `impl Trait`.
+fn bindings_type(ty: TokenStream2, generics: &[String]) -> Option<TokenStream2> {
+    let mut tokens = Vec::new();
+    let mut iter = ty.into_iter().peekable();
+    while let Some(token) = iter.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "impl" || generics.iter().any(|g| ident == g) => {
+                return None
+            }
+            // the type is used inside the wrapper macro, which can be called from other crates
+            TokenTree::Ident(ident) if ident == "crate" => {
+                tokens.push(TokenTree::Punct(Punct::new('$', Spacing::Alone)));
+                tokens.push(TokenTree::Ident(ident));
+            }
+            TokenTree::Group(group) => {
+                let stream = bindings_type(group.stream(), generics)?;
+                let mut erased = Group::new(group.delimiter(), stream);
+                erased.set_span(group.span());
+                tokens.push(TokenTree::Group(erased));
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                // skip the lifetime name and let the compiler infer it
+                iter.next();
+                tokens.push(TokenTree::Punct(punct));
+                tokens.push(TokenTree::Ident(Ident::new("_", Span::call_site())));
+            }
+            token => tokens.push(token),
+        }
+    }
+    Some(tokens.into_iter().collect())
 }
@@ -0,0 +1,156 @@
+use heck::ToShoutySnakeCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Fields, Ident, LitStr, Path,
+};
+
+/// The `#[ipc_error(...)]` attributes of the type or of a variant.
+#[derive(Default)]
+struct ErrorAttributes {
+    code: Option<LitStr>,
+    root: Option<Path>,
+}
+
+fn parse_attributes(attrs: &[Attribute]) -> syn::Result<ErrorAttributes> {
+    let mut attributes = ErrorAttributes::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("ipc_error")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                attributes.code = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("root") {
+                let root: LitStr = meta.value()?.parse()?;
+                attributes.root = Some(root.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `code` or `root`"))
+            }
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// Finds the field marked with `#[ipc_error(data)]`.
+fn data_field(fields: &Fields) -> syn::Result<Option<usize>> {
+    let mut data = None;
+    for (i, field) in fields.iter().enumerate() {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("ipc_error"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("data") {
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `data`"))
+                }
+            })?;
+            if data.replace(i).is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "only one field can be marked with `#[ipc_error(data)]`",
+                ));
+            }
+        }
+    }
+    Ok(data)
+}
+
+/// The pattern binding the data field of a struct or variant and the expression to set it as the error data.
+fn data_binding(fields: &Fields) -> syn::Result<(TokenStream, TokenStream)> {
+    let Some(index) = data_field(fields)? else {
+        return Ok((
+            match fields {
+                Fields::Named(_) => quote!({ .. }),
+                Fields::Unnamed(_) => quote!((..)),
+                Fields::Unit => quote!(),
+            },
+            quote!(),
+        ));
+    };
+
+    let binding = format_ident!("__ipc_error_data");
+    let pattern = match fields {
+        Fields::Named(_) => {
+            let name = fields.iter().nth(index).unwrap().ident.as_ref().unwrap();
+            quote!({ #name: #binding, .. })
+        }
+        _ => {
+            let skipped = (0..index).map(|_| quote!(_));
+            quote!((#(#skipped,)* #binding, ..))
+        }
+    };
+    Ok((pattern, quote!(.with_data(#binding))))
+}
+
+/// The code of a type or variant without `#[ipc_error(code = "...")]`.
+fn default_code(ident: &Ident) -> LitStr {
+    LitStr::new(&ident.to_string().to_shouty_snake_case(), ident.span())
+}
+
+pub(crate) fn derive_ipc_error(input: DeriveInput) -> syn::Result<TokenStream> {
+    let attributes = parse_attributes(&input.attrs)?;
+    let root = attributes.root.unwrap_or_else(|| parse_quote!(::tauri));
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let code = attributes.code.unwrap_or_else(|| default_code(name));
+            let (pattern, data) = data_binding(&data.fields)?;
+            quote! {
+                let Self #pattern = self;
+                #root::ipc::IpcError::from_error(#code, self) #data
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    let code = parse_attributes(&variant.attrs)?
+                        .code
+                        .unwrap_or_else(|| default_code(ident));
+                    let (pattern, data) = data_binding(&variant.fields)?;
+                    Ok(quote! {
+                        Self::#ident #pattern => #root::ipc::IpcError::from_error(#code, self) #data
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "IpcError cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #root::ipc::IntoIpcError for #name #ty_generics #where_clause {
+            fn to_ipc_error(&self) -> #root::ipc::IpcError {
+                #body
+            }
+        }
+
+        impl #impl_generics #root::ipc::private::serde::Serialize for #name #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #root::ipc::private::serde::Serializer,
+            {
+                #root::ipc::private::serde::Serialize::serialize(
+                    &#root::ipc::IntoIpcError::to_ipc_error(self),
+                    serializer,
+                )
+            }
+        }
+    })
+}
@@ -10,6 +10,7 @@ use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
 mod command;
+mod ipc_error;
 mod menu;
 mod mobile;
 mod runtime;
@@ -19,6 +20,14 @@ mod context;
 
 /// Mark a function as a command handler. It creates a wrapper function with the necessary glue code.
 ///
+/// A command can also return a `futures::Stream` of `Result` items,
+/// which resolves to an async iterator on the JavaScript side.
+/// Items are produced on demand and the stream is dropped as soon as the webview stops iterating.
+///
+/// An `async fn` command can bound its execution time with the `timeout` attribute, e.g. `#[command(timeout = "30s")]`,
+/// accepting the `ms`, `s`, `m` and `h` units. When it expires the command is aborted
+/// and the invoke is rejected with a `COMMAND_TIMEOUT` error.
+///
 /// # Stability
 /// The output of this macro is managed internally by Tauri,
 /// and should not be accessed directly on normal applications.
@@ -59,6 +68,41 @@ pub fn generate_handler(item: TokenStream) -> TokenStream {
     parse_macro_input!(item as command::Handler).into()
 }
 
+/// Accepts the same list of commands as [`generate_handler!`] and creates a `Vec<tauri::ipc::bindings::CommandBindings>`
+/// describing their arguments and return types, used to generate TypeScript bindings.
+///
+/// # Examples
+/// ```rust,ignore
+/// use tauri_macros::{command, generate_bindings};
+/// #[command]
+/// fn greet(name: String) -> String {
+///   format!("Hello {name}")
+/// }
+/// fn main() {
+///   let _bindings = generate_bindings![greet];
+/// }
+/// ```
+/// # Stability
+/// The output of this macro is managed internally by Tauri,
+/// and should not be accessed directly on normal applications.
+/// It may have breaking changes in the future.
+#[proc_macro]
+pub fn generate_bindings(item: TokenStream) -> TokenStream {
+    parse_macro_input!(item as command::Handler).into_bindings()
+}
+
+/// Implements `tauri::ipc::IntoIpcError` and `serde::Serialize` for an error type,
+/// so it is sent to the webview as a structured `tauri::ipc::IpcError`.
+///
+/// See the `tauri::ipc::IntoIpcError` documentation for the supported attributes.
+#[proc_macro_derive(IpcError, attributes(ipc_error))]
+pub fn ipc_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    ipc_error::derive_ipc_error(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// Reads a Tauri config file and generates a `::tauri::Context` based on the content.
 ///
 /// # Stability
@@ -3,11 +3,14 @@
 
 #[doc(hidden)]
 pub use phf;
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     path::{Component, Path},
 };
 
+pub mod pack;
+
 /// Represent an asset file path in a normalized way.
 ///
 /// The following rules are enforced and added if needed:
@@ -72,6 +75,67 @@ impl<P: AsRef<Path>> From<P> for AssetKey {
     }
 }
 
+/// The encoding of an asset stored compressed, served as is with the `Content-Encoding` header.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetEncoding {
+    /// Brotli compression.
+    Brotli,
+}
+
+impl AssetEncoding {
+    /// The `Content-Encoding` header value of the encoding.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Brotli => "br",
+        }
+    }
+
+    /// Parses the encodings of an `Accept-Encoding` header value, ignoring the unknown ones and the ones with a `q=0` weight.
+    pub fn parse_accepted(header: &str) -> Vec<Self> {
+        header
+            .split(',')
+            .filter_map(|value| {
+                let mut parts = value.split(';').map(str::trim);
+                let encoding = match parts.next()? {
+                    "br" => Self::Brotli,
+                    _ => return None,
+                };
+                let refused = parts.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                (!refused).then_some(encoding)
+            })
+            .collect()
+    }
+
+    /// Decodes up to `len` bytes from the start of the encoded data, e.g. to sniff its mime type.
+    ///
+    /// Returns `None` if the encoding is not supported by the enabled features.
+    pub fn decode_prefix(&self, data: &[u8], len: usize) -> Option<Vec<u8>> {
+        match self {
+            #[cfg(feature = "compression")]
+            Self::Brotli => {
+                use std::io::Read;
+                let mut buf = Vec::with_capacity(len);
+                let _ = brotli::Decompressor::new(data, 4096)
+                    .take(len as u64)
+                    .read_to_end(&mut buf);
+                Some(buf)
+            }
+            #[cfg(not(feature = "compression"))]
+            Self::Brotli => {
+                let _ = (data, len);
+                None
+            }
+        }
+    }
+}
+
 /// A Content-Security-Policy hash value for a specific directive.
 /// For more information see [the MDN page](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy#directives).
 #[non_exhaustive]
@@ -110,6 +174,8 @@ pub struct EmbeddedAssets {
     global_hashes: &'static [CspHash<'static>],
     // Hashes that are associated to the CSP of the HTML file identified by the map key (the HTML asset key).
     html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+    // Hashes of the asset contents, identified by the asset key.
+    content_hashes: Option<phf::Map<&'static str, &'static str>>,
 }
 
 impl EmbeddedAssets {
@@ -123,9 +189,26 @@ impl EmbeddedAssets {
             assets: map,
             global_hashes,
             html_hashes,
+            content_hashes: None,
+        }
+    }
+
+    /// Sets the hashes of the asset contents, identified by the asset key.
+    pub const fn with_content_hashes(
+        self,
+        content_hashes: phf::Map<&'static str, &'static str>,
+    ) -> Self {
+        Self {
+            content_hashes: Some(content_hashes),
+            ..self
         }
     }
 
+    /// The hash of the asset contents, computed at compile time.
+    pub fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+        self.content_hashes.as_ref()?.get(key.as_ref()).copied()
+    }
+
     /// Get an asset by key.
     #[cfg(feature = "compression")]
     pub fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
@@ -150,6 +233,25 @@ impl EmbeddedAssets {
             .map(|a| Cow::Owned(a.to_vec()))
     }
 
+    /// Get an asset by key as it is stored, without decompressing it.
+    ///
+    /// Returns `None` if the asset does not exist or if it is not stored with the given encoding.
+    pub fn get_encoded(
+        &self,
+        key: &AssetKey,
+        encoding: AssetEncoding,
+    ) -> Option<Cow<'static, [u8]>> {
+        match encoding {
+            #[cfg(feature = "compression")]
+            AssetEncoding::Brotli => self.assets.get(key.as_ref()).map(|&a| Cow::Borrowed(a)),
+            #[cfg(not(feature = "compression"))]
+            AssetEncoding::Brotli => {
+                let _ = key;
+                None
+            }
+        }
+    }
+
     /// Iterate on the assets.
     pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
         Box::new(self.assets.into_iter().map(|(k, b)| (*k, *b)))
@@ -171,3 +273,22 @@ impl EmbeddedAssets {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AssetEncoding;
+
+    #[test]
+    fn parses_accepted_encodings() {
+        assert_eq!(
+            AssetEncoding::parse_accepted("gzip, deflate, br"),
+            vec![AssetEncoding::Brotli]
+        );
+        assert_eq!(
+            AssetEncoding::parse_accepted("br;q=0.5, gzip"),
+            vec![AssetEncoding::Brotli]
+        );
+        assert!(AssetEncoding::parse_accepted("br;q=0, gzip").is_empty());
+        assert!(AssetEncoding::parse_accepted("identity").is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+//! An external asset pack, used instead of embedding the frontend assets in the application binary.
+//!
+//! The pack file starts with [`MAGIC`], followed by the length of the index as a little endian `u64`,
+//! the JSON serialized [`AssetPackIndex`] and the contents of the assets.
+//! The SHA-256 hash of the index is pinned in the application binary and the index holds the hash of each asset,
+//! so a tampered pack is detected without reading it whole at startup.
+//!
+//! The pack is memory mapped and each asset is copied out of it and verified on its first read.
+//! The verified copy is kept for the lifetime of the program and served on later reads,
+//! so changes made to the file after that are never served.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs::File,
+    io::Write,
+    path::Path,
+    sync::OnceLock,
+};
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{AssetEncoding, AssetKey, CspHash};
+
+/// The file name of the asset pack, placed at the root of the resource directory.
+pub const ASSET_PACK_FILE_NAME: &str = "assets.pak";
+
+/// The bytes every asset pack starts with.
+pub const MAGIC: &[u8; 8] = b"TAURIPAK";
+
+const HEADER_LEN: usize = MAGIC.len() + 8;
+
+/// The contents of an asset as stored in the pack, with their encoding.
+pub type AssetContents<'a> = (&'a [u8], Option<AssetEncoding>);
+
+/// Errors that can occur while reading or writing an asset pack.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Failed to read or write the pack file.
+    #[error("asset pack I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file is not an asset pack.
+    #[error("the file is not an asset pack")]
+    InvalidFormat,
+
+    /// The index could not be serialized or deserialized.
+    #[error("invalid asset pack index: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The hash of the index or of an asset does not match the expected one.
+    #[error("asset pack integrity check failed for {0}")]
+    Integrity(String),
+}
+
+/// An asset stored in the pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetPackEntry {
+    /// The offset of the asset contents, relative to the end of the index.
+    pub offset: u64,
+    /// The length of the asset contents.
+    pub len: u64,
+    /// The encoding of the asset contents.
+    pub encoding: Option<AssetEncoding>,
+    /// The hex encoded SHA-256 hash of the asset contents.
+    pub sha256: String,
+}
+
+/// The index of an asset pack.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AssetPackIndex {
+    /// The assets, keyed by their [`AssetKey`].
+    pub assets: BTreeMap<String, AssetPackEntry>,
+    /// The `script-src` hashes that must be injected to the CSP of every HTML file.
+    pub script_hashes: Vec<String>,
+    /// The `style-src` hashes that must be injected to the CSP of every HTML file.
+    pub style_hashes: Vec<String>,
+    /// The `script-src` hashes associated to the CSP of the HTML file identified by the map key.
+    pub html_script_hashes: BTreeMap<String, Vec<String>>,
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 * bytes.len());
+    for b in bytes {
+        let _ = write!(hex, "{b:02x}");
+    }
+    hex
+}
+
+/// Builds an asset pack file.
+#[derive(Debug, Default)]
+pub struct AssetPackWriter {
+    index: AssetPackIndex,
+    contents: Vec<u8>,
+}
+
+impl AssetPackWriter {
+    /// Creates an empty asset pack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an asset, with its contents stored with the given encoding.
+    pub fn add(&mut self, key: &AssetKey, contents: &[u8], encoding: Option<AssetEncoding>) {
+        self.index.assets.insert(
+            key.as_ref().to_string(),
+            AssetPackEntry {
+                offset: self.contents.len() as u64,
+                len: contents.len() as u64,
+                encoding,
+                sha256: hex(&sha256(contents)),
+            },
+        );
+        self.contents.extend_from_slice(contents);
+    }
+
+    /// The index of the pack, e.g. to set its CSP hashes.
+    pub fn index_mut(&mut self) -> &mut AssetPackIndex {
+        &mut self.index
+    }
+
+    /// Writes the pack to the given path,
+    /// returning the SHA-256 hash of its index to pin with [`PackedAssets::new`].
+    pub fn write(&self, path: &Path) -> Result<[u8; 32], Error> {
+        let index = serde_json::to_vec(&self.index)?;
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&(index.len() as u64).to_le_bytes())?;
+        file.write_all(&index)?;
+        file.write_all(&self.contents)?;
+        Ok(sha256(&index))
+    }
+}
+
+/// An open asset pack. The file is memory mapped and the asset contents are verified on their first read.
+#[derive(Debug)]
+pub struct AssetPack {
+    mmap: Mmap,
+    index: AssetPackIndex,
+    contents_offset: usize,
+    /// The contents of each asset copied out of the pack on its first read,
+    /// or `None` if their hash does not match the index.
+    verified: HashMap<String, OnceLock<Option<&'static [u8]>>>,
+}
+
+impl AssetPack {
+    /// Opens the pack at the given path, checking its index against the expected SHA-256 hash.
+    pub fn open(path: &Path, index_hash: &[u8; 32]) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        // SAFETY: the pack is a read-only resource of the application, it is not modified while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let header = mmap.get(..HEADER_LEN).ok_or(Error::InvalidFormat)?;
+        let (magic, index_len) = header.split_at(MAGIC.len());
+        if magic != MAGIC {
+            return Err(Error::InvalidFormat);
+        }
+        let index_len = usize::try_from(u64::from_le_bytes(index_len.try_into().unwrap()))
+            .map_err(|_| Error::InvalidFormat)?;
+        let contents_offset = HEADER_LEN
+            .checked_add(index_len)
+            .ok_or(Error::InvalidFormat)?;
+
+        let index = mmap
+            .get(HEADER_LEN..contents_offset)
+            .ok_or(Error::InvalidFormat)?;
+        if sha256(index) != *index_hash {
+            return Err(Error::Integrity("the index".into()));
+        }
+        let index: AssetPackIndex = serde_json::from_slice(index)?;
+        let verified = index
+            .assets
+            .keys()
+            .map(|key| (key.clone(), OnceLock::new()))
+            .collect();
+
+        Ok(Self {
+            mmap,
+            index,
+            contents_offset,
+            verified,
+        })
+    }
+
+    /// The index of the pack.
+    pub fn index(&self) -> &AssetPackIndex {
+        &self.index
+    }
+
+    /// Reads the contents of an asset as stored in the pack, checking its hash on the first read.
+    ///
+    /// The contents are copied out of the pack when they are checked and the copy is kept for the lifetime of the program.
+    pub fn read(&self, key: &str) -> Result<Option<AssetContents<'static>>, Error> {
+        let Some(entry) = self.index.assets.get(key) else {
+            return Ok(None);
+        };
+
+        let verified = match self.verified[key].get() {
+            Some(verified) => *verified,
+            None => {
+                let contents = usize::try_from(entry.offset)
+                    .ok()
+                    .zip(usize::try_from(entry.len).ok())
+                    .and_then(|(offset, len)| {
+                        let start = self.contents_offset.checked_add(offset)?;
+                        self.mmap.get(start..start.checked_add(len)?)
+                    })
+                    .ok_or(Error::InvalidFormat)?
+                    .to_vec();
+                *self.verified[key].get_or_init(|| {
+                    (hex(&sha256(&contents)) == entry.sha256)
+                        .then(|| &*Box::leak(contents.into_boxed_slice()))
+                })
+            }
+        };
+
+        match verified {
+            Some(contents) => Ok(Some((contents, entry.encoding))),
+            None => Err(Error::Integrity(format!("asset `{key}`"))),
+        }
+    }
+}
+
+/// `Assets` implementation backed by an [`AssetPack`] shipped as a resource of the application.
+#[derive(Debug)]
+pub struct PackedAssets {
+    file_name: &'static str,
+    index_hash: [u8; 32],
+    pack: OnceLock<AssetPack>,
+}
+
+impl PackedAssets {
+    /// Creates a new instance for the pack with the given file name and the SHA-256 hash of its index.
+    ///
+    /// The pack must be opened with [`Self::load`] before its assets are available.
+    pub const fn new(file_name: &'static str, index_hash: [u8; 32]) -> Self {
+        Self {
+            file_name,
+            index_hash,
+            pack: OnceLock::new(),
+        }
+    }
+
+    /// Opens the pack in the given directory. Does nothing if it is already open.
+    pub fn load(&self, dir: &Path) -> Result<(), Error> {
+        if self.pack.get().is_none() {
+            let pack = AssetPack::open(&dir.join(self.file_name), &self.index_hash)?;
+            let _ = self.pack.set(pack);
+        }
+        Ok(())
+    }
+
+    fn read(&self, key: &AssetKey) -> Option<AssetContents<'static>> {
+        match self.pack.get()?.read(key.as_ref()) {
+            Ok(asset) => asset,
+            Err(e) => {
+                log::error!("failed to read `{}` from the asset pack: {e}", key.as_ref());
+                None
+            }
+        }
+    }
+
+    /// Get an asset by key.
+    pub fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+        let (contents, encoding) = self.read(key)?;
+        match encoding {
+            None => Some(Cow::Borrowed(contents)),
+            #[cfg(feature = "compression")]
+            Some(AssetEncoding::Brotli) => {
+                let mut contents = contents;
+                let mut buf = Vec::with_capacity(contents.len());
+                brotli::BrotliDecompress(&mut contents, &mut buf)
+                    .ok()
+                    .map(|()| Cow::Owned(buf))
+            }
+            #[cfg(not(feature = "compression"))]
+            Some(encoding) => {
+                log::error!(
+                    "asset `{}` is encoded with {encoding:?}, enable the `compression` feature to decode it",
+                    key.as_ref()
+                );
+                None
+            }
+        }
+    }
+
+    /// Get an asset by key as it is stored, without decoding it.
+    ///
+    /// Returns `None` if the asset does not exist or if it is not stored with the given encoding.
+    pub fn get_encoded(
+        &self,
+        key: &AssetKey,
+        encoding: AssetEncoding,
+    ) -> Option<Cow<'static, [u8]>> {
+        let entry = self.pack.get()?.index().assets.get(key.as_ref())?;
+        if entry.encoding != Some(encoding) {
+            return None;
+        }
+        self.read(key).map(|(contents, _)| Cow::Borrowed(contents))
+    }
+
+    /// The SHA-256 hash of the asset contents as stored in the pack.
+    pub fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+        self.pack
+            .get()?
+            .index()
+            .assets
+            .get(key.as_ref())
+            .map(|entry| entry.sha256.as_str())
+    }
+
+    /// Iterate on the assets as they are stored in the pack, skipping the ones that fail the integrity check.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
+        let Some(pack) = self.pack.get() else {
+            return Box::new(std::iter::empty());
+        };
+        Box::new(pack.index().assets.keys().filter_map(|key| {
+            self.read(&AssetKey::from(key.as_str()))
+                .map(|(contents, _)| (key.as_str(), contents))
+        }))
+    }
+
+    /// CSP hashes for the given asset.
+    pub fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+        let Some(pack) = self.pack.get() else {
+            return Box::new(std::iter::empty());
+        };
+        let index = pack.index();
+        Box::new(
+            index
+                .script_hashes
+                .iter()
+                .map(|hash| CspHash::Script(hash))
+                .chain(index.style_hashes.iter().map(|hash| CspHash::Style(hash)))
+                .chain(
+                    index
+                        .html_script_hashes
+                        .get(html_path.as_ref())
+                        .into_iter()
+                        .flatten()
+                        .map(|hash| CspHash::Script(hash)),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetPackWriter, Error, PackedAssets};
+    use crate::assets::AssetKey;
+
+    #[test]
+    fn reads_and_verifies_pack() {
+        let dir = std::env::temp_dir().join(format!("tauri-asset-pack-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.pak");
+
+        let mut writer = AssetPackWriter::new();
+        writer.add(&AssetKey::from("index.html"), b"<html></html>", None);
+        writer.add(&AssetKey::from("main.js"), b"console.log(1)", None);
+        writer
+            .index_mut()
+            .html_script_hashes
+            .insert("/index.html".into(), vec!["'sha256-abc'".into()]);
+        let hash = writer.write(&path).unwrap();
+
+        let assets = PackedAssets::new("test.pak", hash);
+        assets.load(&dir).unwrap();
+        assert_eq!(
+            assets.get(&AssetKey::from("main.js")).unwrap().as_ref(),
+            b"console.log(1)"
+        );
+        assert!(assets.get(&AssetKey::from("missing.js")).is_none());
+        assert_eq!(assets.iter().count(), 2);
+        assert_eq!(assets.csp_hashes(&AssetKey::from("index.html")).count(), 1);
+
+        // tampering with an asset is detected when it is read
+        let mut contents = std::fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 1;
+        std::fs::write(&path, &contents).unwrap();
+        let tampered_assets = PackedAssets::new("test.pak", hash);
+        tampered_assets.load(&dir).unwrap();
+        assert!(tampered_assets.get(&AssetKey::from("main.js")).is_none());
+        assert!(tampered_assets.get(&AssetKey::from("index.html")).is_some());
+
+        // the verified contents are served even if the file changes later
+        assert_eq!(
+            assets.get(&AssetKey::from("main.js")).unwrap().as_ref(),
+            b"console.log(1)"
+        );
+
+        // a pack with a different index is rejected
+        let assets = PackedAssets::new("test.pak", [0; 32]);
+        assert!(matches!(assets.load(&dir), Err(Error::Integrity(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
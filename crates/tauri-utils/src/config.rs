@@ -1577,7 +1577,7 @@ impl FsScope {
 /// Config for the asset custom protocol.
 ///
 /// See more: <https://tauri.app/v1/api/config#assetprotocolconfig>
-#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct AssetProtocolConfig {
@@ -1587,6 +1587,45 @@ pub struct AssetProtocolConfig {
     /// Enables the asset protocol.
     #[serde(default)]
     pub enable: bool,
+    /// The `Cache-Control` policies of the asset protocol responses, the first one matching the file path is used.
+    ///
+    /// Files without a matching policy are served with `no-cache`,
+    /// so the webview revalidates them with their `ETag` and `Last-Modified` validators.
+    #[serde(default, alias = "cache-policies")]
+    pub cache_policies: Vec<AssetCachePolicy>,
+    /// The maximum number of bytes sent in a single range response, defaults to 1000 KiB.
+    ///
+    /// Larger ranges are truncated, and the webview requests the remaining bytes in following range requests.
+    /// Requests without a range are always answered with the whole file.
+    #[serde(default = "default_max_chunk_size", alias = "max-chunk-size")]
+    pub max_chunk_size: u64,
+}
+
+impl Default for AssetProtocolConfig {
+    fn default() -> Self {
+        Self {
+            scope: Default::default(),
+            enable: false,
+            cache_policies: Vec::new(),
+            max_chunk_size: default_max_chunk_size(),
+        }
+    }
+}
+
+fn default_max_chunk_size() -> u64 {
+    1000 * 1024
+}
+
+/// A `Cache-Control` policy for the asset protocol files matching a glob pattern.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssetCachePolicy {
+    /// The glob pattern matched against the absolute path of the requested file, e.g. `**/*.mp4`.
+    pub path: String,
+    /// The `Cache-Control` header value, e.g. `max-age=3600`.
+    #[serde(alias = "cache-control")]
+    pub cache_control: String,
 }
 
 /// Security configuration.
@@ -1694,6 +1733,9 @@ pub struct AppConfig {
     /// Whether we should inject the Tauri API on `window.__TAURI__` or not.
     #[serde(default, alias = "with-global-tauri")]
     pub with_global_tauri: bool,
+    /// How request paths are resolved to the frontend assets.
+    #[serde(default)]
+    pub routing: RoutingConfig,
 }
 
 impl AppConfig {
@@ -1729,6 +1771,90 @@ impl AppConfig {
     }
 }
 
+/// The routing table used to resolve request paths to the frontend assets,
+/// honored by the `tauri://` protocol and the CLI builtin dev server.
+///
+/// The `source` of each rule is a glob pattern matched against the request path, e.g. `/docs/**`.
+/// A `*` does not match the path separator, use `**` to match nested paths.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RoutingConfig {
+    /// Paths answered with a redirect to another location, the first matching rule is used.
+    #[serde(default)]
+    pub redirects: Vec<RouteRedirect>,
+    /// Paths served with the asset of another path, the first matching rule is used.
+    ///
+    /// Rewrites are applied after the redirects.
+    #[serde(default)]
+    pub rewrites: Vec<RouteRewrite>,
+    /// Serve `index.html` for the paths that do not match any asset, as single-page applications expect.
+    ///
+    /// Disable it to make missing assets, such as a missing JavaScript chunk, fail with a `404 Not Found`.
+    #[serde(default = "default_true", alias = "spa-fallback")]
+    pub spa_fallback: bool,
+    /// The page served with a `404 Not Found` status when a path does not match any asset, e.g. `/404.html`.
+    ///
+    /// Only used when [`Self::spa_fallback`] is disabled.
+    #[serde(alias = "not-found-page")]
+    pub not_found_page: Option<String>,
+    /// Additional response headers, e.g. the `Cross-Origin-Opener-Policy` and `Cross-Origin-Embedder-Policy`
+    /// headers required by `SharedArrayBuffer`.
+    ///
+    /// The headers of every matching rule are added, later rules override the earlier ones.
+    #[serde(default)]
+    pub headers: Vec<RouteHeaders>,
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            redirects: Vec::new(),
+            rewrites: Vec::new(),
+            spa_fallback: true,
+            not_found_page: None,
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// A redirect rule of the [`RoutingConfig`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RouteRedirect {
+    /// The glob pattern matched against the request path.
+    pub source: String,
+    /// The redirect location, either a path such as `/new-page` or an URL.
+    pub destination: String,
+    /// Whether the redirect is permanent (`308`) or temporary (`307`).
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+/// A rewrite rule of the [`RoutingConfig`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RouteRewrite {
+    /// The glob pattern matched against the request path.
+    pub source: String,
+    /// The path of the asset to serve instead, e.g. `/app/index.html`.
+    pub destination: String,
+}
+
+/// A response headers rule of the [`RoutingConfig`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RouteHeaders {
+    /// The glob pattern matched against the request path.
+    pub source: String,
+    /// The header names and values to add to the response.
+    pub headers: HashMap<String, String>,
+}
+
 /// Configuration for application tray icon.
 ///
 /// See more: <https://tauri.app/v1/api/config#trayiconconfig>
@@ -1883,6 +2009,14 @@ pub struct BuildConfig {
     /// and the application will load that URL by default.
     #[serde(alias = "frontend-dist")]
     pub frontend_dist: Option<FrontendDist>,
+    /// Whether the [`frontendDist`](BuildConfig::frontend_dist) assets are written to an external asset pack
+    /// instead of being embedded in the application binary.
+    ///
+    /// The pack is shipped as a bundle resource, so a frontend change does not require recompiling the binary's assets,
+    /// and its hash is pinned in the binary so a tampered pack is rejected at startup.
+    /// Only the Linux bundles (deb, rpm and AppImage) include it for now.
+    #[serde(default, alias = "asset-pack")]
+    pub asset_pack: bool,
     /// A shell command to run before `tauri dev` kicks in.
     ///
     /// The TAURI_ENV_PLATFORM, TAURI_ENV_ARCH, TAURI_ENV_FAMILY, TAURI_ENV_PLATFORM_VERSION, TAURI_ENV_PLATFORM_TYPE and TAURI_ENV_DEBUG environment variables are set if you perform conditional compilation.
@@ -2101,6 +2235,7 @@ fn default_build() -> BuildConfig {
         runner: None,
         dev_url: None,
         frontend_dist: None,
+        asset_pack: false,
         before_dev_command: None,
         before_build_command: None,
         before_bundle_command: None,
@@ -2461,6 +2596,7 @@ mod build {
         fn to_tokens(&self, tokens: &mut TokenStream) {
             let dev_url = opt_lit(self.dev_url.as_ref().map(url_lit).as_ref());
             let frontend_dist = opt_lit(self.frontend_dist.as_ref());
+            let asset_pack = self.asset_pack;
             let runner = quote!(None);
             let before_dev_command = quote!(None);
             let before_build_command = quote!(None);
@@ -2473,6 +2609,7 @@ mod build {
                 runner,
                 dev_url,
                 frontend_dist,
+                asset_pack,
                 before_dev_command,
                 before_build_command,
                 before_bundle_command,
@@ -2620,7 +2757,23 @@ mod build {
     impl ToTokens for AssetProtocolConfig {
         fn to_tokens(&self, tokens: &mut TokenStream) {
             let scope = &self.scope;
-            tokens.append_all(quote! { ::tauri::utils::config::AssetProtocolConfig { scope: #scope, ..Default::default() } })
+            let cache_policies = vec_lit(&self.cache_policies, identity);
+            let max_chunk_size = self.max_chunk_size;
+            tokens.append_all(quote! { ::tauri::utils::config::AssetProtocolConfig { scope: #scope, cache_policies: #cache_policies, max_chunk_size: #max_chunk_size, ..Default::default() } })
+        }
+    }
+
+    impl ToTokens for AssetCachePolicy {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let path = str_lit(&self.path);
+            let cache_control = str_lit(&self.cache_control);
+
+            literal_struct!(
+                tokens,
+                ::tauri::utils::config::AssetCachePolicy,
+                path,
+                cache_control
+            );
         }
     }
 
@@ -2631,6 +2784,7 @@ mod build {
             let tray_icon = opt_lit(self.tray_icon.as_ref());
             let macos_private_api = self.macos_private_api;
             let with_global_tauri = self.with_global_tauri;
+            let routing = &self.routing;
 
             literal_struct!(
                 tokens,
@@ -2639,7 +2793,77 @@ mod build {
                 security,
                 tray_icon,
                 macos_private_api,
-                with_global_tauri
+                with_global_tauri,
+                routing
+            );
+        }
+    }
+
+    impl ToTokens for RoutingConfig {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let redirects = vec_lit(&self.redirects, identity);
+            let rewrites = vec_lit(&self.rewrites, identity);
+            let spa_fallback = self.spa_fallback;
+            let not_found_page = opt_str_lit(self.not_found_page.as_ref());
+            let headers = vec_lit(&self.headers, identity);
+
+            literal_struct!(
+                tokens,
+                ::tauri::utils::config::RoutingConfig,
+                redirects,
+                rewrites,
+                spa_fallback,
+                not_found_page,
+                headers
+            );
+        }
+    }
+
+    impl ToTokens for RouteRedirect {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let source = str_lit(&self.source);
+            let destination = str_lit(&self.destination);
+            let permanent = self.permanent;
+
+            literal_struct!(
+                tokens,
+                ::tauri::utils::config::RouteRedirect,
+                source,
+                destination,
+                permanent
+            );
+        }
+    }
+
+    impl ToTokens for RouteRewrite {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let source = str_lit(&self.source);
+            let destination = str_lit(&self.destination);
+
+            literal_struct!(
+                tokens,
+                ::tauri::utils::config::RouteRewrite,
+                source,
+                destination
+            );
+        }
+    }
+
+    impl ToTokens for RouteHeaders {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let source = str_lit(&self.source);
+            let headers = map_lit(
+                quote! { ::std::collections::HashMap },
+                &self.headers,
+                str_lit,
+                str_lit,
+            );
+
+            literal_struct!(
+                tokens,
+                ::tauri::utils::config::RouteHeaders,
+                source,
+                headers
             );
         }
     }
@@ -2716,6 +2940,7 @@ mod test {
             tray_icon: None,
             macos_private_api: false,
             with_global_tauri: false,
+            routing: Default::default(),
         };
 
         // create a build config
@@ -2723,6 +2948,7 @@ mod test {
             runner: None,
             dev_url: None,
             frontend_dist: None,
+            asset_pack: false,
             before_dev_command: None,
             before_build_command: None,
             before_bundle_command: None,
@@ -27,6 +27,7 @@ pub mod plugin;
 /// Prepare application resources and sidecars.
 #[cfg(feature = "resources")]
 pub mod resources;
+pub mod routing;
 #[cfg(feature = "build")]
 pub mod tokens;
 
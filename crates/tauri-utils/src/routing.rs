@@ -0,0 +1,260 @@
+//! The runtime matcher of the [`RoutingConfig`], shared by the `tauri://` protocol and the CLI builtin dev server.
+
+use crate::config::{RouteRedirect, RoutingConfig};
+
+/// The outcome of resolving a request path with the [`Routes`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Route<T> {
+    /// The asset that matches the path, after the rewrites and fallbacks.
+    Asset {
+        /// The asset key, without the leading `/`.
+        key: String,
+        /// The asset returned by the lookup function.
+        asset: T,
+    },
+    /// No asset matches the path and the not found page is served.
+    NotFoundPage {
+        /// The not found page key, without the leading `/`.
+        key: String,
+        /// The asset returned by the lookup function.
+        asset: T,
+    },
+    /// The path must be redirected.
+    Redirect {
+        /// The redirect location.
+        location: String,
+        /// Whether the redirect is permanent.
+        permanent: bool,
+    },
+    /// No asset matches the path.
+    NotFound,
+}
+
+/// The [`RoutingConfig`] with its glob patterns compiled, see [`Routes::new`].
+#[derive(Debug, Clone)]
+pub struct Routes {
+    redirects: Vec<(glob::Pattern, RouteRedirect)>,
+    rewrites: Vec<(glob::Pattern, String)>,
+    spa_fallback: bool,
+    not_found_page: Option<String>,
+    headers: Vec<(glob::Pattern, http::HeaderMap)>,
+}
+
+impl Routes {
+    /// Compiles the glob patterns and validates the headers of the routing table.
+    ///
+    /// Rules with an invalid `source` pattern and headers with an invalid name or value are logged and skipped.
+    pub fn new(config: &RoutingConfig) -> Self {
+        fn compile_rules<'a, T: 'a>(
+            rules: impl IntoIterator<Item = (&'a String, T)>,
+        ) -> Vec<(glob::Pattern, T)> {
+            rules
+                .into_iter()
+                .filter_map(|(source, rule)| match glob::Pattern::new(source) {
+                    Ok(pattern) => Some((pattern, rule)),
+                    Err(e) => {
+                        log::error!("Ignoring invalid route source `{source}`: {e}");
+                        None
+                    }
+                })
+                .collect()
+        }
+
+        let headers = config.headers.iter().map(|rule| {
+            let mut headers = http::HeaderMap::new();
+            for (name, value) in &rule.headers {
+                match (
+                    http::HeaderName::try_from(name),
+                    http::HeaderValue::try_from(value),
+                ) {
+                    (Ok(name), Ok(value)) => {
+                        headers.insert(name, value);
+                    }
+                    _ => log::error!("Ignoring invalid routing header `{name}: {value}`"),
+                }
+            }
+            (&rule.source, headers)
+        });
+
+        Self {
+            redirects: compile_rules(
+                config
+                    .redirects
+                    .iter()
+                    .map(|redirect| (&redirect.source, redirect.clone())),
+            ),
+            rewrites: compile_rules(
+                config
+                    .rewrites
+                    .iter()
+                    .map(|rewrite| (&rewrite.source, rewrite.destination.clone())),
+            ),
+            spa_fallback: config.spa_fallback,
+            not_found_page: config.not_found_page.clone(),
+            headers: compile_rules(headers),
+        }
+    }
+
+    /// Resolves the given request path, looking the candidate asset keys up with `get`.
+    ///
+    /// Each path is tried as is, then with the `.html` extension and as a directory with an `index.html` file.
+    pub fn resolve<T>(&self, path: &str, mut get: impl FnMut(&str) -> Option<T>) -> Route<T> {
+        let path = format!("/{}", path.trim_start_matches('/'));
+
+        if let Some((_, redirect)) = self
+            .redirects
+            .iter()
+            .find(|(pattern, _)| route_matches(pattern, &path))
+        {
+            return Route::Redirect {
+                location: redirect.destination.clone(),
+                permanent: redirect.permanent,
+            };
+        }
+
+        let path = self
+            .rewrites
+            .iter()
+            .find(|(pattern, _)| route_matches(pattern, &path))
+            .map_or(path.as_str(), |(_, destination)| destination.as_str());
+
+        let mut lookup = |path: &str| {
+            let path = path.trim_matches('/');
+            let candidates = if path.is_empty() {
+                vec!["index.html".to_string()]
+            } else {
+                vec![
+                    path.to_string(),
+                    format!("{path}.html"),
+                    format!("{path}/index.html"),
+                ]
+            };
+            candidates
+                .into_iter()
+                .find_map(|key| get(&key).map(|asset| (key, asset)))
+        };
+
+        if let Some((key, asset)) = lookup(path) {
+            return Route::Asset { key, asset };
+        }
+        if self.spa_fallback {
+            if let Some((key, asset)) = lookup("") {
+                return Route::Asset { key, asset };
+            }
+        } else if let Some((key, asset)) = self.not_found_page.as_deref().and_then(&mut lookup) {
+            return Route::NotFoundPage { key, asset };
+        }
+        Route::NotFound
+    }
+
+    /// The additional response headers of the given request path.
+    pub fn headers(&self, path: &str) -> http::HeaderMap {
+        let path = format!("/{}", path.trim_start_matches('/'));
+        let mut headers = http::HeaderMap::new();
+        for (_, rule_headers) in self
+            .headers
+            .iter()
+            .filter(|(pattern, _)| route_matches(pattern, &path))
+        {
+            for (name, value) in rule_headers {
+                headers.insert(name, value.clone());
+            }
+        }
+        headers
+    }
+}
+
+fn route_matches(pattern: &glob::Pattern, path: &str) -> bool {
+    pattern.matches_with(
+        path,
+        glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Route, Routes};
+    use crate::config::RoutingConfig;
+
+    #[test]
+    fn resolves_routes() {
+        let assets = [
+            "index.html",
+            "about.html",
+            "docs/index.html",
+            "404.html",
+            "app.js",
+        ];
+        let get = |key: &str| assets.contains(&key).then_some(());
+        let asset = |key: &str| Route::Asset {
+            key: key.into(),
+            asset: (),
+        };
+
+        let mut routing: RoutingConfig = serde_json::from_value(serde_json::json!({
+            "redirects": [
+                { "source": "/[", "destination": "/invalid" },
+                { "source": "/old/**", "destination": "/docs", "permanent": true }
+            ],
+            "rewrites": [{ "source": "/app/*", "destination": "/index.html" }],
+            "headers": [
+                { "source": "/**", "headers": { "Cross-Origin-Opener-Policy": "same-origin" } },
+                { "source": "/*.js", "headers": { "Cache-Control": "no-store", "Invalid Name": "value" } }
+            ]
+        }))
+        .unwrap();
+
+        let routes = Routes::new(&routing);
+        assert_eq!(routes.resolve("/", get), asset("index.html"));
+        assert_eq!(routes.resolve("/app.js", get), asset("app.js"));
+        assert_eq!(routes.resolve("/about", get), asset("about.html"));
+        assert_eq!(routes.resolve("/docs/", get), asset("docs/index.html"));
+        assert_eq!(routes.resolve("/app/settings", get), asset("index.html"));
+        assert_eq!(
+            routes.resolve("/old/page", get),
+            Route::Redirect {
+                location: "/docs".into(),
+                permanent: true
+            }
+        );
+        assert_eq!(routes.resolve("/missing.js", get), asset("index.html"));
+
+        let headers = |path: &str| {
+            routes
+                .headers(path)
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        assert_eq!(
+            headers("/app.js"),
+            HashMap::from([
+                ("cross-origin-opener-policy".into(), "same-origin".into()),
+                ("cache-control".into(), "no-store".into())
+            ])
+        );
+        assert_eq!(
+            headers("/docs/app.js"),
+            HashMap::from([("cross-origin-opener-policy".into(), "same-origin".into())])
+        );
+
+        routing.spa_fallback = false;
+        let routes = Routes::new(&routing);
+        assert_eq!(routes.resolve("/missing.js", get), Route::NotFound);
+        assert_eq!(routes.resolve("/app/settings", get), asset("index.html"));
+
+        routing.not_found_page = Some("/404.html".into());
+        assert_eq!(
+            Routes::new(&routing).resolve("/missing.js", get),
+            Route::NotFoundPage {
+                key: "404.html".into(),
+                asset: ()
+            }
+        );
+    }
+}
@@ -34,6 +34,7 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
             ("unlisten", true),
             ("emit", true),
             ("emit_to", true),
+            ("reply", false),
         ],
     ),
     (
@@ -144,7 +145,7 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
             ("size", true),
         ],
     ),
-    ("resources", &[("close", true)]),
+    ("resources", &[("close", true), ("list", false)]),
     (
         "menu",
         &[
@@ -35,6 +35,45 @@
         },
     });
 
+    const STREAM_MARKER = '__TAURI_STREAM__';
+
+    // turns the response of a command that returned a stream into an async iterator
+    function streamIterator(id) {
+        let done = false;
+        return {
+            [Symbol.asyncIterator]() {
+                return this;
+            },
+            next() {
+                if (done) {
+                    return Promise.resolve({ done: true, value: undefined });
+                }
+                return window.__TAURI_INTERNALS__
+                    .invoke(`plugin:${STREAM_MARKER}|next`, { id })
+                    .then(
+                        (item) => {
+                            done = item.done;
+                            return { done: item.done, value: item.value };
+                        },
+                        (e) => {
+                            done = true;
+                            throw e;
+                        },
+                    );
+            },
+            return(value) {
+                if (!done) {
+                    done = true;
+                    // drops the stream on the Rust side
+                    window.__TAURI_INTERNALS__
+                        .invoke(`plugin:${STREAM_MARKER}|close`, { id })
+                        .catch(console.error);
+                }
+                return Promise.resolve({ done: true, value });
+            },
+        };
+    }
+
     const ipcQueue = [];
     let isWaitingForIpc = false;
 
@@ -48,25 +87,96 @@
         }
     }
 
+    // decodes a response of a codec, which is an ArrayBuffer or an array of bytes on the postMessage interface
+    function decodeResponse(codec, r) {
+        if (!codec) {
+            return r;
+        }
+        if (r instanceof ArrayBuffer) {
+            return codec.decode(new Uint8Array(r));
+        }
+        if (ArrayBuffer.isView(r)) {
+            return codec.decode(new Uint8Array(r.buffer, r.byteOffset, r.byteLength));
+        }
+        if (Array.isArray(r)) {
+            return codec.decode(Uint8Array.from(r));
+        }
+        // the errors of the IPC itself are always JSON
+        return r;
+    }
+
     Object.defineProperty(window.__TAURI_INTERNALS__, 'invoke', {
         value: function (cmd, payload = {}, options) {
+            // the signal and the codec cannot be serialized, so they are not sent to the backend
+            const { signal, codec, ...ipcOptions } = options || {};
+            if (ipcOptions.headers instanceof Headers) {
+                ipcOptions.headers = Object.fromEntries(ipcOptions.headers.entries());
+            }
+            if (codec) {
+                payload = codec.encode(payload);
+                ipcOptions.headers = {
+                    ...ipcOptions.headers,
+                    'Content-Type': codec.mimeType,
+                    Accept: codec.mimeType,
+                };
+            }
+
             return new Promise(function (resolve, reject) {
                 const callback = window.__TAURI_INTERNALS__.transformCallback(function (r) {
-                    resolve(r);
+                    try {
+                        r = decodeResponse(codec, r);
+                    } catch (e) {
+                        reject(e);
+                        delete window[`_${error}`];
+                        return;
+                    }
+                    if (r && typeof r === 'object' && typeof r[STREAM_MARKER] === 'number') {
+                        resolve(streamIterator(r[STREAM_MARKER]));
+                    } else {
+                        resolve(r);
+                    }
                     delete window[`_${error}`];
                 }, true);
                 const error = window.__TAURI_INTERNALS__.transformCallback(function (e) {
-                    reject(e);
+                    try {
+                        reject(decodeResponse(codec, e));
+                    } catch (decodeError) {
+                        reject(decodeError);
+                    }
                     delete window[`_${callback}`];
                 }, true);
 
+                if (signal) {
+                    if (signal.aborted) {
+                        delete window[`_${callback}`];
+                        delete window[`_${error}`];
+                        reject(signal.reason);
+                        return;
+                    }
+                    signal.addEventListener(
+                        'abort',
+                        () => {
+                            // no-op if the invoke already resolved
+                            if (window[`_${callback}`]) {
+                                delete window[`_${callback}`];
+                                delete window[`_${error}`];
+                                window.__TAURI_INTERNALS__
+                                    .invoke('plugin:__TAURI_IPC__|cancel', { id: callback })
+                                    .catch(console.error);
+                                reject(signal.reason);
+                            }
+                        },
+                        { once: true },
+                    );
+                }
+
                 const action = () => {
                     window.__TAURI_INTERNALS__.ipc({
                         cmd,
                         callback,
                         error,
                         payload,
-                        options,
+                        options: ipcOptions.headers ? ipcOptions : undefined,
                     });
                 };
                 if ('ipc' in window.__TAURI_INTERNALS__) {
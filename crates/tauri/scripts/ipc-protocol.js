@@ -54,12 +54,21 @@
                 });
         } else {
             // otherwise use the postMessage interface
+            // the message is serialized as JSON, so binary payloads are sent as arrays of bytes
+            let ipcPayload = payload;
+            if (payload instanceof ArrayBuffer) {
+                ipcPayload = Array.from(new Uint8Array(payload));
+            } else if (ArrayBuffer.isView(payload)) {
+                ipcPayload = Array.from(
+                    new Uint8Array(payload.buffer, payload.byteOffset, payload.byteLength),
+                );
+            }
             const { data } = processIpcMessage({
                 cmd,
                 callback,
                 error,
                 options,
-                payload,
+                payload: ipcPayload,
             });
             window.ipc.postMessage(data);
         }
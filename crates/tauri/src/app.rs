@@ -2,7 +2,7 @@ use crate::{
     image::Image,
     ipc::{
         channel::ChannelDataIpcQueue, CallbackFn, CommandArg, CommandItem, Invoke, InvokeError,
-        InvokeHandler, InvokeResponder, InvokeResponse,
+        InvokeHandler, InvokeResponder, InvokeResponse, IpcMiddleware,
     },
     manager::{
         webview::{UriSchemeProtocol, WebviewLabelDef},
@@ -42,7 +42,9 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     fmt,
+    future::Future,
     sync::{mpsc::Sender, Arc, MutexGuard},
+    time::Duration,
 };
 
 use crate::{event::EventId, runtime::RuntimeHandle, Event, EventTarget};
@@ -257,7 +259,10 @@ pub struct AssetResolver<R: Runtime> {
 impl<R: Runtime> AssetResolver<R> {
     /// Gets the app asset associated with the given path.
     pub fn get(&self, path: String) -> Option<Asset> {
-        self.manager.get_asset(path).ok()
+        self.manager
+            .get_asset(path)
+            .ok()
+            .filter(|asset| asset.status.is_success())
     }
 
     /// Iterate on all assets.
@@ -445,6 +450,8 @@ pub struct App<R: Runtime> {
     manager: Arc<AppManager<R>>,
     handle: AppHandle<R>,
     ran_setup: bool,
+    #[cfg(all(dev, desktop))]
+    capability_watcher: Option<crate::ipc::authority::CapabilityWatcher>,
 }
 
 impl<R: Runtime> fmt::Debug for App<R> {
@@ -999,6 +1006,15 @@ pub struct Builder<R: Runtime> {
     /// The JS message responder.
     invoke_responder: Option<Arc<InvokeResponder<R>>>,
 
+    /// The IPC middlewares.
+    ipc_middlewares: Vec<Arc<dyn IpcMiddleware<R>>>,
+
+    /// The default timeout of async commands.
+    invoke_timeout: Option<Duration>,
+
+    /// The maximum number of retained events.
+    retained_events_capacity: usize,
+
     /// The script that initializes the `window.__TAURI_INTERNALS__.postMessage` function.
     invoke_initialization_script: String,
 
@@ -1017,6 +1033,9 @@ pub struct Builder<R: Runtime> {
     /// App state.
     state: StateManager,
 
+    /// The initializers of the state managed with [`Self::manage_async`] and [`Self::manage_lazy`].
+    state_initializers: Vec<Box<dyn FnOnce(&AppHandle<R>) + Send>>,
+
     /// A closure that returns the menu set to all windows.
     #[cfg(desktop)]
     menu: Option<Box<dyn FnOnce(&AppHandle<R>) -> crate::Result<Menu<R>> + Send>>,
@@ -1072,6 +1091,9 @@ impl<R: Runtime> Builder<R> {
             setup: Box::new(|_| Ok(())),
             invoke_handler: Box::new(|_| false),
             invoke_responder: None,
+            ipc_middlewares: Vec::new(),
+            invoke_timeout: None,
+            retained_events_capacity: crate::event::DEFAULT_RETAINED_EVENTS_CAPACITY,
             invoke_initialization_script: InvokeInitializationScript {
                 process_ipc_message_fn: crate::manager::webview::PROCESS_IPC_MESSAGE_FN,
                 os_name: std::env::consts::OS,
@@ -1085,6 +1107,7 @@ impl<R: Runtime> Builder<R> {
             plugins: PluginStore::default(),
             uri_scheme_protocols: Default::default(),
             state: StateManager::new(),
+            state_initializers: Vec::new(),
             #[cfg(desktop)]
             menu: None,
             enable_macos_default_menu: true,
@@ -1148,6 +1171,48 @@ impl<R: Runtime> Builder<R> {
         self
     }
 
+    /// Adds a middleware that runs around every IPC invoke.
+    ///
+    /// Middlewares run in the order they are added, before the ones registered by plugins.
+    /// See [`IpcMiddleware`] for more information.
+    #[must_use]
+    pub fn ipc_middleware<M: IpcMiddleware<R>>(mut self, middleware: M) -> Self {
+        self.ipc_middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Sets the default timeout of async commands.
+    ///
+    /// When a command does not finish in time its task is aborted and the invoke is rejected with [`InvokeError::timeout`].
+    /// Commands can override it with the `timeout` attribute, e.g. `#[tauri::command(timeout = "30s")]`.
+    /// It does not apply to the internal commands of the IPC, such as fetching the next item of a command stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// tauri::Builder::default()
+    ///   .invoke_timeout(std::time::Duration::from_secs(60));
+    /// ```
+    #[must_use]
+    pub fn invoke_timeout(mut self, timeout: Duration) -> Self {
+        self.invoke_timeout.replace(timeout);
+        self
+    }
+
+    /// Sets the maximum number of events retained by [`Manager::emit_retained_to`]. Defaults to 64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// tauri::Builder::default()
+    ///   .retained_events_capacity(16);
+    /// ```
+    #[must_use]
+    pub fn retained_events_capacity(mut self, capacity: usize) -> Self {
+        self.retained_events_capacity = capacity;
+        self
+    }
+
     /// Defines the setup hook.
     ///
     /// # Examples
@@ -1322,6 +1387,133 @@ tauri::Builder::default()
         self
     }
 
+    /// Adds a state initialized asynchronously when the app is built.
+    ///
+    /// The commands that access the state before the initialization finishes wait for it,
+    /// and fail with [`crate::ipc::codes::STATE_INIT_FAILED`] if it returned an error or panicked.
+    /// Synchronous commands and [`Manager::state`] block on it, async commands wait asynchronously,
+    /// and [`Manager::try_state`] returns `None` without waiting until the state is ready.
+    ///
+    /// If the initialization failed, the state can be set with [`Manager::manage`] or [`Manager::replace_state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if state of type `T` is already being managed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tauri::Manager;
+    ///
+    /// struct Database {
+    ///   url: String,
+    /// }
+    ///
+    /// async fn connect(url: &str) -> Result<Database, std::io::Error> {
+    ///   Ok(Database { url: url.into() })
+    /// }
+    ///
+    /// #[tauri::command]
+    /// async fn database_url(db: tauri::State<'_, Database>) -> Result<String, String> {
+    ///   Ok(db.url.clone())
+    /// }
+    ///
+    /// tauri::Builder::default()
+    ///   .manage_async(|app| async move {
+    ///     let dir = app.path().app_data_dir().expect("failed to resolve the data directory");
+    ///     connect(&format!("sqlite://{}", dir.join("app.db").display())).await
+    ///   })
+    ///   .invoke_handler(tauri::generate_handler![database_url]);
+    /// ```
+    #[must_use]
+    pub fn manage_async<T, F, Fut, E>(mut self, init: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(AppHandle<R>) -> Fut + Send + 'static,
+        Fut: Future<Output = std::result::Result<T, E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        let type_name = std::any::type_name::<T>();
+        assert!(
+            self.state.set_pending::<T>(),
+            "state for type '{type_name}' is already being managed",
+        );
+        self.state_initializers.push(Box::new(move |app| {
+            let app = app.clone();
+            crate::async_runtime::spawn(async move {
+                // a separate task so a panic fails the initialization instead of leaving it pending
+                let init = init(app.clone());
+                let result = match crate::async_runtime::spawn(async move {
+                    init.await.map_err(|e| e.to_string())
+                })
+                .await
+                {
+                    Ok(result) => result,
+                    Err(crate::Error::JoinError(e)) if e.is_panic() => {
+                        Err(crate::state::panic_message(&*e.into_panic()))
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                app.manager.state.finish(result);
+            });
+        }));
+        self
+    }
+
+    /// Adds a state initialized on its first access.
+    ///
+    /// The initialization runs on the blocking thread pool.
+    /// Like with [`Self::manage_async`], the commands and [`Manager::state`] wait for it to finish,
+    /// while [`Manager::try_state`] starts it and returns `None` until the state is ready.
+    /// If the initialization fails or panics, it is called again on the next access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if state of type `T` is already being managed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tauri::Manager;
+    ///
+    /// struct Keystore(Vec<u8>);
+    ///
+    /// #[tauri::command]
+    /// async fn key_count(keystore: tauri::State<'_, Keystore>) -> Result<usize, String> {
+    ///   Ok(keystore.0.len())
+    /// }
+    ///
+    /// tauri::Builder::default()
+    ///   .manage_lazy(|app| {
+    ///     let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    ///     std::fs::read(dir.join("keystore")).map(Keystore).map_err(|e| e.to_string())
+    ///   })
+    ///   .invoke_handler(tauri::generate_handler![key_count]);
+    /// ```
+    #[must_use]
+    pub fn manage_lazy<T, F, E>(mut self, init: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&AppHandle<R>) -> std::result::Result<T, E> + Send + Sync + 'static,
+        E: fmt::Display,
+    {
+        let type_name = std::any::type_name::<T>();
+        assert!(
+            self.state.set_pending::<T>(),
+            "state for type '{type_name}' is already being managed",
+        );
+        self.state_initializers.push(Box::new(move |app| {
+            let app_ = app.clone();
+            app.manager.state.set_lazy::<T, _>(move || {
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| init(&app_)))
+                    .map_err(|panic| crate::state::panic_message(&*panic))
+                    .and_then(|result| result.map_err(|e| e.to_string()));
+                app_.manager.state.finish(result);
+            });
+        }));
+        self
+    }
+
     /// Sets the menu to use on all windows.
     ///
     /// # Examples
@@ -1550,6 +1742,8 @@ tauri::Builder::default()
             context,
             self.plugins,
             self.invoke_handler,
+            self.ipc_middlewares,
+            self.invoke_timeout,
             self.on_page_load,
             self.uri_scheme_protocols,
             self.state,
@@ -1559,6 +1753,9 @@ tauri::Builder::default()
             HashMap::new(),
             (self.invoke_responder, self.invoke_initialization_script),
         ));
+        manager
+            .listeners()
+            .set_retained_capacity(self.retained_events_capacity);
 
         let runtime_args = RuntimeInitArgs {
             #[cfg(windows)]
@@ -1630,6 +1827,8 @@ tauri::Builder::default()
                 manager,
             },
             ran_setup: false,
+            #[cfg(all(dev, desktop))]
+            capability_watcher: None,
         };
 
         #[cfg(desktop)]
@@ -1662,6 +1861,16 @@ tauri::Builder::default()
         app.manage(ChannelDataIpcQueue::default());
         app.handle.plugin(crate::ipc::channel::plugin())?;
 
+        app.manage(crate::ipc::stream::IpcStreams::default());
+        app.handle.plugin(crate::ipc::stream::plugin())?;
+
+        app.manage(crate::ipc::cancel::PendingInvokes::default());
+        app.handle.plugin(crate::ipc::cancel::plugin())?;
+
+        for init in self.state_initializers {
+            init(&app.handle);
+        }
+
         #[cfg(windows)]
         {
             if let crate::utils::config::WebviewInstallMode::FixedRuntime { path } =
@@ -1770,6 +1979,11 @@ impl<R: Runtime> HasDisplayHandle for App<R> {
 fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
     app.ran_setup = true;
 
+    #[cfg(all(dev, desktop))]
+    {
+        app.capability_watcher = crate::ipc::authority::watch_capabilities(app.handle.clone());
+    }
+
     let window_labels = app
         .config()
         .app
@@ -1785,13 +1999,14 @@ fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
         })
         .collect::<Vec<_>>();
 
+    // the assets must be ready before the windows load them
+    app.manager.assets.setup(app);
+
     for window_config in app.config().app.windows.clone() {
         WebviewWindowBuilder::from_config(app.handle(), &window_config)?
             .build_internal(&window_labels, &webview_labels)?;
     }
 
-    app.manager.assets.setup(app);
-
     if let Some(setup) = app.setup.take() {
         (setup)(app).map_err(|e| crate::Error::Setup(e.into()))?;
     }
@@ -1914,6 +2129,60 @@ fn on_event_loop_event<R: Runtime>(
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        state::StateError,
+        test::{mock_builder, mock_context, noop_assets},
+        Manager,
+    };
+
+    #[test]
+    fn state_waits_for_initialization() {
+        struct Database(&'static str);
+        struct Keystore(&'static str);
+
+        let app = mock_builder()
+            .manage_async(|_| async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok::<_, String>(Database("async"))
+            })
+            .manage_lazy(|_| Ok::<_, String>(Keystore("lazy")))
+            .build(mock_context(noop_assets()))
+            .unwrap();
+
+        assert_eq!(app.state::<Database>().0, "async");
+        // the first access of a lazy state waits for it
+        assert_eq!(app.state::<Keystore>().0, "lazy");
+    }
+
+    #[test]
+    fn panicking_initialization_fails() {
+        struct Database;
+        struct Keystore;
+
+        let app = mock_builder()
+            .manage_async(|_| async {
+                if true {
+                    panic!("connection lost");
+                }
+                Ok::<_, String>(Database)
+            })
+            .manage_lazy(|_| -> Result<Keystore, String> { panic!("keystore locked") })
+            .build(mock_context(noop_assets()))
+            .unwrap();
+
+        let state = app.handle().manager.state();
+        assert!(matches!(
+            state.wait::<Database>(),
+            Err(StateError::InitFailed(error)) if error == "the initialization panicked: connection lost"
+        ));
+        assert!(matches!(
+            state.wait::<Keystore>(),
+            Err(StateError::InitFailed(error)) if error == "the initialization panicked: keystore locked"
+        ));
+    }
+
     #[test]
     fn is_send_sync() {
         crate::test_utils::assert_send::<super::AppHandle>();
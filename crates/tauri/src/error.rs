@@ -91,6 +91,12 @@ pub enum Error {
     /// Failed to receive message .
     #[error("failed to receive message")]
     FailedToReceiveMessage,
+    /// The IPC channel was closed.
+    #[error("the channel is closed")]
+    ChannelClosed,
+    /// The bounded IPC channel has no free capacity.
+    #[error("the channel is full")]
+    ChannelFull,
     /// Menu error.
     #[error("menu error: {0}")]
     #[cfg(desktop)]
@@ -135,6 +141,15 @@ pub enum Error {
     /// The resource id is invalid.
     #[error("The resource id {0} is invalid.")]
     BadResourceId(crate::resources::ResourceId),
+    /// The resource table is full, see [`crate::ResourceTable::set_limits`].
+    #[error("the resource table {0} limit of {1} was exceeded")]
+    ResourceLimitExceeded(&'static str, usize),
+    /// Failed to resolve the capabilities.
+    #[error(transparent)]
+    Acl(#[from] tauri_utils::acl::Error),
+    /// The capability was not added at runtime.
+    #[error("capability `{0}` was not added at runtime")]
+    CapabilityNotFound(String),
     /// The anyhow crate error.
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
@@ -151,6 +166,18 @@ pub enum Error {
     /// Failed to get a raw handle.
     #[error(transparent)]
     RawHandleError(#[from] raw_window_handle::HandleError),
+    /// No listener of the target handles the requested event.
+    #[error("no listener for event `{0}`")]
+    NoEventListener(String),
+    /// The event request was not answered in time.
+    #[error("request event `{0}` timed out")]
+    EventRequestTimeout(String),
+    /// Replied to an event that was not emitted with [`crate::Manager::request`].
+    #[error("the event is not a request")]
+    NotAnEventRequest,
+    /// Failed to encode an IPC body with the negotiated codec.
+    #[error("failed to encode the IPC body as {0}: {1}")]
+    IpcEncode(&'static str, String),
 }
 
 /// `Result<T, ::tauri::Error>`
@@ -1,11 +1,14 @@
 use crate::{Runtime, Webview};
 
-use super::{EmitArgs, Event, EventId, EventTarget};
+use super::{event_matches, EmitArgs, Event, EventId, EventRequestId, EventTarget};
+
+use serde_json::Value as JsonValue;
+use tokio::sync::oneshot;
 
 use std::{
     boxed::Box,
     cell::Cell,
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc, Mutex,
@@ -21,6 +24,10 @@ enum Pending {
         handler: Handler,
     },
     Emit(EmitArgs),
+    Replay {
+        id: EventId,
+        emit_args: EmitArgs,
+    },
 }
 
 /// Stored in [`Listeners`] to be called upon, when the event that stored it, is triggered.
@@ -53,11 +60,74 @@ impl JsHandler {
 type WebviewLabel = String;
 type EventName = String;
 
+/// The default maximum number of retained events.
+pub(crate) const DEFAULT_RETAINED_EVENTS_CAPACITY: usize = 64;
+
+/// The last payload emitted with [`crate::Manager::emit_retained_to`] for an event name and target.
+struct RetainedEvent {
+    target: EventTarget,
+    emit_args: EmitArgs,
+}
+
+/// The retained events, from the least to the most recently emitted.
+struct RetainedEvents {
+    events: VecDeque<RetainedEvent>,
+    capacity: usize,
+}
+
+impl Default for RetainedEvents {
+    fn default() -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: DEFAULT_RETAINED_EVENTS_CAPACITY,
+        }
+    }
+}
+
+impl RetainedEvents {
+    fn evict(&mut self) {
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+    }
+}
+
+/// A request waiting for its first reply.
+struct PendingRequest {
+    /// The labels of the webviews the request was sent to, the only ones allowed to reply.
+    webviews: HashSet<String>,
+    sender: oneshot::Sender<JsonValue>,
+}
+
+/// Removes a pending request when the future waiting for its reply finishes or is dropped.
+pub(crate) struct RequestGuard {
+    listeners: Listeners,
+    request_id: EventRequestId,
+}
+
+impl RequestGuard {
+    pub(crate) fn new(listeners: Listeners, request_id: EventRequestId) -> Self {
+        Self {
+            listeners,
+            request_id,
+        }
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.listeners.remove_request(self.request_id);
+    }
+}
+
 /// Holds event handlers and pending event handlers, along with the salts associating them.
 struct InnerListeners {
     pending: Mutex<Vec<Pending>>,
     handlers: Mutex<HashMap<EventName, HashMap<EventId, Handler>>>,
     js_event_listeners: Mutex<HashMap<WebviewLabel, HashMap<EventName, HashSet<JsHandler>>>>,
+    retained: Mutex<RetainedEvents>,
+    requests: Mutex<HashMap<EventRequestId, PendingRequest>>,
+    next_request_id: AtomicU32,
     function_name: &'static str,
     listeners_object_name: &'static str,
     next_event_id: Arc<AtomicU32>,
@@ -76,6 +146,9 @@ impl Default for Listeners {
                 pending: Mutex::default(),
                 handlers: Mutex::default(),
                 js_event_listeners: Mutex::default(),
+                retained: Mutex::default(),
+                requests: Mutex::default(),
+                next_request_id: Default::default(),
                 function_name: "__internal_unstable_listeners_function_id__",
                 listeners_object_name: "__internal_unstable_listeners_object_id__",
                 next_event_id: Default::default(),
@@ -126,6 +199,7 @@ impl Listeners {
                 Pending::Emit(args) => {
                     self.emit(args)?;
                 }
+                Pending::Replay { id, emit_args } => self.replay(id, emit_args)?,
             }
         }
 
@@ -149,11 +223,84 @@ impl Listeners {
         handler: F,
     ) -> EventId {
         let id = self.next_event_id();
+        let retained = self.retained(&event, &target);
         let handler = Handler::new(target, handler);
         self.listen_with_id(id, event, handler);
+        for emit_args in retained {
+            let _ = self.replay(id, emit_args);
+        }
         id
     }
 
+    /// Calls a single handler with a retained event.
+    fn replay(&self, id: EventId, emit_args: EmitArgs) -> crate::Result<()> {
+        let mut maybe_pending = false;
+
+        match self.inner.handlers.try_lock() {
+            Err(_) => self.insert_pending(Pending::Replay { id, emit_args }),
+            Ok(lock) => {
+                if let Some(Handler { callback, .. }) =
+                    lock.values().find_map(|handlers| handlers.get(&id))
+                {
+                    maybe_pending = true;
+                    (callback)(Event::new(id, &emit_args))
+                }
+            }
+        }
+
+        if maybe_pending {
+            self.flush_pending()?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the event so it is delivered to the listeners registered later.
+    ///
+    /// Replaces the event previously retained for the same event name and target,
+    /// and drops the least recently retained events when the capacity is reached.
+    pub(crate) fn retain(&self, target: EventTarget, emit_args: EmitArgs) {
+        let mut retained = self.inner.retained.lock().unwrap();
+        retained
+            .events
+            .retain(|e| e.target != target || e.emit_args.event_name != emit_args.event_name);
+        retained
+            .events
+            .push_back(RetainedEvent { target, emit_args });
+        retained.evict();
+    }
+
+    /// The retained events a listener registered with the given event name and target should receive.
+    pub(crate) fn retained(&self, event: &str, listener_target: &EventTarget) -> Vec<EmitArgs> {
+        self.inner
+            .retained
+            .lock()
+            .unwrap()
+            .events
+            .iter()
+            .filter(|e| {
+                event_matches(event, &e.emit_args.event_name) && e.target.matches(listener_target)
+            })
+            .map(|e| e.emit_args.clone())
+            .collect()
+    }
+
+    /// Removes the retained events, or only the ones with the given event name.
+    pub(crate) fn clear_retained(&self, event: Option<&str>) {
+        let mut retained = self.inner.retained.lock().unwrap();
+        match event {
+            Some(event) => retained.events.retain(|e| e.emit_args.event_name != event),
+            None => retained.events.clear(),
+        }
+    }
+
+    /// Sets the maximum number of retained events.
+    pub(crate) fn set_retained_capacity(&self, capacity: usize) {
+        let mut retained = self.inner.retained.lock().unwrap();
+        retained.capacity = capacity;
+        retained.evict();
+    }
+
     /// Listen to an event and immediately unlisten.
     pub(crate) fn once<F: FnOnce(Event) + Send + 'static>(
         &self,
@@ -184,6 +331,67 @@ impl Listeners {
         }
     }
 
+    /// Whether a Rust handler of the event matches the filter.
+    ///
+    /// Returns `true` when the handlers are locked by an emit, as the handler might be registered by then.
+    pub(crate) fn has_listener<F: Fn(&EventTarget) -> bool>(&self, event: &str, filter: F) -> bool {
+        match self.inner.handlers.try_lock() {
+            Err(_) => true,
+            Ok(lock) => lock
+                .iter()
+                .filter(|(pattern, _)| event_matches(pattern, event))
+                .flat_map(|(_, handlers)| handlers.values())
+                .any(|h| match_any_or_filter(&h.target, &Some(&filter))),
+        }
+    }
+
+    /// Registers a request sent to the given webviews, returning its identifier and the receiver of the first reply.
+    pub(crate) fn register_request(
+        &self,
+        webviews: HashSet<String>,
+    ) -> (EventRequestId, oneshot::Receiver<JsonValue>) {
+        let id = self.inner.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.inner
+            .requests
+            .lock()
+            .unwrap()
+            .insert(id, PendingRequest { webviews, sender });
+        (id, receiver)
+    }
+
+    /// Sends the reply of a request. Replies to requests that were already answered or timed out are ignored.
+    pub(crate) fn reply(&self, request_id: EventRequestId, payload: JsonValue) {
+        if let Some(request) = self.remove_request(request_id) {
+            let _ = request.sender.send(payload);
+        }
+    }
+
+    /// Sends the reply of a request from a webview.
+    ///
+    /// Replies from webviews the request was not sent to are ignored.
+    pub(crate) fn reply_from_webview(
+        &self,
+        request_id: EventRequestId,
+        webview: &str,
+        payload: JsonValue,
+    ) {
+        let mut requests = self.inner.requests.lock().unwrap();
+        if requests
+            .get(&request_id)
+            .is_some_and(|request| request.webviews.contains(webview))
+        {
+            if let Some(request) = requests.remove(&request_id) {
+                let _ = request.sender.send(payload);
+            }
+        }
+    }
+
+    /// Removes a pending request.
+    fn remove_request(&self, request_id: EventRequestId) -> Option<PendingRequest> {
+        self.inner.requests.lock().unwrap().remove(&request_id)
+    }
+
     /// Emits the given event with its payload based on a filter.
     pub(crate) fn emit_filter<F>(&self, emit_args: EmitArgs, filter: Option<F>) -> crate::Result<()>
     where
@@ -194,14 +402,15 @@ impl Listeners {
         match self.inner.handlers.try_lock() {
             Err(_) => self.insert_pending(Pending::Emit(emit_args.clone())),
             Ok(lock) => {
-                if let Some(handlers) = lock.get(&emit_args.event_name) {
-                    let handlers = handlers.iter();
-                    let handlers =
-                        handlers.filter(|(_, h)| match_any_or_filter(&h.target, &filter));
-                    for (&id, Handler { callback, .. }) in handlers {
-                        maybe_pending = true;
-                        (callback)(Event::new(id, emit_args.payload.clone()))
-                    }
+                // the handlers of the event name and of the patterns matching it
+                let handlers = lock
+                    .iter()
+                    .filter(|(pattern, _)| event_matches(pattern, &emit_args.event_name))
+                    .flat_map(|(_, handlers)| handlers.iter());
+                let handlers = handlers.filter(|(_, h)| match_any_or_filter(&h.target, &filter));
+                for (&id, Handler { callback, .. }) in handlers {
+                    maybe_pending = true;
+                    (callback)(Event::new(id, &emit_args))
                 }
             }
         }
@@ -256,12 +465,35 @@ impl Listeners {
         let js_listeners = self.inner.js_event_listeners.lock().unwrap();
         js_listeners.values().any(|events| {
             events
-                .get(event)
-                .map(|handlers| handlers.iter().any(|handler| filter(&handler.target)))
-                .unwrap_or(false)
+                .iter()
+                .filter(|(pattern, _)| event_matches(pattern, event))
+                .any(|(_, handlers)| handlers.iter().any(|handler| filter(&handler.target)))
         })
     }
 
+    /// The labels of the webviews with a JavaScript listener of the event matching the filter.
+    pub(crate) fn js_listener_webviews<F: Fn(&EventTarget) -> bool>(
+        &self,
+        event: &str,
+        filter: F,
+    ) -> HashSet<String> {
+        let js_listeners = self.inner.js_event_listeners.lock().unwrap();
+        js_listeners
+            .iter()
+            .filter(|(_, events)| {
+                events
+                    .iter()
+                    .filter(|(pattern, _)| event_matches(pattern, event))
+                    .any(|(_, handlers)| {
+                        handlers
+                            .iter()
+                            .any(|handler| match_any_or_filter(&handler.target, &Some(&filter)))
+                    })
+            })
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
     pub(crate) fn emit_js_filter<'a, R, I, F>(
         &self,
         mut webviews: I,
@@ -276,13 +508,20 @@ impl Listeners {
     {
         let js_listeners = self.inner.js_event_listeners.lock().unwrap();
         webviews.try_for_each(|webview| {
-            if let Some(handlers) = js_listeners.get(webview.label()).and_then(|s| s.get(event)) {
+            let Some(events) = js_listeners.get(webview.label()) else {
+                return Ok(());
+            };
+
+            for (pattern, handlers) in events
+                .iter()
+                .filter(|(pattern, _)| event_matches(pattern, event))
+            {
                 let ids = handlers
                     .iter()
                     .filter(|handler| match_any_or_filter(&handler.target, &filter))
                     .map(|handler| handler.id)
                     .collect::<Vec<_>>();
-                webview.emit_js(emit_args, &ids)?;
+                webview.emit_js(emit_args, pattern, &ids)?;
             }
 
             Ok(())
@@ -319,7 +558,8 @@ fn match_any_or_filter<F: Fn(&EventTarget) -> bool>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::event::EventTarget;
+    use crate::event::{EventStream, EventTarget, OnceEvent};
+    use futures_util::StreamExt;
     use proptest::prelude::*;
 
     // dummy event handler function
@@ -382,7 +622,8 @@ mod test {
         listeners.emit(EmitArgs {
           event_name: key.clone(),
           event: serde_json::to_string(&key).unwrap(),
-          payload: serde_json::to_string(&d).unwrap()
+          payload: serde_json::to_string(&d).unwrap(),
+          request_id: None,
         })?;
 
         // lock the mutex
@@ -392,4 +633,181 @@ mod test {
         assert!(l.contains_key(&key));
       }
     }
+
+    fn emit(listeners: &Listeners, event: &str, payload: &str) {
+        listeners
+            .emit(EmitArgs {
+                event_name: event.into(),
+                event: serde_json::to_string(event).unwrap(),
+                payload: serde_json::to_string(payload).unwrap(),
+                request_id: None,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn stream_unlistens_on_drop() {
+        let listeners = Listeners::default();
+        let mut stream = EventStream::new(&listeners, "ready".into(), EventTarget::Any);
+
+        emit(&listeners, "ready", "a");
+        emit(&listeners, "ready", "b");
+        let events = crate::async_runtime::block_on(async {
+            vec![
+                stream.next().await.unwrap().payload().to_string(),
+                stream.next().await.unwrap().payload().to_string(),
+            ]
+        });
+        assert_eq!(events, ["\"a\"", "\"b\""]);
+
+        drop(stream);
+        assert!(listeners.inner.handlers.lock().unwrap()["ready"].is_empty());
+    }
+
+    #[test]
+    fn once_async_resolves_with_first_event() {
+        let listeners = Listeners::default();
+        let once = OnceEvent::new(&listeners, "ready".into(), EventTarget::Any);
+
+        emit(&listeners, "ready", "first");
+        emit(&listeners, "ready", "second");
+        let event = crate::async_runtime::block_on(once);
+        assert_eq!(event.payload(), "\"first\"");
+        assert!(listeners.inner.handlers.lock().unwrap()["ready"].is_empty());
+    }
+
+    #[test]
+    fn replays_retained_events() {
+        let listeners = Listeners::default();
+        listeners.set_retained_capacity(2);
+
+        let retain = |target: EventTarget, event: &str, payload: &str| {
+            listeners.retain(
+                target,
+                EmitArgs {
+                    event_name: event.into(),
+                    event: serde_json::to_string(event).unwrap(),
+                    payload: serde_json::to_string(payload).unwrap(),
+                    request_id: None,
+                },
+            )
+        };
+        retain(EventTarget::Any, "auth-changed", "alice");
+        retain(EventTarget::Any, "auth-changed", "bob");
+        retain(EventTarget::window("settings"), "theme-changed", "dark");
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_ = received.clone();
+        listeners.listen(
+            "auth-changed".into(),
+            EventTarget::window("main"),
+            move |event| received_.lock().unwrap().push(event.payload().to_string()),
+        );
+        assert_eq!(*received.lock().unwrap(), ["\"bob\""]);
+
+        assert!(listeners
+            .retained("theme-changed", &EventTarget::window("main"))
+            .is_empty());
+        assert_eq!(
+            listeners
+                .retained("theme-changed", &EventTarget::window("settings"))
+                .len(),
+            1
+        );
+
+        retain(EventTarget::Any, "locale-changed", "en");
+        assert!(listeners
+            .retained("auth-changed", &EventTarget::Any)
+            .is_empty());
+
+        listeners.clear_retained(None);
+        assert!(listeners
+            .retained("locale-changed", &EventTarget::Any)
+            .is_empty());
+    }
+
+    #[test]
+    fn replies_to_requests() {
+        let listeners = Listeners::default();
+        let listeners_ = listeners.clone();
+        listeners.listen("get-theme".into(), EventTarget::App, move |event| {
+            let request_id = event.request_id().unwrap();
+            listeners_.reply(request_id, serde_json::json!("dark"));
+            // only the first reply is delivered
+            listeners_.reply(request_id, serde_json::json!("light"));
+        });
+
+        assert!(listeners.has_listener("get-theme", |t| EventTarget::App.matches(t)));
+        assert!(!listeners.has_listener("get-theme", |t| EventTarget::window("main").matches(t)));
+
+        let (request_id, receiver) = listeners.register_request(Default::default());
+        let mut emit_args = EmitArgs::new("get-theme", ()).unwrap();
+        emit_args.request_id.replace(request_id);
+        listeners.emit(emit_args).unwrap();
+
+        assert_eq!(
+            crate::async_runtime::block_on(receiver).unwrap(),
+            serde_json::json!("dark")
+        );
+    }
+
+    #[test]
+    fn ignores_replies_from_other_webviews() {
+        let listeners = Listeners::default();
+        listeners.listen_js("get-theme", "main", EventTarget::Any, 0);
+        listeners.listen_js("get-theme", "other", EventTarget::webview("other"), 1);
+
+        let filter = |t: &EventTarget| EventTarget::webview("main").matches(t);
+        let webviews = listeners.js_listener_webviews("get-theme", filter);
+        assert_eq!(webviews, HashSet::from(["main".to_string()]));
+
+        let (request_id, mut receiver) = listeners.register_request(webviews);
+        listeners.reply_from_webview(request_id, "other", serde_json::json!("light"));
+        assert!(receiver.try_recv().is_err());
+
+        listeners.reply_from_webview(request_id, "main", serde_json::json!("dark"));
+        assert_eq!(receiver.try_recv().unwrap(), serde_json::json!("dark"));
+    }
+
+    #[test]
+    fn guard_removes_request() {
+        let listeners = Listeners::default();
+        let (request_id, mut receiver) = listeners.register_request(Default::default());
+        drop(RequestGuard::new(listeners.clone(), request_id));
+
+        assert!(listeners.inner.requests.lock().unwrap().is_empty());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn listens_to_patterns() {
+        let listeners = Listeners::default();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        for pattern in ["sync:*", "sync:**", "sync:progress"] {
+            let received = received.clone();
+            listeners.listen(pattern.into(), EventTarget::Any, move |event| {
+                received
+                    .lock()
+                    .unwrap()
+                    .push(format!("{pattern} {}", event.name()))
+            });
+        }
+
+        emit(&listeners, "sync:progress", "");
+        emit(&listeners, "sync:file:done", "");
+        emit(&listeners, "other:done", "");
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort();
+        assert_eq!(
+            received,
+            [
+                "sync:* sync:progress",
+                "sync:** sync:file:done",
+                "sync:** sync:progress",
+                "sync:progress sync:progress",
+            ]
+        );
+    }
 }
@@ -1,9 +1,11 @@
 mod listener;
 pub(crate) mod plugin;
-use std::{convert::Infallible, str::FromStr};
+mod stream;
+use std::{convert::Infallible, str::FromStr, time::Duration};
 
-pub(crate) use listener::Listeners;
+pub(crate) use listener::{Listeners, RequestGuard, DEFAULT_RETAINED_EVENTS_CAPACITY};
 use serde::{Deserialize, Serialize};
+pub use stream::{EventStream, OnceEvent};
 
 /// Checks if an event name is valid.
 pub fn is_event_name_valid(event: &str) -> bool {
@@ -19,9 +21,50 @@ pub fn assert_event_name_is_valid(event: &str) {
     );
 }
 
+/// Checks if the event name or pattern a listener subscribes to is valid.
+///
+/// Patterns are event names that contain wildcards:
+/// `*` matches any characters except `:`, so `sync:*` matches `sync:done` but not `sync:file:done`,
+/// and `**` matches any characters, so `sync:**` matches both.
+pub fn is_event_pattern_valid(pattern: &str) -> bool {
+    pattern
+        .chars()
+        .all(|c| c == '*' || c.is_alphanumeric() || c == '-' || c == '/' || c == ':' || c == '_')
+}
+
+pub fn assert_event_pattern_is_valid(pattern: &str) {
+    assert!(
+        is_event_pattern_valid(pattern),
+        "Event name must include only alphanumeric characters, `-`, `/`, `:`, `_` and `*`."
+    );
+}
+
+/// Whether an emitted event is delivered to the listeners of the given event name or pattern.
+pub(crate) fn event_matches(pattern: &str, event: &str) -> bool {
+    fn glob(pattern: &[u8], event: &[u8]) -> bool {
+        match pattern {
+            [] => event.is_empty(),
+            [b'*', b'*', rest @ ..] => (0..=event.len()).any(|i| glob(rest, &event[i..])),
+            [b'*', rest @ ..] => {
+                let segment_len = event.iter().position(|c| *c == b':').unwrap_or(event.len());
+                (0..=segment_len).any(|i| glob(rest, &event[i..]))
+            }
+            [c, rest @ ..] => event.first() == Some(c) && glob(rest, &event[1..]),
+        }
+    }
+
+    pattern == event || (pattern.contains('*') && glob(pattern.as_bytes(), event.as_bytes()))
+}
+
 /// Unique id of an event.
 pub type EventId = u32;
 
+/// Unique id of an event emitted with [`crate::Manager::request`].
+pub type EventRequestId = u32;
+
+/// The timeout of [`crate::Manager::request`].
+pub(crate) const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Event Target
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 #[serde(tag = "kind")]
@@ -96,6 +139,26 @@ impl EventTarget {
             label: label.into(),
         }
     }
+
+    /// Whether an event emitted to this target is delivered to a listener registered with the given target.
+    pub(crate) fn matches(&self, listener_target: &EventTarget) -> bool {
+        if *listener_target == EventTarget::Any {
+            return true;
+        }
+
+        match self {
+            EventTarget::Any => true,
+            EventTarget::AnyLabel {
+                label: target_label,
+            } => match listener_target {
+                EventTarget::Window { label }
+                | EventTarget::Webview { label }
+                | EventTarget::WebviewWindow { label } => label == target_label,
+                _ => false,
+            },
+            target => target == listener_target,
+        }
+    }
 }
 
 impl<T: AsRef<str>> From<T> for EventTarget {
@@ -125,6 +188,8 @@ pub struct EmitArgs {
     pub event: String,
     /// Serialized payload.
     pub payload: String,
+    /// The identifier of the request the listeners should reply to.
+    pub request_id: Option<EventRequestId>,
 }
 
 impl EmitArgs {
@@ -135,6 +200,7 @@ impl EmitArgs {
             event_name: event.into(),
             event: serde_json::to_string(event)?,
             payload: serde_json::to_string(&payload)?,
+            request_id: None,
         })
     }
 }
@@ -143,12 +209,19 @@ impl EmitArgs {
 #[derive(Debug, Clone)]
 pub struct Event {
     id: EventId,
+    name: String,
     data: String,
+    request_id: Option<EventRequestId>,
 }
 
 impl Event {
-    fn new(id: EventId, data: String) -> Self {
-        Self { id, data }
+    fn new(id: EventId, emit_args: &EmitArgs) -> Self {
+        Self {
+            id,
+            name: emit_args.event_name.clone(),
+            data: emit_args.payload.clone(),
+            request_id: emit_args.request_id,
+        }
     }
 
     /// The [`EventId`] of the handler that was triggered.
@@ -156,10 +229,22 @@ impl Event {
         self.id
     }
 
+    /// The name of the emitted event, which differs from the listened name when listening to a pattern like `sync:*`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// The event payload.
     pub fn payload(&self) -> &str {
         &self.data
     }
+
+    /// The identifier of the request when the event was emitted with [`crate::Manager::request`].
+    ///
+    /// Use [`crate::Manager::reply`] to answer it.
+    pub fn request_id(&self) -> Option<EventRequestId> {
+        self.request_id
+    }
 }
 
 pub fn listen_js_script(
@@ -193,13 +278,19 @@ pub fn listen_js_script(
 pub fn emit_js_script(
     event_emit_function_name: &str,
     emit_args: &EmitArgs,
+    listener_event: &str,
     serialized_ids: &str,
 ) -> crate::Result<String> {
+    let request_id = emit_args
+        .request_id
+        .map(|id| format!(", requestId: {id}"))
+        .unwrap_or_default();
     Ok(format!(
-    "(function () {{ const fn = window['{}']; fn && fn({{event: {}, payload: {}}}, {ids}) }})()",
+    "(function () {{ const fn = window['{}']; fn && fn({{event: {}, payload: {}{request_id}}}, {ids}, {}) }})()",
     event_emit_function_name,
     emit_args.event,
     emit_args.payload,
+    serde_json::to_string(listener_event)?,
     ids = serialized_ids,
   ))
 }
@@ -223,8 +314,8 @@ pub fn unlisten_js_script(
 pub fn event_initialization_script(function: &str, listeners: &str) -> String {
     format!(
         "Object.defineProperty(window, '{function}', {{
-      value: function (eventData, ids) {{
-        const listeners = (window['{listeners}'] && window['{listeners}'][eventData.event]) || []
+      value: function (eventData, ids, listenerEvent) {{
+        const listeners = (window['{listeners}'] && window['{listeners}'][listenerEvent || eventData.event]) || []
         for (const id of ids) {{
           const listener = listeners[id]
           if (listener) {{
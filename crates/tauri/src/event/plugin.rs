@@ -5,10 +5,13 @@ use serde_json::Value as JsonValue;
 use tauri_runtime::window::is_label_valid;
 
 use crate::plugin::{Builder, TauriPlugin};
-use crate::{command, ipc::CallbackFn, EventId, Manager, Result, Runtime};
+use crate::{
+    command, ipc::CallbackFn, sealed::ManagerBase, EventId, EventRequestId, Manager, Result,
+    Runtime,
+};
 use crate::{AppHandle, Webview};
 
-use super::{is_event_name_valid, EventTarget};
+use super::{is_event_name_valid, is_event_pattern_valid, EventTarget};
 
 pub struct EventName(String);
 
@@ -36,6 +39,33 @@ impl<'de> Deserialize<'de> for EventName {
     }
 }
 
+/// An event name or a pattern like `sync:*`.
+pub struct EventPattern(String);
+
+impl Deref for EventPattern {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for EventPattern {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        if is_event_pattern_valid(&pattern) {
+            Ok(EventPattern(pattern))
+        } else {
+            Err(serde::de::Error::custom(
+                "Event name must include only alphanumeric characters, `-`, `/`, `:`, `_` and `*`.",
+            ))
+        }
+    }
+}
+
 pub struct WebviewLabel(String);
 
 impl AsRef<str> for WebviewLabel {
@@ -63,7 +93,7 @@ impl<'de> Deserialize<'de> for WebviewLabel {
 #[command(root = "crate")]
 pub fn listen<R: Runtime>(
     webview: Webview<R>,
-    event: EventName,
+    event: EventPattern,
     target: EventTarget,
     handler: CallbackFn,
 ) -> Result<EventId> {
@@ -73,7 +103,7 @@ pub fn listen<R: Runtime>(
 #[command(root = "crate")]
 pub fn unlisten<R: Runtime>(
     webview: Webview<R>,
-    event: EventName,
+    event: EventPattern,
     event_id: EventId,
 ) -> Result<()> {
     webview.unlisten_js(&event, event_id)
@@ -98,9 +128,24 @@ pub fn emit_to<R: Runtime>(
     app.emit_to(target, &event, payload)
 }
 
+#[command(root = "crate")]
+pub fn reply<R: Runtime>(
+    webview: Webview<R>,
+    request_id: EventRequestId,
+    payload: Option<JsonValue>,
+) {
+    webview.manager().listeners().reply_from_webview(
+        request_id,
+        webview.label(),
+        payload.unwrap_or_default(),
+    );
+}
+
 /// Initializes the event plugin.
 pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("event")
-        .invoke_handler(crate::generate_handler![listen, unlisten, emit, emit_to])
+        .invoke_handler(crate::generate_handler![
+            listen, unlisten, emit, emit_to, reply
+        ])
         .build()
 }
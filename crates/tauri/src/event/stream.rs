@@ -0,0 +1,98 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use tokio::sync::{mpsc, oneshot};
+
+use super::{Event, EventId, EventTarget, Listeners};
+
+/// A [`Stream`] of the events emitted to a listener.
+///
+/// Created with [`crate::Manager::listen_stream`]. The listener is removed when the stream is dropped.
+pub struct EventStream {
+    listeners: Listeners,
+    id: EventId,
+    receiver: mpsc::UnboundedReceiver<Event>,
+}
+
+impl EventStream {
+    pub(crate) fn new(listeners: &Listeners, event: String, target: EventTarget) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = listeners.listen(event, target, move |event| {
+            let _ = sender.send(event);
+        });
+        Self {
+            listeners: listeners.clone(),
+            id,
+            receiver,
+        }
+    }
+
+    /// The [`EventId`] of the listener.
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.listeners.unlisten(self.id);
+    }
+}
+
+/// A [`Future`] resolving to the next event emitted to a listener.
+///
+/// Created with [`crate::Manager::once_async`]. The listener is removed when the future is dropped.
+/// If the listener is removed with [`crate::Manager::unlisten`] before the event is emitted, the future never resolves.
+pub struct OnceEvent {
+    listeners: Listeners,
+    id: EventId,
+    receiver: oneshot::Receiver<Event>,
+}
+
+impl OnceEvent {
+    pub(crate) fn new(listeners: &Listeners, event: String, target: EventTarget) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let id = listeners.once(event, target, move |event| {
+            let _ = sender.send(event);
+        });
+        Self {
+            listeners: listeners.clone(),
+            id,
+            receiver,
+        }
+    }
+
+    /// The [`EventId`] of the listener.
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+}
+
+impl Future for OnceEvent {
+    type Output = Event;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Event> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(Ok(event)) => Poll::Ready(event),
+            Poll::Ready(Err(_)) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for OnceEvent {
+    fn drop(&mut self) {
+        self.listeners.unlisten(self.id);
+    }
+}
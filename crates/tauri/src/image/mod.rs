@@ -15,7 +15,11 @@ pub struct Image<'a> {
     height: u32,
 }
 
-impl Resource for Image<'static> {}
+impl Resource for Image<'static> {
+    fn size_hint(&self) -> usize {
+        self.rgba.len()
+    }
+}
 
 impl Image<'static> {
     /// Creates a new Image using RGBA data, in row-major order from top to bottom, and with specified width and height.
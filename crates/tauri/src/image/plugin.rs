@@ -13,7 +13,7 @@ fn new<R: Runtime>(
 ) -> crate::Result<ResourceId> {
     let image = Image::new_owned(rgba, width, height);
     let mut resources_table = webview.resources_table();
-    let rid = resources_table.add(image);
+    let rid = resources_table.try_add(image)?;
     Ok(rid)
 }
 
@@ -22,7 +22,7 @@ fn new<R: Runtime>(
 fn from_bytes<R: Runtime>(webview: Webview<R>, bytes: Vec<u8>) -> crate::Result<ResourceId> {
     let image = Image::from_bytes(&bytes)?.to_owned();
     let mut resources_table = webview.resources_table();
-    let rid = resources_table.add(image);
+    let rid = resources_table.try_add(image)?;
     Ok(rid)
 }
 
@@ -40,7 +40,7 @@ fn from_path<R: Runtime>(
 ) -> crate::Result<ResourceId> {
     let image = Image::from_path(path)?.to_owned();
     let mut resources_table = webview.resources_table();
-    let rid = resources_table.add(image);
+    let rid = resources_table.try_add(image)?;
     Ok(rid)
 }
 
@@ -1,6 +1,8 @@
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -27,7 +29,15 @@ use super::{CommandArg, CommandItem};
 /// The runtime authority used to authorize IPC execution based on the Access Control List.
 pub struct RuntimeAuthority {
     acl: BTreeMap<String, crate::utils::acl::manifest::Manifest>,
+    /// The ACL resolved at build time, or reloaded from the capability files in development.
+    base: Resolved,
+    /// The capabilities added at runtime, applied on top of [`Self::base`].
+    runtime_capabilities: BTreeMap<String, Capability>,
+    /// The usage state of the runtime capabilities that have [`CapabilityLimits`].
+    limited_capabilities: BTreeMap<String, CapabilityUsage>,
     allowed_commands: BTreeMap<String, Vec<ResolvedCommand>>,
+    /// The commands allowed by each limited capability, only checked when no other capability allows the command.
+    limited_commands: BTreeMap<String, BTreeMap<String, Vec<ResolvedCommand>>>,
     denied_commands: BTreeMap<String, Vec<ResolvedCommand>>,
     pub(crate) scope_manager: ScopeManager,
 }
@@ -64,10 +74,207 @@ impl Origin {
     }
 }
 
+/// The outcome of an access check, see [`RuntimeAuthority::explain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessDecision {
+    /// A capability allows the command.
+    Allowed,
+    /// A capability denies the command, which takes precedence over the allowed ones.
+    Denied,
+    /// No capability allows the command for the origin and the window or webview.
+    NotAllowed,
+}
+
+impl Display for AccessDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allowed => write!(f, "allowed"),
+            Self::Denied => write!(f, "denied"),
+            Self::NotAllowed => write!(f, "not allowed"),
+        }
+    }
+}
+
+/// A capability rule considered by an access check.
+#[derive(Debug, Clone)]
+pub struct AccessRule {
+    /// Whether the rule denies the command instead of allowing it.
+    pub deny: bool,
+    /// The identifier of the capability that defines the rule. Only available in debug builds.
+    pub capability: Option<String>,
+    /// The identifier of the permission that defines the rule. Only available in debug builds.
+    pub permission: Option<String>,
+    /// The execution context of the rule, either `local` or the remote URL pattern.
+    pub context: String,
+    /// Whether the origin of the call matches the context of the rule.
+    pub origin_matches: bool,
+    /// Whether the window label matches one of the window patterns of the rule.
+    pub window_matches: bool,
+    /// Whether the webview label matches one of the webview patterns of the rule.
+    pub webview_matches: bool,
+    /// Whether the rule belongs to a capability that expired or reached its maximum number of uses.
+    pub expired: bool,
+}
+
+impl AccessRule {
+    fn new(
+        command: &ResolvedCommand,
+        deny: bool,
+        window: &str,
+        webview: &str,
+        origin: &Origin,
+    ) -> Self {
+        Self {
+            deny,
+            #[cfg(debug_assertions)]
+            capability: Some(command.referenced_by.capability.clone()),
+            #[cfg(not(debug_assertions))]
+            capability: None,
+            #[cfg(debug_assertions)]
+            permission: Some(command.referenced_by.permission.clone()),
+            #[cfg(not(debug_assertions))]
+            permission: None,
+            context: match &command.context {
+                ExecutionContext::Local => "local".into(),
+                ExecutionContext::Remote { url } => format!("remote: {}", url.as_str()),
+            },
+            origin_matches: origin.matches(&command.context),
+            window_matches: command.windows.iter().any(|w| w.matches(window)),
+            webview_matches: command.webviews.iter().any(|w| w.matches(webview)),
+            expired: false,
+        }
+    }
+
+    /// Whether the rule applies to the call.
+    pub fn matches(&self) -> bool {
+        !self.expired && self.origin_matches && (self.window_matches || self.webview_matches)
+    }
+}
+
+/// The report of an access check, listing each capability rule considered for the command.
+///
+/// Created with [`RuntimeAuthority::explain`]. Its [`Display`] implementation is a readable summary.
+#[derive(Debug, Clone)]
+pub struct AccessReport {
+    /// The command, e.g. `plugin:fs|read_file`.
+    pub command: String,
+    /// The label of the window.
+    pub window: String,
+    /// The label of the webview.
+    pub webview: String,
+    /// The origin of the call.
+    pub origin: String,
+    /// The outcome of the check.
+    pub decision: AccessDecision,
+    /// The rules defined for the command, the denied ones first.
+    pub rules: Vec<AccessRule>,
+}
+
+impl Display for AccessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn matches(matches: bool) -> &'static str {
+            if matches {
+                "matches"
+            } else {
+                "does not match"
+            }
+        }
+
+        write!(
+            f,
+            "{} {} on origin {}, window {}, webview {}",
+            self.command, self.decision, self.origin, self.window, self.webview
+        )?;
+        if self.rules.is_empty() {
+            write!(f, "; no capability references this command")?;
+        }
+        for rule in &self.rules {
+            write!(
+                f,
+                "\n- {} [{}]",
+                if rule.deny { "deny" } else { "allow" },
+                rule.context
+            )?;
+            if let (Some(capability), Some(permission)) = (&rule.capability, &rule.permission) {
+                write!(f, " capability: {capability}, permission: {permission}")?;
+            }
+            write!(
+                f,
+                ": origin {}, window {}, webview {}",
+                matches(rule.origin_matches),
+                matches(rule.window_matches),
+                matches(rule.webview_matches)
+            )?;
+            if rule.expired {
+                write!(f, " (expired)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The usage limits of a capability added at runtime.
+///
+/// A capability that expires or reaches its maximum number of uses is removed from the runtime authority.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityLimits {
+    /// How long the capability is valid for after it is added.
+    pub expires_after: Option<Duration>,
+    /// How many commands the capability can allow.
+    pub max_uses: Option<u32>,
+}
+
+impl CapabilityLimits {
+    fn is_unlimited(&self) -> bool {
+        self.expires_after.is_none() && self.max_uses.is_none()
+    }
+}
+
+/// The usage state of a capability with [`CapabilityLimits`].
+struct CapabilityUsage {
+    expires_at: Option<Instant>,
+    remaining_uses: Option<AtomicU32>,
+}
+
+impl CapabilityUsage {
+    fn new(limits: CapabilityLimits) -> Self {
+        Self {
+            expires_at: limits.expires_after.map(|d| Instant::now() + d),
+            remaining_uses: limits.max_uses.map(AtomicU32::new),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.expires_at.map_or(true, |at| Instant::now() < at)
+            && self
+                .remaining_uses
+                .as_ref()
+                .map_or(true, |uses| uses.load(Ordering::Acquire) > 0)
+    }
+
+    /// Takes one use of the capability, returning `false` if it is no longer active.
+    fn consume(&self) -> bool {
+        if self.expires_at.is_some_and(|at| Instant::now() >= at) {
+            return false;
+        }
+        match &self.remaining_uses {
+            Some(uses) => uses
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                .is_ok(),
+            None => true,
+        }
+    }
+}
+
 /// A capability that can be added at runtime.
 pub trait RuntimeCapability {
     /// Creates the capability file.
     fn build(self) -> CapabilityFile;
+
+    /// The usage limits of the capability. Unlimited by default.
+    fn limits(&self) -> CapabilityLimits {
+        CapabilityLimits::default()
+    }
 }
 
 impl<T: AsRef<str>> RuntimeCapability for T {
@@ -77,21 +284,24 @@ impl<T: AsRef<str>> RuntimeCapability for T {
 }
 
 /// A builder for a [`Capability`].
-pub struct CapabilityBuilder(Capability);
+pub struct CapabilityBuilder(Capability, CapabilityLimits);
 
 impl CapabilityBuilder {
     /// Creates a new capability builder with a unique identifier.
     pub fn new(identifier: impl Into<String>) -> Self {
-        Self(Capability {
-            identifier: identifier.into(),
-            description: "".into(),
-            remote: None,
-            local: true,
-            windows: Vec::new(),
-            webviews: Vec::new(),
-            permissions: Vec::new(),
-            platforms: None,
-        })
+        Self(
+            Capability {
+                identifier: identifier.into(),
+                description: "".into(),
+                remote: None,
+                local: true,
+                windows: Vec::new(),
+                webviews: Vec::new(),
+                permissions: Vec::new(),
+                platforms: None,
+            },
+            CapabilityLimits::default(),
+        )
     }
 
     /// Allows this capability to be used by a remote URL.
@@ -209,33 +419,55 @@ impl CapabilityBuilder {
             .extend(platforms);
         self
     }
+
+    /// Removes the capability after the given duration.
+    ///
+    /// When added with [`Manager::add_capability`], the `tauri://capability-expired` event
+    /// is emitted with the capability identifier once it is removed.
+    pub fn expires_after(mut self, duration: Duration) -> Self {
+        self.1.expires_after.replace(duration);
+        self
+    }
+
+    /// Removes the capability after it allowed the given number of commands.
+    ///
+    /// The capability is only used when no other capability allows the command.
+    pub fn max_uses(mut self, uses: u32) -> Self {
+        self.1.max_uses.replace(uses);
+        self
+    }
 }
 
 impl RuntimeCapability for CapabilityBuilder {
     fn build(self) -> CapabilityFile {
         CapabilityFile::Capability(self.0)
     }
+
+    fn limits(&self) -> CapabilityLimits {
+        self.1
+    }
 }
 
 impl RuntimeAuthority {
     #[doc(hidden)]
     pub fn new(acl: BTreeMap<String, Manifest>, resolved_acl: Resolved) -> Self {
-        let command_cache = resolved_acl
-            .command_scope
-            .keys()
-            .map(|key| (*key, <TypeMap![Send + Sync]>::new()))
-            .collect();
-        Self {
+        let mut authority = Self {
             acl,
-            allowed_commands: resolved_acl.allowed_commands,
-            denied_commands: resolved_acl.denied_commands,
+            base: resolved_acl,
+            runtime_capabilities: Default::default(),
+            limited_capabilities: Default::default(),
+            allowed_commands: Default::default(),
+            limited_commands: Default::default(),
+            denied_commands: Default::default(),
             scope_manager: ScopeManager {
-                command_scope: resolved_acl.command_scope,
-                global_scope: resolved_acl.global_scope,
-                command_cache,
+                command_scope: Default::default(),
+                global_scope: Default::default(),
+                command_cache: Default::default(),
                 global_scope_cache: Default::default(),
             },
-        }
+        };
+        authority.apply(Resolved::default(), Vec::new());
+        authority
     }
 
     pub(crate) fn has_app_manifest(&self) -> bool {
@@ -244,85 +476,230 @@ impl RuntimeAuthority {
 
     #[doc(hidden)]
     pub fn __allow_command(&mut self, command: String, context: ExecutionContext) {
-        self.allowed_commands.insert(
-            command,
-            vec![ResolvedCommand {
-                context,
-                windows: vec!["*".parse().unwrap()],
-                ..Default::default()
-            }],
-        );
+        let resolved = vec![ResolvedCommand {
+            context,
+            windows: vec!["*".parse().unwrap()],
+            ..Default::default()
+        }];
+        self.base
+            .allowed_commands
+            .insert(command.clone(), resolved.clone());
+        self.allowed_commands.insert(command, resolved);
     }
 
     /// Adds the given capability to the runtime authority.
+    ///
+    /// A capability with the same identifier that was previously added at runtime is replaced.
+    ///
+    /// Capabilities with [`CapabilityLimits`] are removed by [`Self::remove_expired_capabilities`]
+    /// once they expire or reach their maximum number of uses.
     pub fn add_capability(&mut self, capability: impl RuntimeCapability) -> crate::Result<()> {
-        let mut capabilities = BTreeMap::new();
-        match capability.build() {
-            CapabilityFile::Capability(c) => {
-                capabilities.insert(c.identifier.clone(), c);
-            }
-
+        let limits = capability.limits();
+        let new_capabilities = match capability.build() {
+            CapabilityFile::Capability(c) => vec![c],
             CapabilityFile::List(capabilities_list)
             | CapabilityFile::NamedList {
                 capabilities: capabilities_list,
-            } => {
-                capabilities.extend(
-                    capabilities_list
-                        .into_iter()
-                        .map(|c| (c.identifier.clone(), c)),
-                );
+            } => capabilities_list,
+        };
+
+        let mut capabilities = self.runtime_capabilities.clone();
+        let mut limited = self.limited_capabilities_limits();
+        for capability in new_capabilities {
+            if limits.is_unlimited() {
+                limited.remove(&capability.identifier);
+            } else {
+                limited.insert(capability.identifier.clone(), Some(limits));
             }
+            capabilities.insert(capability.identifier.clone(), capability);
         }
 
-        let resolved = Resolved::resolve(
-            &self.acl,
-            capabilities,
-            tauri_utils::platform::Target::current(),
-        )
-        .unwrap();
+        self.set_runtime_capabilities(capabilities, limited)
+    }
 
-        // fill global scope
-        for (plugin, global_scope) in resolved.global_scope {
-            let global_scope_entry = self.scope_manager.global_scope.entry(plugin).or_default();
+    /// Removes a capability that was added with [`Self::add_capability`].
+    ///
+    /// The capabilities defined at build time cannot be removed.
+    pub fn remove_capability(&mut self, identifier: &str) -> crate::Result<()> {
+        let mut capabilities = self.runtime_capabilities.clone();
+        if capabilities.remove(identifier).is_none() {
+            return Err(crate::Error::CapabilityNotFound(identifier.into()));
+        }
+        let mut limited = self.limited_capabilities_limits();
+        limited.remove(identifier);
+        self.set_runtime_capabilities(capabilities, limited)
+    }
 
-            global_scope_entry.allow.extend(global_scope.allow);
-            global_scope_entry.deny.extend(global_scope.deny);
+    /// Removes the capabilities added at runtime that expired or reached their maximum number of uses,
+    /// returning their identifiers.
+    pub fn remove_expired_capabilities(&mut self) -> Vec<String> {
+        let expired = self
+            .limited_capabilities
+            .iter()
+            .filter(|(_, usage)| !usage.is_active())
+            .map(|(identifier, _)| identifier.clone())
+            .collect::<Vec<_>>();
+        if expired.is_empty() {
+            return expired;
+        }
 
-            self.scope_manager.global_scope_cache = Default::default();
+        let mut capabilities = self.runtime_capabilities.clone();
+        let mut limited = self.limited_capabilities_limits();
+        for identifier in &expired {
+            capabilities.remove(identifier);
+            limited.remove(identifier);
         }
+        // the remaining capabilities were already resolved so this cannot fail
+        if let Err(e) = self.set_runtime_capabilities(capabilities, limited) {
+            log::error!("failed to remove expired capabilities: {e}");
+        }
+        expired
+    }
 
-        // denied commands
-        for (cmd_key, resolved_cmds) in resolved.denied_commands {
-            let entry = self.denied_commands.entry(cmd_key).or_default();
-            entry.extend(resolved_cmds);
+    /// The limited capabilities, keeping their current usage state.
+    fn limited_capabilities_limits(&self) -> BTreeMap<String, Option<CapabilityLimits>> {
+        self.limited_capabilities
+            .keys()
+            .map(|identifier| (identifier.clone(), None))
+            .collect()
+    }
+
+    /// Replaces the capabilities defined at build time, keeping the ones added at runtime.
+    #[cfg(all(dev, desktop))]
+    pub(crate) fn reload_capabilities(
+        &mut self,
+        capabilities: BTreeMap<String, Capability>,
+    ) -> crate::Result<()> {
+        self.base = Resolved::resolve(&self.acl, capabilities, Target::current())?;
+        let (runtime, limited) = self.resolve_runtime(&self.runtime_capabilities)?;
+        self.apply(runtime, limited);
+        Ok(())
+    }
+
+    /// Sets the runtime capabilities and the limited ones among them,
+    /// where a `None` limit keeps the current usage state of the capability.
+    fn set_runtime_capabilities(
+        &mut self,
+        capabilities: BTreeMap<String, Capability>,
+        limited: BTreeMap<String, Option<CapabilityLimits>>,
+    ) -> crate::Result<()> {
+        let mut limited_capabilities = std::mem::take(&mut self.limited_capabilities);
+        let limited_capabilities = limited
+            .into_iter()
+            .filter_map(|(identifier, limits)| {
+                let usage = match limits {
+                    Some(limits) => CapabilityUsage::new(limits),
+                    None => limited_capabilities.remove(&identifier)?,
+                };
+                Some((identifier, usage))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let previous_limited =
+            std::mem::replace(&mut self.limited_capabilities, limited_capabilities);
+
+        match self.resolve_runtime(&capabilities) {
+            Ok((runtime, limited)) => {
+                self.runtime_capabilities = capabilities;
+                self.apply(runtime, limited);
+                Ok(())
+            }
+            Err(e) => {
+                self.limited_capabilities = previous_limited;
+                Err(e)
+            }
         }
+    }
 
-        // allowed commands
-        for (cmd_key, resolved_cmds) in resolved.allowed_commands {
-            // fill command scope
-            for resolved_cmd in &resolved_cmds {
-                if let Some(scope_id) = resolved_cmd.scope_id {
-                    let command_scope = resolved.command_scope.get(&scope_id).unwrap();
-
-                    let command_scope_entry = self
-                        .scope_manager
-                        .command_scope
-                        .entry(scope_id)
-                        .or_default();
-                    command_scope_entry
-                        .allow
-                        .extend(command_scope.allow.clone());
-                    command_scope_entry.deny.extend(command_scope.deny.clone());
-
-                    self.scope_manager.command_cache.remove(&scope_id);
-                }
+    /// Resolves the runtime capabilities, each limited capability on its own
+    /// so its commands can be checked against its usage state.
+    fn resolve_runtime(
+        &self,
+        capabilities: &BTreeMap<String, Capability>,
+    ) -> crate::Result<(Resolved, Vec<(String, Resolved)>)> {
+        let (limited, unlimited): (BTreeMap<_, _>, BTreeMap<_, _>) = capabilities
+            .clone()
+            .into_iter()
+            .partition(|(identifier, _)| self.limited_capabilities.contains_key(identifier));
+
+        let runtime = Resolved::resolve(&self.acl, unlimited, Target::current())?;
+        let limited = limited
+            .into_iter()
+            .map(|(identifier, capability)| {
+                let capabilities = BTreeMap::from([(identifier.clone(), capability)]);
+                Ok((
+                    identifier,
+                    Resolved::resolve(&self.acl, capabilities, Target::current())?,
+                ))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok((runtime, limited))
+    }
+
+    /// Merges the resolved runtime capabilities with the base ACL and clears the scope caches.
+    fn apply(&mut self, runtime: Resolved, limited: Vec<(String, Resolved)>) {
+        let mut allowed_commands = self.base.allowed_commands.clone();
+        let mut denied_commands = self.base.denied_commands.clone();
+        let mut command_scope = self.base.command_scope.clone();
+        let mut global_scope = self.base.global_scope.clone();
+
+        // merges the denied commands and scopes, returning the allowed commands
+        let mut merge = |resolved: Resolved| {
+            // the resolved scope ids start at 1, move them after the existing ones
+            let scope_id_offset = command_scope.keys().max().copied().unwrap_or_default();
+            let offset_scope_ids = |commands: BTreeMap<String, Vec<ResolvedCommand>>| {
+                commands
+                    .into_iter()
+                    .map(|(cmd_key, mut resolved_cmds)| {
+                        for command in &mut resolved_cmds {
+                            if let Some(scope_id) = &mut command.scope_id {
+                                *scope_id += scope_id_offset;
+                            }
+                        }
+                        (cmd_key, resolved_cmds)
+                    })
+                    .collect::<BTreeMap<_, _>>()
+            };
+
+            for (cmd_key, resolved_cmds) in offset_scope_ids(resolved.denied_commands) {
+                denied_commands
+                    .entry(cmd_key)
+                    .or_default()
+                    .extend(resolved_cmds);
+            }
+            for (scope_id, scope) in resolved.command_scope {
+                command_scope.insert(scope_id + scope_id_offset, scope);
+            }
+            for (plugin, scope) in resolved.global_scope {
+                let global_scope_entry = global_scope.entry(plugin).or_default();
+                global_scope_entry.allow.extend(scope.allow);
+                global_scope_entry.deny.extend(scope.deny);
             }
+            offset_scope_ids(resolved.allowed_commands)
+        };
 
-            let entry = self.allowed_commands.entry(cmd_key).or_default();
-            entry.extend(resolved_cmds);
+        for (cmd_key, resolved_cmds) in merge(runtime) {
+            allowed_commands
+                .entry(cmd_key)
+                .or_default()
+                .extend(resolved_cmds);
         }
+        let limited_commands = limited
+            .into_iter()
+            .map(|(identifier, resolved)| (identifier, merge(resolved)))
+            .collect();
 
-        Ok(())
+        self.allowed_commands = allowed_commands;
+        self.limited_commands = limited_commands;
+        self.denied_commands = denied_commands;
+        self.scope_manager = ScopeManager {
+            command_cache: command_scope
+                .keys()
+                .map(|key| (*key, <TypeMap![Send + Sync]>::new()))
+                .collect(),
+            command_scope,
+            global_scope,
+            global_scope_cache: Default::default(),
+        };
     }
 
     #[cfg(debug_assertions)]
@@ -481,6 +858,91 @@ impl RuntimeAuthority {
         }
     }
 
+    /// Explains the access decision for the given IPC execution,
+    /// listing every capability rule defined for the command and whether it matched.
+    ///
+    /// The decision is the same made by [`Self::resolve_access`].
+    pub fn explain(
+        &self,
+        command: &str,
+        window: &str,
+        webview: &str,
+        origin: &Origin,
+    ) -> AccessReport {
+        let denied = self.denied_commands.get(command);
+        let allowed = self.allowed_commands.get(command);
+        let rules = denied
+            .into_iter()
+            .flatten()
+            .map(|cmd| AccessRule::new(cmd, true, window, webview, origin))
+            .chain(
+                allowed
+                    .into_iter()
+                    .flatten()
+                    .map(|cmd| AccessRule::new(cmd, false, window, webview, origin)),
+            )
+            .chain(
+                self.limited_commands
+                    .iter()
+                    .filter_map(|(identifier, commands)| {
+                        commands.get(command).map(|resolved| (identifier, resolved))
+                    })
+                    .flat_map(|(identifier, resolved)| {
+                        let expired = !self
+                            .limited_capabilities
+                            .get(identifier)
+                            .is_some_and(CapabilityUsage::is_active);
+                        resolved.iter().map(move |cmd| AccessRule {
+                            capability: Some(identifier.clone()),
+                            expired,
+                            ..AccessRule::new(cmd, false, window, webview, origin)
+                        })
+                    }),
+            )
+            .collect::<Vec<_>>();
+
+        // like `resolve_access`, a denied command is denied regardless of the matches
+        let decision = if denied.is_some() {
+            AccessDecision::Denied
+        } else if rules.iter().any(AccessRule::matches) {
+            AccessDecision::Allowed
+        } else {
+            AccessDecision::NotAllowed
+        };
+
+        AccessReport {
+            command: command.into(),
+            window: window.into(),
+            webview: webview.into(),
+            origin: origin.to_string(),
+            decision,
+            rules,
+        }
+    }
+
+    /// Logs the [`Self::explain`] report of the given IPC execution
+    /// with the `tauri::acl` target at the debug level, if enabled.
+    pub(crate) fn log_access(&self, command: &str, window: &str, webview: &str, origin: &Origin) {
+        #[cfg(feature = "tracing")]
+        if tracing::enabled!(target: "tauri::acl", tracing::Level::DEBUG) {
+            let report = self.explain(command, window, webview, origin);
+            tracing::debug!(
+                target: "tauri::acl",
+                command,
+                window,
+                webview,
+                origin = %origin,
+                decision = %report.decision,
+                "{report}"
+            );
+        }
+        #[cfg(not(feature = "tracing"))]
+        if log::log_enabled!(target: "tauri::acl", log::Level::Debug) {
+            let report = self.explain(command, window, webview, origin);
+            log::debug!(target: "tauri::acl", "{report}");
+        }
+    }
+
     /// Checks if the given IPC execution is allowed and returns the [`ResolvedCommand`] if it is.
     pub fn resolve_access(
         &self,
@@ -497,7 +959,7 @@ impl RuntimeAuthority {
         {
             None
         } else {
-            self.allowed_commands.get(command).and_then(|resolved| {
+            let filter = |resolved: &Vec<ResolvedCommand>| {
                 let resolved_cmds = resolved
                     .iter()
                     .filter(|cmd| {
@@ -512,7 +974,23 @@ impl RuntimeAuthority {
                 } else {
                     Some(resolved_cmds)
                 }
-            })
+            };
+
+            self.allowed_commands
+                .get(command)
+                .and_then(filter)
+                .or_else(|| {
+                    // limited capabilities are only used when no other capability allows the command
+                    self.limited_commands
+                        .iter()
+                        .find_map(|(identifier, commands)| {
+                            let resolved_cmds = commands.get(command).and_then(filter)?;
+                            self.limited_capabilities
+                                .get(identifier)
+                                .is_some_and(CapabilityUsage::consume)
+                                .then_some(resolved_cmds)
+                        })
+                })
         }
     }
 }
@@ -748,18 +1226,161 @@ impl ScopeManager {
     }
 }
 
+/// A watcher of the capability files, stopped when dropped.
+#[cfg(all(dev, desktop))]
+pub(crate) type CapabilityWatcher =
+    notify_debouncer_mini::Debouncer<notify_debouncer_mini::notify::RecommendedWatcher>;
+
+/// Watches the capability files of the app and applies their changes without restarting it.
+///
+/// The files are read from the `capabilities` folder of the `CARGO_MANIFEST_DIR`,
+/// which is set when the app runs with `cargo run` or `tauri dev`.
+/// The changes are applied until the returned watcher is dropped.
+#[cfg(all(dev, desktop))]
+pub(crate) fn watch_capabilities<R: Runtime>(app: AppHandle<R>) -> Option<CapabilityWatcher> {
+    use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebounceEventResult};
+
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")?;
+    let capabilities_dir = std::path::Path::new(&manifest_dir).join("capabilities");
+    if !capabilities_dir.is_dir() {
+        return None;
+    }
+    let pattern = capabilities_dir
+        .join("**")
+        .join("*")
+        .to_string_lossy()
+        .into_owned();
+
+    let mut watcher = new_debouncer(
+        std::time::Duration::from_millis(500),
+        move |result: DebounceEventResult| {
+            let Ok(events) = result else {
+                return;
+            };
+            if !events.iter().any(|event| is_capability_file(&event.path)) {
+                return;
+            }
+
+            let result = load_capabilities(app.config(), capability_files(&pattern).iter())
+                .and_then(|capabilities| {
+                    app.manager()
+                        .runtime_authority
+                        .lock()
+                        .unwrap()
+                        .reload_capabilities(capabilities)
+                });
+            match result {
+                Ok(()) => log::info!("reloaded the capability files"),
+                Err(e) => log::error!("failed to reload the capability files: {e}"),
+            }
+        },
+    )
+    .map_err(|e| log::error!("failed to watch the capability files: {e}"))
+    .ok()?;
+
+    watcher
+        .watcher()
+        .watch(&capabilities_dir, RecursiveMode::Recursive)
+        .map_err(|e| log::error!("failed to watch the capability files: {e}"))
+        .ok()?;
+
+    Some(watcher)
+}
+
+/// The capability files matching the pattern.
+#[cfg(all(dev, desktop))]
+fn capability_files(pattern: &str) -> Vec<std::path::PathBuf> {
+    glob::glob(pattern)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|path| is_capability_file(path))
+        .collect()
+}
+
+/// Whether the path is a capability file, skipping the generated schemas.
+#[cfg(all(dev, desktop))]
+fn is_capability_file(path: &std::path::Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("json" | "toml")
+    ) && path.parent().and_then(|p| p.file_name()) != Some("schemas".as_ref())
+}
+
+/// Loads the capability files, keeping the ones enabled in the configuration like the build does.
+#[cfg(all(dev, desktop))]
+fn load_capabilities<'a>(
+    config: &crate::Config,
+    paths: impl Iterator<Item = &'a std::path::PathBuf>,
+) -> crate::Result<BTreeMap<String, Capability>> {
+    use crate::utils::config::CapabilityEntry;
+
+    let mut capabilities_from_files = BTreeMap::new();
+    for path in paths {
+        match CapabilityFile::load(path)? {
+            CapabilityFile::Capability(capability) => {
+                capabilities_from_files.insert(capability.identifier.clone(), capability);
+            }
+            CapabilityFile::List(capabilities) | CapabilityFile::NamedList { capabilities } => {
+                for capability in capabilities {
+                    capabilities_from_files.insert(capability.identifier.clone(), capability);
+                }
+            }
+        }
+    }
+
+    if config.app.security.capabilities.is_empty() {
+        return Ok(capabilities_from_files);
+    }
+
+    let mut capabilities = BTreeMap::new();
+    for capability_entry in &config.app.security.capabilities {
+        match capability_entry {
+            CapabilityEntry::Inlined(capability) => {
+                capabilities.insert(capability.identifier.clone(), capability.clone());
+            }
+            CapabilityEntry::Reference(id) => {
+                if let Some(capability) = capabilities_from_files.remove(id) {
+                    capabilities.insert(id.clone(), capability);
+                }
+            }
+        }
+    }
+    Ok(capabilities)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use glob::Pattern;
     use tauri_utils::acl::{
+        manifest::Manifest,
         resolved::{Resolved, ResolvedCommand},
         ExecutionContext,
     };
 
-    use crate::ipc::Origin;
+    use crate::ipc::{AccessDecision, CapabilityBuilder, Origin};
 
     use super::RuntimeAuthority;
 
+    /// An authority without capabilities that knows the `allow-ping` permission of a `test` plugin.
+    fn ping_authority() -> RuntimeAuthority {
+        let manifest: Manifest = serde_json::from_value(serde_json::json!({
+            "default_permission": null,
+            "permissions": {
+                "allow-ping": { "identifier": "allow-ping", "commands": { "allow": ["ping"] } }
+            },
+            "permission_sets": {},
+            "global_scope_schema": null
+        }))
+        .unwrap();
+        RuntimeAuthority::new(
+            [("test".to_string(), manifest)].into_iter().collect(),
+            Resolved::default(),
+        )
+    }
+
     #[test]
     fn window_glob_pattern_matches() {
         let command = "my-command";
@@ -975,4 +1596,130 @@ mod tests {
             .resolve_access(command, window, webview, &Origin::Local)
             .is_none());
     }
+
+    #[test]
+    fn removes_runtime_capabilities() {
+        let mut authority = ping_authority();
+        let command = "plugin:test|ping";
+
+        for (identifier, window) in [("admin", "main"), ("other", "other")] {
+            authority
+                .add_capability(
+                    CapabilityBuilder::new(identifier)
+                        .window(window)
+                        .permission("test:allow-ping"),
+                )
+                .unwrap();
+        }
+        assert!(authority
+            .resolve_access(command, "main", "main", &Origin::Local)
+            .is_some());
+
+        authority.remove_capability("admin").unwrap();
+        assert!(authority
+            .resolve_access(command, "main", "main", &Origin::Local)
+            .is_none());
+        assert!(authority
+            .resolve_access(command, "other", "other", &Origin::Local)
+            .is_some());
+        assert!(matches!(
+            authority.remove_capability("admin"),
+            Err(crate::Error::CapabilityNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn limits_runtime_capabilities() {
+        let mut authority = ping_authority();
+        let command = "plugin:test|ping";
+
+        authority
+            .add_capability(
+                CapabilityBuilder::new("once")
+                    .window("main")
+                    .permission("test:allow-ping")
+                    .max_uses(1),
+            )
+            .unwrap();
+        authority
+            .add_capability(
+                CapabilityBuilder::new("expired")
+                    .window("other")
+                    .permission("test:allow-ping")
+                    .expires_after(Duration::ZERO),
+            )
+            .unwrap();
+
+        assert!(authority
+            .resolve_access(command, "main", "main", &Origin::Local)
+            .is_some());
+        assert!(authority
+            .resolve_access(command, "main", "main", &Origin::Local)
+            .is_none());
+        assert!(authority
+            .resolve_access(command, "other", "other", &Origin::Local)
+            .is_none());
+        assert_eq!(
+            authority
+                .explain(command, "main", "main", &Origin::Local)
+                .decision,
+            AccessDecision::NotAllowed
+        );
+
+        let mut expired = authority.remove_expired_capabilities();
+        expired.sort();
+        assert_eq!(expired, vec!["expired".to_string(), "once".to_string()]);
+        assert!(authority.remove_expired_capabilities().is_empty());
+        assert!(matches!(
+            authority.remove_capability("once"),
+            Err(crate::Error::CapabilityNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn explains_access() {
+        let command = "my-command";
+        let allowed_commands = [(
+            command.to_string(),
+            vec![
+                ResolvedCommand {
+                    windows: vec![Pattern::new("main").unwrap()],
+                    ..Default::default()
+                },
+                ResolvedCommand {
+                    context: ExecutionContext::Remote {
+                        url: "https://tauri.app".parse().unwrap(),
+                    },
+                    windows: vec![Pattern::new("*").unwrap()],
+                    ..Default::default()
+                },
+            ],
+        )]
+        .into_iter()
+        .collect();
+        let authority = RuntimeAuthority::new(
+            Default::default(),
+            Resolved {
+                allowed_commands,
+                ..Default::default()
+            },
+        );
+
+        let report = authority.explain(command, "main", "main", &Origin::Local);
+        assert_eq!(report.decision, AccessDecision::Allowed);
+        assert!(report.rules[0].matches());
+        assert!(!report.rules[1].origin_matches);
+
+        let report = authority.explain(command, "other", "other", &Origin::Local);
+        assert_eq!(report.decision, AccessDecision::NotAllowed);
+        assert!(!report.rules[0].window_matches);
+        assert!(report.rules[1].window_matches && !report.rules[1].origin_matches);
+        assert!(authority
+            .resolve_access(command, "other", "other", &Origin::Local)
+            .is_none());
+
+        let report = authority.explain("unknown", "main", "main", &Origin::Local);
+        assert_eq!(report.decision, AccessDecision::NotAllowed);
+        assert!(report.rules.is_empty());
+    }
 }
@@ -0,0 +1,511 @@
+//! Generation of TypeScript bindings for commands.
+//!
+//! The argument and return types of the commands are described with [`schemars`],
+//! so every type sent through the IPC should implement [`JsonSchema`].
+//! Arguments and return types sent through the IPC without a schema are typed as `unknown`.
+//! Arguments that are not deserialized from the invoke payload, such as [`crate::State`] or [`crate::Webview`],
+//! are injected by Tauri and are not part of the bindings.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! #[derive(serde::Serialize, schemars::JsonSchema)]
+//! struct User {
+//!   name: String,
+//! }
+//!
+//! #[tauri::command]
+//! fn get_user(id: u32) -> Result<User, String> {
+//!   Ok(User { name: format!("user {id}") })
+//! }
+//!
+//! fn main() {
+//!   #[cfg(debug_assertions)]
+//!   tauri::ipc::bindings::export(
+//!     &tauri::generate_bindings![get_user],
+//!     "../src/bindings.ts",
+//!   )
+//!   .expect("failed to export the command bindings");
+//!
+//!   tauri::Builder::default()
+//!     .invoke_handler(tauri::generate_handler![get_user]);
+//! }
+//! ```
+
+use std::{fmt, path::Path};
+
+use heck::ToLowerCamelCase;
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+
+use super::Channel;
+
+/// The schema extension that marks a [`Channel`] argument.
+const CHANNEL_EXTENSION: &str = "x-tauri-channel";
+
+type ArgsFn = fn(&mut SchemaGenerator) -> Vec<(&'static str, Option<Schema>)>;
+type ReturnsFn = fn(&mut SchemaGenerator) -> Option<Schema>;
+
+/// The signature of a command, created by [`crate::generate_bindings!`].
+pub struct CommandBindings {
+    name: &'static str,
+    plugin: Option<&'static str>,
+    args: ArgsFn,
+    returns: ReturnsFn,
+}
+
+impl fmt::Debug for CommandBindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandBindings")
+            .field("name", &self.name)
+            .field("plugin", &self.plugin)
+            .finish()
+    }
+}
+
+impl CommandBindings {
+    /// The command name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The name of the plugin that defines the command.
+    pub fn plugin(&self) -> Option<&'static str> {
+        self.plugin
+    }
+}
+
+#[doc(hidden)]
+pub mod private {
+    use std::marker::PhantomData;
+
+    use schemars::{
+        schema::{Schema, SchemaObject},
+        JsonSchema,
+    };
+    use serde::Deserialize;
+
+    use super::{ArgsFn, CommandBindings, ReturnsFn};
+
+    pub use schemars::gen::SchemaGenerator;
+
+    pub fn command_bindings(
+        name: &'static str,
+        plugin: Option<&'static str>,
+        args: ArgsFn,
+        returns: ReturnsFn,
+    ) -> CommandBindings {
+        CommandBindings {
+            name,
+            plugin,
+            args,
+            returns,
+        }
+    }
+
+    pub trait SchemaKind {
+        fn schema(&self, generator: &mut SchemaGenerator) -> Option<Schema>;
+    }
+
+    impl<T: JsonSchema> SchemaKind for &&PhantomData<T> {
+        #[inline(always)]
+        fn schema(&self, generator: &mut SchemaGenerator) -> Option<Schema> {
+            Some(generator.subschema_for::<T>())
+        }
+    }
+
+    /// An argument sent by the webview without a schema, typed as `unknown`.
+    pub trait UnknownSchemaKind {
+        #[inline(always)]
+        fn schema(&self, _generator: &mut SchemaGenerator) -> Option<Schema> {
+            Some(SchemaObject::default().into())
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> UnknownSchemaKind for &PhantomData<T> {}
+
+    /// An argument injected by Tauri.
+    pub trait NoSchemaKind {
+        #[inline(always)]
+        fn schema(&self, _generator: &mut SchemaGenerator) -> Option<Schema> {
+            None
+        }
+    }
+
+    impl<T> NoSchemaKind for PhantomData<T> {}
+
+    /// A `Result` return type, whose error is sent to the error callback.
+    pub trait ResultReturnKind {
+        fn returns_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema>;
+    }
+
+    impl<T: JsonSchema, E> ResultReturnKind for &&PhantomData<Result<T, E>> {
+        #[inline(always)]
+        fn returns_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema> {
+            Some(generator.subschema_for::<T>())
+        }
+    }
+
+    pub trait ReturnKind {
+        fn returns_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema>;
+    }
+
+    impl<T: JsonSchema> ReturnKind for &PhantomData<T> {
+        #[inline(always)]
+        fn returns_schema(&self, generator: &mut SchemaGenerator) -> Option<Schema> {
+            Some(generator.subschema_for::<T>())
+        }
+    }
+
+    pub trait NoReturnKind {
+        #[inline(always)]
+        fn returns_schema(&self, _generator: &mut SchemaGenerator) -> Option<Schema> {
+            None
+        }
+    }
+
+    impl<T> NoReturnKind for PhantomData<T> {}
+}
+
+impl<TSend: JsonSchema> JsonSchema for Channel<TSend> {
+    fn schema_name() -> String {
+        format!("Channel_for_{}", TSend::schema_name())
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        };
+        schema.extensions.insert(
+            CHANNEL_EXTENSION.into(),
+            serde_json::to_value(generator.subschema_for::<TSend>()).unwrap_or_default(),
+        );
+        schema.into()
+    }
+}
+
+/// Generates a TypeScript module with a typed function for each command and the types they reference.
+pub fn to_typescript(commands: &[CommandBindings]) -> String {
+    let mut generator = SchemaSettings::draft07().into_generator();
+
+    let mut functions = Vec::new();
+    for command in commands {
+        let args = (command.args)(&mut generator)
+            .into_iter()
+            .filter_map(|(key, schema)| schema.map(|schema| (key, schema)))
+            .collect::<Vec<_>>();
+        let returns = (command.returns)(&mut generator)
+            .map(|schema| ts_type(&schema))
+            .unwrap_or_else(|| "unknown".into());
+        let returns = if returns == "null" {
+            "void".into()
+        } else {
+            returns
+        };
+
+        let cmd = match command.plugin {
+            Some(plugin) => format!("plugin:{plugin}|{}", command.name),
+            None => command.name.into(),
+        };
+
+        let function = if args.is_empty() {
+            format!(
+                "export function {}(options?: InvokeOptions): Promise<{returns}> {{\n  return invoke('{cmd}', {{}}, options)\n}}",
+                command.name.to_lower_camel_case()
+            )
+        } else {
+            let optional = args.iter().all(|(_, schema)| is_nullable(schema));
+            let args = args
+                .iter()
+                .map(|(key, schema)| property(key, schema, is_nullable(schema)))
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!(
+                "export function {}(args: {{ {args} }}{}, options?: InvokeOptions): Promise<{returns}> {{\n  return invoke('{cmd}', args, options)\n}}",
+                command.name.to_lower_camel_case(),
+                if optional { " = {}" } else { "" },
+            )
+        };
+        functions.push(function);
+    }
+
+    let mut definitions = generator.definitions().iter().collect::<Vec<_>>();
+    definitions.sort_by_key(|(name, _)| *name);
+    let types = definitions
+        .into_iter()
+        .map(|(name, schema)| {
+            let description = match schema {
+                Schema::Object(SchemaObject {
+                    metadata: Some(metadata),
+                    ..
+                }) => metadata.description.as_deref(),
+                _ => None,
+            };
+            format!(
+                "{}export type {name} = {}",
+                description
+                    .map(|d| format!("/** {} */\n", d.replace("*/", "*\\/")))
+                    .unwrap_or_default(),
+                ts_type(schema)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut module = String::from(
+        "// This file was generated by `tauri::ipc::bindings`, do not edit it manually.\n\n",
+    );
+    module.push_str("import { invoke } from '@tauri-apps/api/core'\n");
+    let channel_import = if functions
+        .iter()
+        .chain(types.iter())
+        .any(|s| s.contains("Channel<"))
+    {
+        "Channel, "
+    } else {
+        ""
+    };
+    module.push_str(&format!(
+        "import type {{ {channel_import}InvokeOptions }} from '@tauri-apps/api/core'\n"
+    ));
+
+    for item in types.iter().chain(functions.iter()) {
+        module.push('\n');
+        module.push_str(item);
+        module.push('\n');
+    }
+
+    module
+}
+
+/// Writes the TypeScript bindings of the commands to the given path, see [`to_typescript`].
+///
+/// The file is only written if its content changed, so file watchers are not triggered on every run.
+pub fn export<P: AsRef<Path>>(commands: &[CommandBindings], path: P) -> crate::Result<()> {
+    let path = path.as_ref();
+    let module = to_typescript(commands);
+    if std::fs::read_to_string(path).ok().as_deref() != Some(module.as_str()) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, module)?;
+    }
+    Ok(())
+}
+
+fn is_nullable(schema: &Schema) -> bool {
+    match schema {
+        Schema::Bool(accepts) => *accepts,
+        Schema::Object(object) => {
+            object
+                .instance_type
+                .as_ref()
+                .map(|t| t.contains(&InstanceType::Null))
+                .unwrap_or_default()
+                || object
+                    .subschemas
+                    .as_ref()
+                    .and_then(|s| s.any_of.as_ref().or(s.one_of.as_ref()))
+                    .map(|schemas| schemas.iter().any(is_nullable))
+                    .unwrap_or_default()
+        }
+    }
+}
+
+fn property(key: &str, schema: &Schema, optional: bool) -> String {
+    let is_identifier = key.chars().enumerate().all(|(i, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+    }) && !key.is_empty();
+    format!(
+        "{}{}: {}",
+        if is_identifier {
+            key.to_string()
+        } else {
+            serde_json::Value::String(key.into()).to_string()
+        },
+        if optional { "?" } else { "" },
+        ts_type(schema)
+    )
+}
+
+fn join(schemas: &[Schema], separator: &str) -> String {
+    let mut types = Vec::new();
+    for ty in schemas.iter().map(ts_type) {
+        if !types.contains(&ty) {
+            types.push(ty);
+        }
+    }
+    types.join(separator)
+}
+
+fn ts_type(schema: &Schema) -> String {
+    let object = match schema {
+        Schema::Bool(true) => return "unknown".into(),
+        Schema::Bool(false) => return "never".into(),
+        Schema::Object(object) => object,
+    };
+
+    if let Some(inner) = object.extensions.get(CHANNEL_EXTENSION) {
+        let inner = serde_json::from_value(inner.clone()).unwrap_or(Schema::Bool(true));
+        return format!("Channel<{}>", ts_type(&inner));
+    }
+
+    if let Some(reference) = &object.reference {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+
+    if let Some(values) = &object.enum_values {
+        return values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    if let Some(value) = &object.const_value {
+        return value.to_string();
+    }
+
+    if let Some(subschemas) = &object.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            return join(all_of, " & ");
+        }
+        if let Some(any_of) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref()) {
+            return join(any_of, " | ");
+        }
+    }
+
+    match &object.instance_type {
+        Some(SingleOrVec::Single(ty)) => instance_type(ty, object),
+        Some(SingleOrVec::Vec(types)) => types
+            .iter()
+            .map(|ty| instance_type(ty, object))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        None => "unknown".into(),
+    }
+}
+
+fn instance_type(ty: &InstanceType, object: &SchemaObject) -> String {
+    match ty {
+        InstanceType::Null => "null".into(),
+        InstanceType::Boolean => "boolean".into(),
+        InstanceType::Integer | InstanceType::Number => "number".into(),
+        InstanceType::String => "string".into(),
+        InstanceType::Array => match object.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => {
+                let item = ts_type(item);
+                if item.contains(' ') {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            Some(SingleOrVec::Vec(items)) => format!(
+                "[{}]",
+                items.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+            ),
+            None => "unknown[]".into(),
+        },
+        InstanceType::Object => {
+            let Some(validation) = &object.object else {
+                return "Record<string, unknown>".into();
+            };
+            if validation.properties.is_empty() {
+                let value = validation
+                    .additional_properties
+                    .as_deref()
+                    .map(ts_type)
+                    .unwrap_or_else(|| "unknown".into());
+                return format!("Record<string, {value}>");
+            }
+            let properties = validation
+                .properties
+                .iter()
+                .map(|(key, schema)| property(key, schema, !validation.required.contains(key)))
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!("{{ {properties} }}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+
+    use crate::ipc::Channel;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct User {
+        name: String,
+        age: Option<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    enum Status {
+        Active,
+        Banned,
+    }
+
+    #[allow(dead_code)]
+    #[crate::command(root = "crate")]
+    fn get_user(_id: u32, _status: Status) -> Result<User, String> {
+        unimplemented!()
+    }
+
+    #[allow(dead_code)]
+    #[crate::command(root = "crate")]
+    fn watch<R: crate::Runtime>(_webview: crate::Webview<R>, _on_event: Channel<User>) {}
+
+    #[allow(dead_code)]
+    #[derive(serde::Deserialize)]
+    struct Filter {
+        name: String,
+    }
+
+    #[allow(dead_code)]
+    #[crate::command(root = "crate")]
+    fn find_user(_filter: Filter) -> crate::Result<User> {
+        unimplemented!()
+    }
+
+    #[test]
+    fn generates_typescript() {
+        let module = super::to_typescript(&crate::generate_bindings![get_user, watch]);
+
+        assert!(
+            module.contains("import type { Channel, InvokeOptions } from '@tauri-apps/api/core'")
+        );
+        assert!(module.contains("export type User = { name: string; age?: number | null }"));
+        assert!(module.contains("export type Status = \"Active\" | \"Banned\""));
+        assert!(module.contains(
+            "export function getUser(args: { id: number; status: Status }, options?: InvokeOptions): Promise<User> {\n  return invoke('get_user', args, options)\n}"
+        ));
+        assert!(module.contains(
+            "export function watch(args: { onEvent: Channel<User> }, options?: InvokeOptions): Promise<void>"
+        ));
+    }
+
+    #[test]
+    fn types_arguments_without_schema_as_unknown() {
+        let module = super::to_typescript(&crate::generate_bindings![find_user]);
+
+        assert!(module.contains(
+            "export function findUser(args: { filter: unknown }, options?: InvokeOptions): Promise<User>"
+        ));
+    }
+}
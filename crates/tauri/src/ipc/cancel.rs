@@ -0,0 +1,201 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::{sync::Notify, task::AbortHandle};
+
+use crate::{
+    command,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    Runtime, State, Webview,
+};
+
+use super::{CommandArg, CommandItem, InvokeError};
+
+pub const IPC_PLUGIN_NAME: &str = "__TAURI_IPC__";
+// TODO: ideally this const references IPC_PLUGIN_NAME
+pub const CANCEL_INVOKE_COMMAND: &str = "plugin:__TAURI_IPC__|cancel";
+
+/// A token that is triggered when an invoke is cancelled,
+/// either by the webview or because the webview navigated or was destroyed.
+///
+/// By default cancelling an invoke aborts the async task running its command.
+/// Commands that take a [`CancellationToken`] argument are not aborted,
+/// and must instead check the token and return early.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::CancellationToken;
+///
+/// #[tauri::command]
+/// async fn export(cancellation: CancellationToken) -> Result<(), String> {
+///   for _ in 0..100 {
+///     if cancellation.is_cancelled() {
+///       // clean up the partially exported data
+///       return Err("export cancelled".into());
+///     }
+///     // export a chunk
+///   }
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<CancellationTokenInner>);
+
+#[derive(Debug, Default)]
+struct CancellationTokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+    // whether the command handles the cancellation itself
+    cooperative: AtomicBool,
+    task: Mutex<Option<AbortHandle>>,
+}
+
+impl CancellationToken {
+    /// Whether the invoke was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Waits until the invoke is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub(crate) fn cancel(&self) {
+        if self.0.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        self.0.notify.notify_waiters();
+
+        if !self.0.cooperative.load(Ordering::SeqCst) {
+            if let Some(task) = self.0.task.lock().unwrap().take() {
+                task.abort();
+            }
+        }
+    }
+
+    /// Sets the task to abort when the invoke is cancelled.
+    pub(crate) fn set_task(&self, task: AbortHandle) {
+        let mut current = self.0.task.lock().unwrap();
+        if self.is_cancelled() {
+            if !self.0.cooperative.load(Ordering::SeqCst) {
+                task.abort();
+            }
+        } else {
+            current.replace(task);
+        }
+    }
+}
+
+impl<'de, R: Runtime> CommandArg<'de, R> for CancellationToken {
+    /// Grabs the [`CancellationToken`] of the invoke, opting out of the task abort.
+    fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+        let token = command.message.cancellation.clone();
+        token.0.cooperative.store(true, Ordering::SeqCst);
+        Ok(token)
+    }
+}
+
+/// Tracks the cancellation token of the invokes that did not respond yet,
+/// keyed by webview label and callback identifier.
+#[derive(Default)]
+pub(crate) struct PendingInvokes(Mutex<HashMap<(String, u32), CancellationToken>>);
+
+impl PendingInvokes {
+    pub(crate) fn insert(&self, webview: &str, id: u32, token: CancellationToken) {
+        self.0.lock().unwrap().insert((webview.into(), id), token);
+    }
+
+    pub(crate) fn remove(&self, webview: &str, id: u32) {
+        self.0.lock().unwrap().remove(&(webview.into(), id));
+    }
+
+    fn cancel(&self, webview: &str, id: u32) {
+        let token = self.0.lock().unwrap().remove(&(webview.into(), id));
+        if let Some(token) = token {
+            token.cancel();
+        }
+    }
+
+    /// Cancels all pending invokes of the webview with the given label.
+    pub(crate) fn cancel_webview(&self, label: &str) {
+        let tokens = {
+            let mut pending = self.0.lock().unwrap();
+            let keys = pending
+                .keys()
+                .filter(|(webview, _)| webview == label)
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.into_iter()
+                .filter_map(|key| pending.remove(&key))
+                .collect::<Vec<_>>()
+        };
+
+        for token in tokens {
+            token.cancel();
+        }
+    }
+}
+
+#[command(root = "crate")]
+fn cancel<R: Runtime>(webview: Webview<R>, pending: State<'_, PendingInvokes>, id: u32) {
+    pending.cancel(webview.label(), id);
+}
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new(IPC_PLUGIN_NAME)
+        .invoke_handler(crate::generate_handler![cancel])
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::CancellationToken;
+
+    #[test]
+    fn cancel_aborts_task() {
+        crate::async_runtime::block_on(async {
+            let token = CancellationToken::default();
+            let task = crate::async_runtime::spawn(std::future::pending::<()>());
+            token.set_task(task.inner().abort_handle());
+
+            token.cancel();
+            token.cancelled().await;
+            assert!(token.is_cancelled());
+            assert!(task.await.is_err());
+        });
+    }
+
+    #[test]
+    fn cooperative_task_is_not_aborted() {
+        crate::async_runtime::block_on(async {
+            let token = CancellationToken::default();
+            token.0.cooperative.store(true, Ordering::SeqCst);
+
+            let token_ = token.clone();
+            let task = crate::async_runtime::spawn(async move {
+                token_.cancelled().await;
+                "cleaned up"
+            });
+            token.set_task(task.inner().abort_handle());
+
+            token.cancel();
+            assert_eq!(task.await.unwrap(), "cleaned up");
+        });
+    }
+}
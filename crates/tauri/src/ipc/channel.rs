@@ -1,17 +1,19 @@
 use std::{
     collections::HashMap,
+    marker::PhantomData,
     str::FromStr,
     sync::{
-        atomic::{AtomicU32, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
         Arc, Mutex,
     },
 };
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
 
 use crate::{
     command,
-    ipc::{CommandArg, CommandItem},
+    ipc::{CommandArg, CommandItem, IpcError},
     plugin::{Builder as PluginBuilder, TauriPlugin},
     Manager, Runtime, State, Webview,
 };
@@ -27,18 +29,99 @@ pub(crate) const CHANNEL_ID_HEADER_NAME: &str = "Tauri-Channel-Id";
 static CHANNEL_COUNTER: AtomicU32 = AtomicU32::new(0);
 static CHANNEL_DATA_COUNTER: AtomicU32 = AtomicU32::new(0);
 
+type OnMessage =
+    dyn Fn(InvokeBody, Option<OwnedSemaphorePermit>) -> crate::Result<()> + Send + Sync;
+type OnClose = dyn Fn() -> crate::Result<()> + Send + Sync;
+
 /// Maps a channel id to a pending data that must be send to the JavaScript side via the IPC.
 #[derive(Default, Clone)]
-pub struct ChannelDataIpcQueue(pub(crate) Arc<Mutex<HashMap<u32, InvokeBody>>>);
+pub struct ChannelDataIpcQueue(pub(crate) Arc<Mutex<HashMap<u32, ChannelData>>>);
+
+impl ChannelDataIpcQueue {
+    fn push(&self, webview: &str, body: InvokeBody, permit: Option<OwnedSemaphorePermit>) -> u32 {
+        let data_id = CHANNEL_DATA_COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.0.lock().unwrap().insert(
+            data_id,
+            ChannelData {
+                webview: webview.into(),
+                body,
+                _permit: permit,
+            },
+        );
+        data_id
+    }
+
+    /// Drops all pending data that was queued for the webview with the given label,
+    /// releasing the capacity held by bounded channels.
+    pub(crate) fn clear_webview(&self, label: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|_, data| data.webview != label);
+    }
+}
+
+/// Data waiting to be fetched by a webview.
+pub(crate) struct ChannelData {
+    webview: String,
+    body: InvokeBody,
+    // released when the webview fetches the data or it is discarded
+    _permit: Option<OwnedSemaphorePermit>,
+}
 
 /// An IPC channel.
-#[derive(Clone)]
-pub struct Channel {
+///
+/// The `TSend` type parameter defines the type of the data sent through the channel,
+/// so a command and its JavaScript consumer share the same payload contract.
+/// It defaults to [`InvokeBody`], which accepts any raw or JSON payload.
+///
+/// Messages are delivered to the JavaScript `onmessage` handler in the order they were sent.
+/// By default a channel is unbounded; see [`Self::bounded`] to limit how much data can be
+/// waiting for the webview to consume it.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::Channel;
+///
+/// #[derive(serde::Serialize)]
+/// #[serde(rename_all = "camelCase", tag = "event", content = "data")]
+/// enum DownloadEvent {
+///   Started { content_length: u64 },
+///   Progress { chunk_length: usize },
+///   Finished,
+/// }
+///
+/// #[tauri::command]
+/// fn download(on_event: Channel<DownloadEvent>) {
+///   on_event.send(DownloadEvent::Started { content_length: 1000 }).unwrap();
+///   on_event.send(DownloadEvent::Progress { chunk_length: 1000 }).unwrap();
+///   on_event.send(DownloadEvent::Finished).unwrap();
+/// }
+/// ```
+pub struct Channel<TSend = InvokeBody> {
     id: u32,
-    on_message: Arc<dyn Fn(InvokeBody) -> crate::Result<()> + Send + Sync>,
+    on_message: Arc<OnMessage>,
+    on_close: Option<Arc<OnClose>>,
+    capacity: Option<Arc<Semaphore>>,
+    closed: Arc<AtomicBool>,
+    phantom: PhantomData<fn(TSend)>,
 }
 
-impl Serialize for Channel {
+impl<TSend> Clone for Channel<TSend> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            on_message: self.on_message.clone(),
+            on_close: self.on_close.clone(),
+            capacity: self.capacity.clone(),
+            closed: self.closed.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<TSend> Serialize for Channel<TSend> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -84,19 +167,18 @@ impl FromStr for JavaScriptChannelId {
 
 impl JavaScriptChannelId {
     /// Gets a [`Channel`] for this channel ID on the given [`Webview`].
-    pub fn channel_on<R: Runtime>(&self, webview: Webview<R>) -> Channel {
+    pub fn channel_on<R: Runtime, TSend>(&self, webview: Webview<R>) -> Channel<TSend> {
         let callback_id = self.0;
-        let counter = AtomicUsize::new(0);
+        // the message index is used by the JS side to preserve message order
+        let counter = Arc::new(AtomicUsize::new(0));
+        let close_counter = counter.clone();
+        let close_webview = webview.clone();
 
-        Channel::new_with_id(callback_id.0, move |body| {
-            let data_id = CHANNEL_DATA_COUNTER.fetch_add(1, Ordering::Relaxed);
-
-            webview
-                .state::<ChannelDataIpcQueue>()
-                .0
-                .lock()
-                .unwrap()
-                .insert(data_id, body);
+        let mut channel = Channel::new_with_id(callback_id.0, move |body, permit| {
+            let data_id =
+                webview
+                    .state::<ChannelDataIpcQueue>()
+                    .push(webview.label(), body, permit);
 
             let i = counter.fetch_add(1, Ordering::Relaxed);
 
@@ -106,7 +188,17 @@ impl JavaScriptChannelId {
       ))?;
 
             Ok(())
-        })
+        });
+
+        channel.on_close.replace(Arc::new(move || {
+            let i = close_counter.fetch_add(1, Ordering::Relaxed);
+            close_webview.eval(&format!(
+                "window['_' + {}]?.({{ end: true, id: {i} }})",
+                callback_id.0
+            ))
+        }));
+
+        channel
     }
 }
 
@@ -124,15 +216,21 @@ impl<'de> Deserialize<'de> for JavaScriptChannelId {
     }
 }
 
-impl Channel {
+impl<TSend> Channel<TSend> {
     /// Creates a new channel with the given message handler.
     pub fn new<F: Fn(InvokeBody) -> crate::Result<()> + Send + Sync + 'static>(
         on_message: F,
     ) -> Self {
-        Self::new_with_id(CHANNEL_COUNTER.fetch_add(1, Ordering::Relaxed), on_message)
+        Self::new_with_id(
+            CHANNEL_COUNTER.fetch_add(1, Ordering::Relaxed),
+            // the message is consumed as soon as the handler returns
+            move |body, _permit| on_message(body),
+        )
     }
 
-    fn new_with_id<F: Fn(InvokeBody) -> crate::Result<()> + Send + Sync + 'static>(
+    fn new_with_id<
+        F: Fn(InvokeBody, Option<OwnedSemaphorePermit>) -> crate::Result<()> + Send + Sync + 'static,
+    >(
         id: u32,
         on_message: F,
     ) -> Self {
@@ -140,24 +238,31 @@ impl Channel {
         let channel = Self {
             id,
             on_message: Arc::new(on_message),
+            on_close: None,
+            capacity: None,
+            closed: Default::default(),
+            phantom: PhantomData,
         };
 
         #[cfg(mobile)]
-        crate::plugin::mobile::register_channel(channel.clone());
+        crate::plugin::mobile::register_channel(Channel {
+            id,
+            on_message: channel.on_message.clone(),
+            on_close: None,
+            capacity: None,
+            closed: channel.closed.clone(),
+            phantom: PhantomData,
+        });
 
         channel
     }
 
     pub(crate) fn from_callback_fn<R: Runtime>(webview: Webview<R>, callback: CallbackFn) -> Self {
-        Channel::new_with_id(callback.0, move |body| {
-            let data_id = CHANNEL_DATA_COUNTER.fetch_add(1, Ordering::Relaxed);
-
-            webview
-                .state::<ChannelDataIpcQueue>()
-                .0
-                .lock()
-                .unwrap()
-                .insert(data_id, body);
+        Self::new_with_id(callback.0, move |body, permit| {
+            let data_id =
+                webview
+                    .state::<ChannelDataIpcQueue>()
+                    .push(webview.label(), body, permit);
 
             webview.eval(&format!(
         "window.__TAURI_INTERNALS__.invoke('{FETCH_CHANNEL_DATA_COMMAND}', null, {{ headers: {{ '{CHANNEL_ID_HEADER_NAME}': '{data_id}' }} }}).then((response) => window['_' + {}](response)).catch(console.error)",
@@ -168,26 +273,132 @@ impl Channel {
         })
     }
 
+    /// Limits the amount of messages that can be waiting for the webview to consume them.
+    ///
+    /// When the limit is reached, [`Self::send`] returns [`crate::Error::ChannelFull`]
+    /// and [`Self::send_async`] waits until the webview catches up.
+    /// Data queued for a webview is discarded, and its capacity released,
+    /// when the webview navigates or is destroyed.
+    ///
+    /// The limit is shared by the clones created after calling this function.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri::ipc::Channel;
+    ///
+    /// #[tauri::command]
+    /// async fn read_file(path: std::path::PathBuf, on_chunk: Channel<Vec<u8>>) -> tauri::Result<()> {
+    ///   let on_chunk = on_chunk.bounded(8);
+    ///   let data = std::fs::read(path)?;
+    ///   for chunk in data.chunks(64 * 1024) {
+    ///     on_chunk.send_async(chunk.to_vec()).await?;
+    ///   }
+    ///   on_chunk.close()
+    /// }
+    /// ```
+    pub fn bounded(mut self, capacity: usize) -> Self {
+        self.capacity.replace(Arc::new(Semaphore::new(capacity)));
+        self
+    }
+
     /// The channel identifier.
     pub fn id(&self) -> u32 {
         self.id
     }
 
+    /// Whether [`Self::close`] was called on this channel or one of its clones.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
     /// Sends the given data through the channel.
-    pub fn send<T: IpcResponse>(&self, data: T) -> crate::Result<()> {
+    ///
+    /// Returns [`crate::Error::ChannelFull`] if this is a [bounded](Self::bounded) channel without free capacity.
+    ///
+    /// The data must match the channel payload type:
+    ///
+    /// ```compile_fail
+    /// use tauri::ipc::Channel;
+    ///
+    /// let channel: Channel<u32> = Channel::new(|_| Ok(()));
+    /// channel.send("not a number").unwrap();
+    /// ```
+    pub fn send(&self, data: TSend) -> crate::Result<()>
+    where
+        TSend: IpcResponse,
+    {
+        if self.is_closed() {
+            return Err(crate::Error::ChannelClosed);
+        }
+
+        let permit = match &self.capacity {
+            Some(capacity) => Some(capacity.clone().try_acquire_owned().map_err(|e| match e {
+                TryAcquireError::NoPermits => crate::Error::ChannelFull,
+                TryAcquireError::Closed => crate::Error::ChannelClosed,
+            })?),
+            None => None,
+        };
+
         let body = data.body()?;
-        (self.on_message)(body)
+        (self.on_message)(body, permit)
+    }
+
+    /// Sends the given data through the channel,
+    /// waiting for the webview to consume pending messages if this is a [bounded](Self::bounded) channel without free capacity.
+    pub async fn send_async(&self, data: TSend) -> crate::Result<()>
+    where
+        TSend: IpcResponse,
+    {
+        if self.is_closed() {
+            return Err(crate::Error::ChannelClosed);
+        }
+
+        let permit = match &self.capacity {
+            Some(capacity) => Some(
+                capacity
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| crate::Error::ChannelClosed)?,
+            ),
+            None => None,
+        };
+
+        let body = data.body()?;
+        (self.on_message)(body, permit)
+    }
+
+    /// Closes the channel, triggering the `onclose` handler on the JavaScript side
+    /// after all previously sent messages are delivered.
+    ///
+    /// Sending data after the channel is closed results in [`crate::Error::ChannelClosed`].
+    /// Closing an already closed channel is a no-op.
+    pub fn close(&self) -> crate::Result<()> {
+        if self.closed.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        if let Some(capacity) = &self.capacity {
+            capacity.close();
+        }
+
+        if let Some(on_close) = &self.on_close {
+            on_close()?;
+        }
+
+        Ok(())
     }
 }
 
-impl<'de, R: Runtime> CommandArg<'de, R> for Channel {
+impl<'de, R: Runtime, TSend> CommandArg<'de, R> for Channel<TSend> {
     /// Grabs the [`Webview`] from the [`CommandItem`] and returns the associated [`Channel`].
     fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
         let name = command.name;
         let arg = command.key;
         let webview = command.message.webview();
-        let value: String = Deserialize::deserialize(command)
-            .map_err(|e| crate::Error::InvalidArgs(name, arg, e))?;
+        let value: String =
+            Deserialize::deserialize(command).map_err(|e| IpcError::invalid_args(name, arg, e))?;
         JavaScriptChannelId::from_str(&value)
             .map(|id| id.channel_on(webview))
             .map_err(|_| {
@@ -210,7 +421,7 @@ fn fetch(
         .and_then(|id| id.parse().ok())
     {
         if let Some(data) = cache.0.lock().unwrap().remove(&id) {
-            Ok(Response::new(data))
+            Ok(Response::new(data.body))
         } else {
             Err("data not found")
         }
@@ -224,3 +435,185 @@ pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
         .invoke_handler(crate::generate_handler![fetch])
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use serde_json::json;
+
+    use super::{Channel, ChannelDataIpcQueue, InvokeBody, JavaScriptChannelId};
+    use crate::{
+        test::{mock_app, MockRuntime},
+        App, Manager, Webview, WebviewWindowBuilder,
+    };
+
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "camelCase", tag = "event", content = "data")]
+    enum DownloadEvent {
+        #[serde(rename_all = "camelCase")]
+        Progress {
+            chunk_length: usize,
+        },
+        Finished,
+    }
+
+    fn webview_channel(
+        app: &App<MockRuntime>,
+        label: &str,
+    ) -> (Webview<MockRuntime>, Channel<u32>) {
+        let webview = WebviewWindowBuilder::new(app, label, Default::default())
+            .build()
+            .unwrap()
+            .as_ref()
+            .clone();
+        let channel = JavaScriptChannelId::from_str("__CHANNEL__:1")
+            .unwrap()
+            .channel_on(webview.clone());
+        (webview, channel)
+    }
+
+    fn queued(webview: &Webview<MockRuntime>) -> usize {
+        webview
+            .state::<ChannelDataIpcQueue>()
+            .0
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|data| data.webview == webview.label())
+            .count()
+    }
+
+    fn recording_channel<TSend>() -> (Channel<TSend>, Arc<Mutex<Vec<InvokeBody>>>) {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let messages_ = messages.clone();
+        let channel = Channel::new(move |body| {
+            messages_.lock().unwrap().push(body);
+            Ok(())
+        });
+        (channel, messages)
+    }
+
+    #[test]
+    fn typed_channel_serializes_payload() {
+        let (channel, messages) = recording_channel::<DownloadEvent>();
+        channel
+            .send(DownloadEvent::Progress { chunk_length: 10 })
+            .unwrap();
+        channel.send(DownloadEvent::Finished).unwrap();
+
+        let messages = std::mem::take(&mut *messages.lock().unwrap())
+            .into_iter()
+            .map(InvokeBody::into_json)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                json!({ "event": "progress", "data": { "chunkLength": 10 } }),
+                json!({ "event": "finished" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn typed_channel_rejects_unserializable_payload() {
+        // JSON object keys must be strings
+        let (channel, messages) = recording_channel::<HashMap<(u8, u8), u8>>();
+        assert!(matches!(
+            channel.send(HashMap::from([((1, 2), 3)])),
+            Err(crate::Error::Json(_))
+        ));
+        assert!(messages.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bounded_channel_waits_for_capacity() {
+        let app = mock_app();
+        let (webview, channel) = webview_channel(&app, "main");
+        let channel = channel.bounded(1);
+        channel.send(1).unwrap();
+        assert!(matches!(channel.send(2), Err(crate::Error::ChannelFull)));
+
+        crate::async_runtime::block_on(async {
+            assert!(
+                tokio::time::timeout(Duration::from_millis(50), channel.send_async(2))
+                    .await
+                    .is_err(),
+                "send_async must wait while the channel is full"
+            );
+
+            let sender = channel.clone();
+            let send = crate::async_runtime::spawn(async move { sender.send_async(3).await });
+            // the webview fetches the pending message, releasing its capacity
+            webview
+                .state::<ChannelDataIpcQueue>()
+                .0
+                .lock()
+                .unwrap()
+                .clear();
+            tokio::time::timeout(Duration::from_secs(1), send)
+                .await
+                .expect("send_async did not resume after the data was fetched")
+                .unwrap()
+                .unwrap();
+        });
+        assert_eq!(queued(&webview), 1);
+    }
+
+    #[test]
+    fn close() {
+        let app = mock_app();
+        let (webview, channel) = webview_channel(&app, "main");
+        let channel = channel.bounded(1);
+        let clone = channel.clone();
+        channel.send(1).unwrap();
+
+        let waiting = crate::async_runtime::spawn(async move { clone.send_async(2).await });
+        channel.close().unwrap();
+        assert!(channel.is_closed());
+        assert!(matches!(
+            crate::async_runtime::block_on(waiting).unwrap(),
+            Err(crate::Error::ChannelClosed)
+        ));
+        assert!(matches!(channel.send(3), Err(crate::Error::ChannelClosed)));
+        assert_eq!(queued(&webview), 1);
+
+        // the JavaScript `onclose` handler is triggered
+        let script = webview.webview.dispatcher.last_evaluated_script().unwrap();
+        assert!(script.contains("end: true"), "{script}");
+
+        // closing twice is a no-op
+        webview.eval("").unwrap();
+        channel.close().unwrap();
+        assert_eq!(
+            webview
+                .webview
+                .dispatcher
+                .last_evaluated_script()
+                .as_deref(),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn navigation_clears_queued_data() {
+        let app = mock_app();
+        let (mut webview, channel) = webview_channel(&app, "main");
+        let (other, other_channel) = webview_channel(&app, "other");
+        let channel = channel.bounded(1);
+        channel.send(1).unwrap();
+        other_channel.send(1).unwrap();
+
+        webview.navigate("http://tauri.localhost/other-page".parse().unwrap());
+
+        assert_eq!(queued(&webview), 0);
+        assert_eq!(queued(&other), 1);
+        // the capacity held by the discarded message is released
+        channel.send(2).unwrap();
+    }
+}
@@ -0,0 +1,520 @@
+//! Codecs of the IPC request and response bodies.
+
+use std::fmt;
+
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    HeaderMap,
+};
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor},
+    Serialize,
+};
+use serde_json::Value as JsonValue;
+
+use super::InvokeBody;
+
+/// The format of a structured IPC body.
+///
+/// The request codec is read from the `Content-Type` header and the response codec
+/// is negotiated with [`BodyCodec::negotiate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BodyCodec {
+    /// `application/json`.
+    Json,
+    /// `application/msgpack`.
+    #[cfg(feature = "ipc-msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ipc-msgpack")))]
+    MsgPack,
+    /// `application/cbor`.
+    #[cfg(feature = "ipc-cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ipc-cbor")))]
+    Cbor,
+}
+
+impl BodyCodec {
+    /// Finds the codec for the given mime type essence.
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            "application/json" => Some(Self::Json),
+            #[cfg(feature = "ipc-msgpack")]
+            "application/msgpack" | "application/x-msgpack" => Some(Self::MsgPack),
+            #[cfg(feature = "ipc-cbor")]
+            "application/cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// The codec of the request body, read from the `Content-Type` header.
+    pub(crate) fn from_content_type(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|mime| Self::from_mime(mime.split(';').next().unwrap_or_default().trim()))
+    }
+
+    /// The codec to serialize the response with.
+    ///
+    /// Uses the first supported mime type of the `Accept` header,
+    /// falling back to the request content type and then to JSON.
+    pub fn negotiate(headers: &HeaderMap) -> Self {
+        let values = |name| {
+            headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .into_iter()
+                .flat_map(|h| h.split(','))
+        };
+
+        values(ACCEPT)
+            .chain(values(CONTENT_TYPE))
+            .filter_map(|mime| Self::from_mime(mime.split(';').next().unwrap_or_default().trim()))
+            .next()
+            .unwrap_or(Self::Json)
+    }
+
+    /// The mime type of the codec.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Json => mime::APPLICATION_JSON.essence_str(),
+            #[cfg(feature = "ipc-msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "ipc-cbor")]
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Reads a request body.
+    ///
+    /// JSON is parsed upfront, the binary codecs keep their bytes so command arguments
+    /// are deserialized from them directly.
+    pub(crate) fn read(self, body: Vec<u8>) -> Result<InvokeBody, String> {
+        match self {
+            Self::Json => serde_json::from_slice(&body)
+                .map(InvokeBody::Json)
+                .map_err(|e| e.to_string()),
+            #[allow(unreachable_patterns)]
+            _ => Ok(InvokeBody::Encoded(self, body)),
+        }
+    }
+
+    /// Deserializes a whole body.
+    pub(crate) fn deserialize<T: DeserializeOwned>(&self, body: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(body).map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-msgpack")]
+            Self::MsgPack => rmp_serde::from_slice(body).map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-cbor")]
+            Self::Cbor => ciborium::from_reader(body).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Deserializes the value of `key` in a body that encodes a map.
+    ///
+    /// Gives the seed back if the map does not have the key.
+    pub(crate) fn deserialize_field<'de, S: DeserializeSeed<'de>>(
+        &self,
+        body: &'de [u8],
+        key: &str,
+        seed: S,
+    ) -> Result<Result<S::Value, S>, String> {
+        let visitor = FieldVisitor { key, seed };
+        match self {
+            Self::Json => {
+                let mut deserializer = serde_json::Deserializer::from_slice(body);
+                let value = (&mut deserializer)
+                    .deserialize_map(visitor)
+                    .map_err(|e| e.to_string())?;
+                deserializer.end().map_err(|e| e.to_string())?;
+                Ok(value)
+            }
+            #[cfg(feature = "ipc-msgpack")]
+            Self::MsgPack => (&mut rmp_serde::Deserializer::from_read_ref(body))
+                .deserialize_map(visitor)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-cbor")]
+            Self::Cbor => ciborium::from_reader::<ciborium::Value, _>(body)
+                .map_err(|e| e.to_string())
+                .and_then(|value| {
+                    cbor::ValueDeserializer(value)
+                        .deserialize_map(visitor)
+                        .map_err(|e| e.to_string())
+                }),
+        }
+    }
+
+    /// Serializes a body.
+    pub(crate) fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-msgpack")]
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-cbor")]
+            Self::Cbor => {
+                let mut body = Vec::new();
+                ciborium::into_writer(value, &mut body).map_err(|e| e.to_string())?;
+                Ok(body)
+            }
+        }
+    }
+
+    /// Converts a body to a [`JsonValue`], for consumers that only understand JSON such as mobile plugins.
+    ///
+    /// Byte strings are converted to arrays of numbers.
+    #[allow(dead_code)]
+    pub(crate) fn to_json(self, body: &[u8]) -> Result<JsonValue, String> {
+        match self {
+            Self::Json => serde_json::from_slice(body).map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-msgpack")]
+            Self::MsgPack => rmp_serde::from_slice::<binary::Decoded>(body)
+                .map(|d| d.0)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "ipc-cbor")]
+            Self::Cbor => ciborium::from_reader::<binary::Decoded, _>(body)
+                .map(|d| d.0)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Finds a key in a map and deserializes its value with the seed, skipping the other entries.
+struct FieldVisitor<'k, S> {
+    key: &'k str,
+    seed: S,
+}
+
+impl<'de, S: DeserializeSeed<'de>> Visitor<'de> for FieldVisitor<'_, S> {
+    type Value = Result<S::Value, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map of command arguments")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut value = Err(self.seed);
+        while let Some(key) = map.next_key::<String>()? {
+            value = match value {
+                Err(seed) if key == self.key => Ok(map.next_value_seed(seed)?),
+                value => {
+                    map.next_value::<IgnoredAny>()?;
+                    value
+                }
+            };
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "ipc-cbor")]
+mod cbor {
+    use ciborium::Value;
+    use serde::{
+        de::{
+            value::{Error, MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+            Deserializer, Error as _, IntoDeserializer, Visitor,
+        },
+        forward_to_deserialize_any,
+    };
+
+    /// A [`Deserializer`] of a decoded CBOR [`Value`], since ciborium only exposes the one of its readers
+    /// through `from_reader`, which does not take a visitor.
+    pub(super) struct ValueDeserializer(pub(super) Value);
+
+    impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    fn map(
+        entries: Vec<(Value, Value)>,
+    ) -> impl Iterator<Item = (ValueDeserializer, ValueDeserializer)> {
+        entries
+            .into_iter()
+            .map(|(k, v)| (ValueDeserializer(k), ValueDeserializer(v)))
+    }
+
+    impl<'de> Deserializer<'de> for ValueDeserializer {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0 {
+                Value::Integer(i) => {
+                    let i = i128::from(i);
+                    if let Ok(i) = u64::try_from(i) {
+                        visitor.visit_u64(i)
+                    } else if let Ok(i) = i64::try_from(i) {
+                        visitor.visit_i64(i)
+                    } else {
+                        visitor.visit_i128(i)
+                    }
+                }
+                Value::Bytes(b) => visitor.visit_byte_buf(b),
+                Value::Float(f) => visitor.visit_f64(f),
+                Value::Text(s) => visitor.visit_string(s),
+                Value::Bool(b) => visitor.visit_bool(b),
+                Value::Null => visitor.visit_unit(),
+                Value::Tag(_, value) => ValueDeserializer(*value).deserialize_any(visitor),
+                Value::Array(values) => {
+                    let mut seq = SeqDeserializer::new(values.into_iter().map(ValueDeserializer));
+                    let value = visitor.visit_seq(&mut seq)?;
+                    seq.end()?;
+                    Ok(value)
+                }
+                Value::Map(entries) => {
+                    let mut map = MapDeserializer::new(map(entries));
+                    let value = visitor.visit_map(&mut map)?;
+                    map.end()?;
+                    Ok(value)
+                }
+                _ => Err(Error::custom("unsupported CBOR value")),
+            }
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0 {
+                Value::Null => visitor.visit_none(),
+                value => visitor.visit_some(ValueDeserializer(value)),
+            }
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0 {
+                // lets `Vec<u8>` arguments read byte strings
+                Value::Bytes(bytes) => {
+                    let mut seq = SeqDeserializer::new(bytes.into_iter());
+                    let value = visitor.visit_seq(&mut seq)?;
+                    seq.end()?;
+                    Ok(value)
+                }
+                value => ValueDeserializer(value).deserialize_any(visitor),
+            }
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            match self.0 {
+                Value::Text(variant) => visitor.visit_enum(variant.into_deserializer()),
+                Value::Map(entries) if entries.len() == 1 => visitor.visit_enum(
+                    MapAccessDeserializer::new(MapDeserializer::new(map(entries))),
+                ),
+                value => ValueDeserializer(value).deserialize_any(visitor),
+            }
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct tuple tuple_struct map struct identifier ignored_any
+        }
+    }
+}
+
+#[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+mod binary {
+    use std::fmt;
+
+    use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde_json::{Map, Number, Value as JsonValue};
+
+    /// A [`JsonValue`] deserialized from a format that supports byte strings and non-string map keys.
+    pub(super) struct Decoded(pub(super) JsonValue);
+
+    impl<'de> Deserialize<'de> for Decoded {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(DecodedVisitor).map(Decoded)
+        }
+    }
+
+    struct DecodedVisitor;
+
+    impl<'de> Visitor<'de> for DecodedVisitor {
+        type Value = JsonValue;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("any valid value")
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<JsonValue, E> {
+            Ok(JsonValue::Bool(v))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<JsonValue, E> {
+            Ok(JsonValue::Number(v.into()))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<JsonValue, E> {
+            Ok(JsonValue::Number(v.into()))
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<JsonValue, E> {
+            Ok(Number::from_f64(v).map_or(JsonValue::Null, JsonValue::Number))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<JsonValue, E> {
+            Ok(JsonValue::String(v.into()))
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<JsonValue, E> {
+            Ok(JsonValue::String(v))
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<JsonValue, E> {
+            Ok(JsonValue::Array(
+                v.iter().map(|b| JsonValue::Number((*b).into())).collect(),
+            ))
+        }
+
+        fn visit_none<E>(self) -> Result<JsonValue, E> {
+            Ok(JsonValue::Null)
+        }
+
+        fn visit_unit<E>(self) -> Result<JsonValue, E> {
+            Ok(JsonValue::Null)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JsonValue, D::Error> {
+            Decoded::deserialize(deserializer).map(|d| d.0)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+            let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+            while let Some(Decoded(value)) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(JsonValue::Array(values))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+            let mut values = Map::new();
+            while let Some((Decoded(key), Decoded(value))) = map.next_entry()? {
+                let key = match key {
+                    JsonValue::String(key) => key,
+                    key => key.to_string(),
+                };
+                values.insert(key, value);
+            }
+            Ok(JsonValue::Object(values))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::ACCEPT, HeaderMap, HeaderValue};
+    #[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+    use serde_json::json;
+
+    use super::BodyCodec;
+
+    #[test]
+    fn negotiates_json_by_default() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(BodyCodec::negotiate(&headers), BodyCodec::Json);
+
+        headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
+        assert_eq!(BodyCodec::negotiate(&headers), BodyCodec::Json);
+    }
+
+    /// A byte buffer that can only be read from a byte string.
+    #[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+    #[derive(Debug, PartialEq)]
+    struct Bytes(Vec<u8>);
+
+    #[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+    impl serde::Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    #[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+    impl<'de> serde::Deserialize<'de> for Bytes {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct BytesVisitor;
+
+            impl serde::de::Visitor<'_> for BytesVisitor {
+                type Value = Bytes;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    formatter.write_str("a byte string")
+                }
+
+                fn visit_bytes<E>(self, v: &[u8]) -> Result<Bytes, E> {
+                    Ok(Bytes(v.to_vec()))
+                }
+            }
+
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+
+    #[cfg(any(feature = "ipc-msgpack", feature = "ipc-cbor"))]
+    fn reads_fields(codec: BodyCodec) {
+        use std::marker::PhantomData;
+
+        #[derive(serde::Serialize)]
+        struct Args {
+            data: Bytes,
+            scale: f32,
+            label: Option<String>,
+        }
+
+        let body = codec
+            .serialize(&Args {
+                data: Bytes(vec![1, 2, 3]),
+                scale: 0.5,
+                label: None,
+            })
+            .unwrap();
+
+        let field = |key| codec.deserialize_field(&body, key, PhantomData::<Bytes>);
+        assert_eq!(field("data").unwrap().ok(), Some(Bytes(vec![1, 2, 3])));
+        assert!(field("missing").unwrap().is_err());
+        assert_eq!(
+            codec
+                .deserialize_field(&body, "scale", PhantomData::<f32>)
+                .unwrap()
+                .ok(),
+            Some(0.5)
+        );
+        assert_eq!(
+            codec
+                .deserialize_field(&body, "label", PhantomData::<Option<String>>)
+                .unwrap()
+                .ok(),
+            Some(None)
+        );
+
+        assert_eq!(
+            codec.to_json(&body).unwrap(),
+            json!({ "data": [1, 2, 3], "scale": 0.5, "label": null })
+        );
+    }
+
+    #[cfg(feature = "ipc-msgpack")]
+    #[test]
+    fn msgpack_reads_fields() {
+        reads_fields(BodyCodec::MsgPack);
+    }
+
+    #[cfg(feature = "ipc-cbor")]
+    #[test]
+    fn cbor_reads_fields() {
+        reads_fields(BodyCodec::Cbor);
+    }
+}
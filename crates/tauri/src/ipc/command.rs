@@ -4,11 +4,11 @@
 //! attribute macro along the way and used by [`crate::generate_handler`] macro.
 
 use crate::{
-    ipc::{InvokeBody, InvokeError, InvokeMessage},
+    ipc::{InvokeBody, InvokeError, InvokeMessage, IpcError},
     Runtime,
 };
 use serde::{
-    de::{Error, Visitor},
+    de::{DeserializeSeed, Error, Visitor},
     Deserialize, Deserializer,
 };
 
@@ -61,11 +61,12 @@ impl<'de, D: Deserialize<'de>, R: Runtime> CommandArg<'de, R> for D {
         let arg = command.key;
         #[cfg(feature = "tracing")]
         let _span = tracing::trace_span!("ipc::request::deserialize_arg", arg = arg).entered();
-        Self::deserialize(command).map_err(|e| crate::Error::InvalidArgs(name, arg, e).into())
+        Self::deserialize(command).map_err(|e| IpcError::invalid_args(name, arg, e).into())
     }
 }
 
-/// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer.
+/// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer,
+/// or the value of the key in an encoded payload into the codec's deserializer.
 ///
 /// Returns an error if the [`CommandItem`]'s key does not exist in the value.
 macro_rules! pass {
@@ -96,11 +97,46 @@ macro_rules! pass {
             }
           }
         }
+        InvokeBody::Encoded(codec, body) => {
+          struct Forward<V> {
+            $($arg: $argt),*
+          }
+
+          impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for Forward<V> {
+            type Value = V::Value;
+
+            fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+              deserializer.$fn($(self.$arg),*)
+            }
+          }
+
+          match codec.deserialize_field(body, self.key, Forward { $($arg),* }) {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => {
+              Err(serde_json::Error::custom(format!(
+                "command {} missing required key {}",
+                self.name, self.key
+              )))
+            }
+            Err(e) => Err(serde_json::Error::custom(e)),
+          }
+        }
       }
     }
   }
 }
 
+/// Forwards an optional value of an encoded payload to the visitor.
+struct OptionSeed<V>(V);
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for OptionSeed<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_option(self.0)
+    }
+}
+
 /// A [`Deserializer`] wrapper around [`CommandItem`].
 ///
 /// If the key doesn't exist, an error will be returned if the deserialized type is not expecting
@@ -137,6 +173,13 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
                 Some(value) => value.deserialize_option(visitor),
                 None => visitor.visit_none(),
             },
+            InvokeBody::Encoded(codec, body) => {
+                match codec.deserialize_field(body, self.key, OptionSeed(visitor)) {
+                    Ok(Ok(value)) => Ok(value),
+                    Ok(Err(OptionSeed(visitor))) => visitor.visit_none(),
+                    Err(e) => Err(serde_json::Error::custom(e)),
+                }
+            }
         }
     }
 
@@ -179,14 +222,46 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
 #[doc(hidden)]
 pub mod private {
     use crate::{
-        ipc::{InvokeBody, InvokeError, InvokeResolver, IpcResponse},
+        ipc::{
+            stream, CommandArg, CommandItem, InvokeBody, InvokeError, InvokeMessage,
+            InvokeResolver, IpcResponse,
+        },
         Runtime,
     };
-    use futures_util::{FutureExt, TryFutureExt};
+    use futures_util::{FutureExt, Stream, TryFutureExt};
+    pub use serde;
     use std::future::Future;
     #[cfg(feature = "tracing")]
     pub use tracing;
 
+    /// Derives an argument of an async command.
+    ///
+    /// If the argument uses a [`crate::State`] whose asynchronous or lazy initialization is not finished,
+    /// this waits for it asynchronously instead of blocking the async runtime.
+    pub async fn command_arg_async<'de, R: Runtime, T: CommandArg<'de, R>>(
+        command: CommandItem<'de, R>,
+    ) -> Result<T, InvokeError> {
+        loop {
+            // subscribe before the lookup to not miss an initialization that finishes in between
+            let mut initialized = command.message.state_ref().subscribe_initialized();
+            let (result, not_ready) = crate::state::without_waiting(|| {
+                T::from_command(CommandItem {
+                    plugin: command.plugin,
+                    name: command.name,
+                    key: command.key,
+                    message: command.message,
+                    acl: command.acl,
+                })
+            });
+            match result {
+                Err(_) if not_ready => {
+                    let _ = initialized.changed().await;
+                }
+                result => return result,
+            }
+        }
+    }
+
     // ===== impl IpcResponse =====
 
     pub struct ResponseTag;
@@ -216,11 +291,20 @@ pub mod private {
         }
 
         #[inline(always)]
-        pub fn future<T>(self, value: T) -> impl Future<Output = Result<InvokeBody, InvokeError>>
+        pub fn future<R, T>(
+            self,
+            value: T,
+            message: &InvokeMessage<R>,
+        ) -> impl Future<Output = Result<InvokeBody, InvokeError>>
         where
+            R: Runtime,
             T: IpcResponse,
         {
-            std::future::ready(value.body().map_err(InvokeError::from_error))
+            std::future::ready(
+                value
+                    .encode(message.codec())
+                    .map_err(InvokeError::from_error),
+            )
         }
     }
 
@@ -254,19 +338,21 @@ pub mod private {
         }
 
         #[inline(always)]
-        pub fn future<T, E>(
+        pub fn future<R, T, E>(
             self,
             value: Result<T, E>,
+            message: &InvokeMessage<R>,
         ) -> impl Future<Output = Result<InvokeBody, InvokeError>>
         where
+            R: Runtime,
             T: IpcResponse,
             E: Into<InvokeError>,
         {
-            std::future::ready(
+            std::future::ready(value.map_err(Into::into).and_then(|value| {
                 value
-                    .map_err(Into::into)
-                    .and_then(|value| value.body().map_err(InvokeError::from_error)),
-            )
+                    .encode(message.codec())
+                    .map_err(InvokeError::from_error)
+            }))
         }
     }
 
@@ -284,12 +370,18 @@ pub mod private {
 
     impl FutureTag {
         #[inline(always)]
-        pub fn future<T, F>(self, value: F) -> impl Future<Output = Result<InvokeBody, InvokeError>>
+        pub fn future<R, T, F>(
+            self,
+            value: F,
+            message: &InvokeMessage<R>,
+        ) -> impl Future<Output = Result<InvokeBody, InvokeError>>
         where
+            R: Runtime,
             T: IpcResponse,
             F: Future<Output = T> + Send + 'static,
         {
-            value.map(|value| value.body().map_err(InvokeError::from_error))
+            let codec = message.codec();
+            value.map(move |value| value.encode(codec).map_err(InvokeError::from_error))
         }
     }
 
@@ -311,18 +403,73 @@ pub mod private {
 
     impl ResultFutureTag {
         #[inline(always)]
-        pub fn future<T, E, F>(
+        pub fn future<R, T, E, F>(
             self,
             value: F,
+            message: &InvokeMessage<R>,
         ) -> impl Future<Output = Result<InvokeBody, InvokeError>>
         where
+            R: Runtime,
             T: IpcResponse,
             E: Into<InvokeError>,
             F: Future<Output = Result<T, E>> + Send,
         {
-            value.err_into().map(|result| {
-                result.and_then(|value| value.body().map_err(InvokeError::from_error))
+            let codec = message.codec();
+            value.err_into().map(move |result| {
+                result.and_then(|value| value.encode(codec).map_err(InvokeError::from_error))
             })
         }
     }
+
+    // ===== Stream<Item = Result<impl Serialize, impl Into<InvokeError>>> =====
+
+    pub struct StreamTag;
+
+    pub trait StreamKind {
+        #[inline(always)]
+        fn blocking_kind(&self) -> StreamTag {
+            StreamTag
+        }
+
+        #[inline(always)]
+        fn async_kind(&self) -> StreamTag {
+            StreamTag
+        }
+    }
+
+    impl<T: IpcResponse, E: Into<InvokeError>, S: Stream<Item = Result<T, E>>> StreamKind for S {}
+
+    impl StreamTag {
+        #[inline(always)]
+        pub fn block<R, T, E, S>(self, value: S, resolver: InvokeResolver<R>)
+        where
+            R: Runtime,
+            T: IpcResponse + 'static,
+            E: Into<InvokeError> + 'static,
+            S: Stream<Item = Result<T, E>> + Send + 'static,
+        {
+            let handle = stream::register(&resolver.webview, value);
+            resolver.respond(Ok(handle))
+        }
+
+        #[inline(always)]
+        pub fn future<R, T, E, S>(
+            self,
+            value: S,
+            message: &InvokeMessage<R>,
+        ) -> impl Future<Output = Result<InvokeBody, InvokeError>>
+        where
+            R: Runtime,
+            T: IpcResponse + 'static,
+            E: Into<InvokeError> + 'static,
+            S: Stream<Item = Result<T, E>> + Send + 'static,
+        {
+            let handle = stream::register(message.webview_ref(), value);
+            std::future::ready(
+                handle
+                    .encode(message.codec())
+                    .map_err(InvokeError::from_error),
+            )
+        }
+    }
 }
@@ -0,0 +1,233 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// The codes of the errors produced by Tauri when handling an invoke.
+pub mod codes {
+    /// The command is not allowed by the capabilities of the webview.
+    pub const ACL_DENIED: &str = "ACL_DENIED";
+    /// The application does not define the command.
+    pub const COMMAND_NOT_FOUND: &str = "COMMAND_NOT_FOUND";
+    /// The plugin of the command is not registered.
+    pub const PLUGIN_NOT_FOUND: &str = "PLUGIN_NOT_FOUND";
+    /// A command argument is missing or could not be deserialized.
+    /// The `data` field contains the `command` and `argument` names.
+    pub const INVALID_ARGS: &str = "INVALID_ARGS";
+    /// The command requested a [`crate::State`] that was not managed.
+    pub const STATE_NOT_MANAGED: &str = "STATE_NOT_MANAGED";
+    /// The asynchronous or lazy initialization of a [`crate::State`] requested by the command failed.
+    pub const STATE_INIT_FAILED: &str = "STATE_INIT_FAILED";
+    /// A [`crate::State`] whose asynchronous or lazy initialization is not finished was requested without waiting for it.
+    /// Commands wait for the initialization, so it is not sent to the webview.
+    pub const STATE_NOT_READY: &str = "STATE_NOT_READY";
+    /// The async command did not finish within its timeout.
+    pub const COMMAND_TIMEOUT: &str = "COMMAND_TIMEOUT";
+    /// The command response could not be encoded in the format negotiated with the webview.
+    pub const RESPONSE_ENCODING_FAILED: &str = "RESPONSE_ENCODING_FAILED";
+}
+
+/// A structured error sent to the webview when an invoke is rejected.
+///
+/// It is serialized as `{ "code": "...", "message": "...", "data"?: ..., "causes"?: [...] }`,
+/// so the frontend can tell the failures apart by their code.
+/// See [`codes`] for the errors produced by Tauri itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::IpcError;
+///
+/// #[tauri::command]
+/// fn withdraw(amount: u64) -> Result<(), IpcError> {
+///   Err(IpcError::new("INSUFFICIENT_FUNDS", "not enough funds").with_data(amount))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcError {
+    /// A stable identifier of the error.
+    pub code: String,
+    /// The human readable error message.
+    pub message: String,
+    /// Additional data about the error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<JsonValue>,
+    /// The messages of the errors that caused this error, from the closest to the root cause.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+impl IpcError {
+    /// Creates an error with the given code and message.
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            data: None,
+            causes: Vec::new(),
+        }
+    }
+
+    /// Creates an error with the message and the cause chain of the given [`std::error::Error`].
+    pub fn from_error<E: std::error::Error + ?Sized>(code: impl Into<String>, error: &E) -> Self {
+        let mut causes = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+
+        Self {
+            code: code.into(),
+            message: error.to_string(),
+            data: None,
+            causes,
+        }
+    }
+
+    /// Sets the additional data of the error.
+    #[must_use]
+    pub fn with_data<T: Serialize>(mut self, data: T) -> Self {
+        self.data = serde_json::to_value(data).ok();
+        self
+    }
+
+    /// Appends a cause to the error.
+    #[must_use]
+    pub fn with_cause(mut self, cause: impl fmt::Display) -> Self {
+        self.causes.push(cause.to_string());
+        self
+    }
+
+    /// The error for an argument that could not be read from the invoke payload.
+    pub(crate) fn invalid_args(command: &str, arg: &str, error: serde_json::Error) -> Self {
+        Self::new(
+            codes::INVALID_ARGS,
+            format!("invalid args `{arg}` for command `{command}`: {error}"),
+        )
+        .with_data(serde_json::json!({ "command": command, "argument": arg }))
+    }
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for IpcError {}
+
+/// Errors that are sent to the webview as an [`IpcError`].
+///
+/// It is usually implemented with the `IpcError` derive macro, which also implements [`Serialize`]
+/// so the type can be returned from commands directly.
+/// The derive requires the type to implement [`std::error::Error`], e.g. with `thiserror`.
+/// The code of each variant defaults to its name in `SCREAMING_SNAKE_CASE`
+/// and can be set with `#[ipc_error(code = "...")]`,
+/// and a field marked with `#[ipc_error(data)]` is sent as the error data.
+///
+/// # Examples
+///
+/// ```rust
+/// #[derive(Debug, thiserror::Error, tauri::ipc::IpcError)]
+/// enum Error {
+///   #[error("file {0} not found")]
+///   NotFound(#[ipc_error(data)] String),
+///   #[error(transparent)]
+///   #[ipc_error(code = "IO")]
+///   Io(#[from] std::io::Error),
+/// }
+///
+/// #[tauri::command]
+/// fn read(path: String) -> Result<String, Error> {
+///   std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+///     std::io::ErrorKind::NotFound => Error::NotFound(path),
+///     _ => e.into(),
+///   })
+/// }
+/// ```
+pub trait IntoIpcError {
+    /// Converts the error to its [`IpcError`] representation.
+    fn to_ipc_error(&self) -> IpcError;
+}
+
+impl IntoIpcError for IpcError {
+    fn to_ipc_error(&self) -> IpcError {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{IntoIpcError, IpcError};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("failed to load settings")]
+    struct LoadError(#[source] std::io::Error);
+
+    #[derive(Debug, thiserror::Error, crate::ipc::IpcError)]
+    #[ipc_error(root = "crate")]
+    enum AppError {
+        #[error("file {path} not found")]
+        FileNotFound {
+            #[ipc_error(data)]
+            path: String,
+        },
+        #[error(transparent)]
+        #[ipc_error(code = "SETTINGS")]
+        Load(#[from] LoadError),
+    }
+
+    #[test]
+    fn serializes_envelope() {
+        let error = IpcError::new("NOT_FOUND", "not found");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "code": "NOT_FOUND", "message": "not found" })
+        );
+
+        let error = IpcError::from_error(
+            "LOAD",
+            &LoadError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "missing file",
+            )),
+        )
+        .with_data(json!({ "path": "settings.json" }));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "LOAD",
+                "message": "failed to load settings",
+                "data": { "path": "settings.json" },
+                "causes": ["missing file"],
+            })
+        );
+    }
+
+    #[test]
+    fn derive_maps_variants() {
+        let error = AppError::FileNotFound {
+            path: "notes.txt".into(),
+        };
+        assert_eq!(
+            error.to_ipc_error(),
+            IpcError::new("FILE_NOT_FOUND", "file notes.txt not found").with_data("notes.txt")
+        );
+
+        let error = AppError::from(LoadError(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "access denied",
+        )));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "SETTINGS",
+                "message": "failed to load settings",
+                "causes": ["access denied"],
+            })
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::{Runtime, Webview};
+
+use super::{InvokeError, InvokeMessage, InvokeResponse};
+
+/// A layer that runs around every IPC invoke, before the ACL check and the command dispatch.
+///
+/// Middlewares are registered with [`crate::Builder::ipc_middleware`] or [`crate::plugin::Builder::ipc_middleware`].
+/// [`IpcMiddleware::on_invoke`] runs in registration order, the application middlewares first,
+/// and [`IpcMiddleware::on_response`] runs in the reverse order.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tauri::{
+///   ipc::{InvokeError, InvokeMessage, InvokeResponse, IpcMiddleware},
+///   Runtime, Webview,
+/// };
+///
+/// struct Audit;
+///
+/// impl<R: Runtime> IpcMiddleware<R> for Audit {
+///   fn on_invoke(&self, message: &InvokeMessage<R>) -> Result<(), InvokeError> {
+///     if message.command() == "delete_everything" {
+///       return Err(InvokeError::from("not today"));
+///     }
+///     Ok(())
+///   }
+///
+///   fn on_response(
+///     &self,
+///     webview: &Webview<R>,
+///     command: &str,
+///     elapsed: Duration,
+///     _response: &mut InvokeResponse,
+///   ) {
+///     println!("{} invoked {command} in {elapsed:?}", webview.label());
+///   }
+/// }
+///
+/// tauri::Builder::default().ipc_middleware(Audit);
+/// ```
+pub trait IpcMiddleware<R: Runtime>: Send + Sync + 'static {
+    /// Called when the webview invokes a command, before it is dispatched.
+    ///
+    /// Returning an error rejects the invoke without calling the remaining middlewares and the command.
+    fn on_invoke(&self, message: &InvokeMessage<R>) -> Result<(), InvokeError> {
+        let _ = message;
+        Ok(())
+    }
+
+    /// Called before the response of an invoke is sent to the webview, including rejected invokes.
+    ///
+    /// The `elapsed` duration is measured from the moment the invoke was received.
+    fn on_response(
+        &self,
+        webview: &Webview<R>,
+        command: &str,
+        elapsed: Duration,
+        response: &mut InvokeResponse,
+    ) {
+        let _ = (webview, command, elapsed, response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use serde_json::{json, Value as JsonValue};
+
+    use super::IpcMiddleware;
+    use crate::{
+        ipc::{CallbackFn, InvokeBody, InvokeError, InvokeMessage, InvokeResponse},
+        test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime},
+        webview::InvokeRequest,
+        Webview, WebviewWindowBuilder,
+    };
+
+    #[crate::command(root = "crate")]
+    fn ping() -> &'static str {
+        "pong"
+    }
+
+    #[crate::command(root = "crate")]
+    fn secret() -> &'static str {
+        "leaked"
+    }
+
+    #[derive(Default)]
+    struct Guard {
+        responses: Arc<AtomicUsize>,
+    }
+
+    impl IpcMiddleware<MockRuntime> for Guard {
+        fn on_invoke(&self, message: &InvokeMessage<MockRuntime>) -> Result<(), InvokeError> {
+            if message.command() == "secret" {
+                return Err(InvokeError::from("forbidden"));
+            }
+            Ok(())
+        }
+
+        fn on_response(
+            &self,
+            _webview: &Webview<MockRuntime>,
+            _command: &str,
+            _elapsed: Duration,
+            _response: &mut InvokeResponse,
+        ) {
+            self.responses.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn short_circuits_invoke() {
+        let guard = Guard::default();
+        let responses = guard.responses.clone();
+
+        let app = mock_builder()
+            .ipc_middleware(guard)
+            .invoke_handler(crate::generate_handler![ping, secret])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let invoke = |cmd: &str| {
+            get_ipc_response(
+                &webview,
+                InvokeRequest {
+                    cmd: cmd.into(),
+                    callback: CallbackFn(0),
+                    error: CallbackFn(1),
+                    url: "http://tauri.localhost".parse().unwrap(),
+                    body: InvokeBody::default(),
+                    headers: Default::default(),
+                },
+            )
+            .map(|body| body.deserialize::<JsonValue>().unwrap())
+        };
+
+        assert_eq!(invoke("ping"), Ok(json!("pong")));
+        assert_eq!(invoke("secret"), Err(json!("forbidden")));
+        assert_eq!(responses.load(Ordering::SeqCst), 2);
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! This module includes utilities to send messages to the JS layer of the webview.
 
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use futures_util::Future;
 use http::HeaderMap;
@@ -12,20 +15,33 @@ pub use serialize_to_javascript::Options as SerializeOptions;
 use tauri_macros::default_runtime;
 use tauri_utils::acl::resolved::ResolvedCommand;
 
-use crate::{webview::Webview, Runtime, StateManager};
+use crate::{sealed::ManagerBase, webview::Webview, Runtime, StateManager};
 
-mod authority;
+pub(crate) mod authority;
+#[cfg(feature = "ipc-bindings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ipc-bindings")))]
+pub mod bindings;
+pub(crate) mod cancel;
 pub(crate) mod channel;
+mod codec;
 mod command;
+mod error;
 pub(crate) mod format_callback;
+mod middleware;
 pub(crate) mod protocol;
+pub(crate) mod stream;
 
 pub use authority::{
-    CapabilityBuilder, CommandScope, GlobalScope, Origin, RuntimeAuthority, RuntimeCapability,
-    ScopeObject, ScopeValue,
+    AccessDecision, AccessReport, AccessRule, CapabilityBuilder, CapabilityLimits, CommandScope,
+    GlobalScope, Origin, RuntimeAuthority, RuntimeCapability, ScopeObject, ScopeValue,
 };
+pub use cancel::CancellationToken;
 pub use channel::{Channel, JavaScriptChannelId};
+pub use codec::BodyCodec;
 pub use command::{private, CommandArg, CommandItem};
+pub use error::{codes, IntoIpcError, IpcError};
+pub use middleware::IpcMiddleware;
+pub use tauri_macros::IpcError;
 
 /// A closure that is run every time Tauri receives a message it doesn't explicitly handle.
 pub type InvokeHandler<R> = dyn Fn(Invoke<R>) -> bool + Send + Sync + 'static;
@@ -44,6 +60,8 @@ pub enum InvokeBody {
     Json(JsonValue),
     /// Bytes payload.
     Raw(Vec<u8>),
+    /// Payload encoded with a binary [`BodyCodec`].
+    Encoded(BodyCodec, Vec<u8>),
 }
 
 impl Default for InvokeBody {
@@ -78,6 +96,7 @@ impl InvokeBody {
             Self::Raw(v) => {
                 JsonValue::Array(v.into_iter().map(|n| JsonValue::Number(n.into())).collect())
             }
+            Self::Encoded(codec, v) => codec.to_json(&v).unwrap_or_default(),
         }
     }
 
@@ -86,6 +105,9 @@ impl InvokeBody {
         match self {
             InvokeBody::Json(v) => serde_json::from_value(v),
             InvokeBody::Raw(v) => serde_json::from_slice(&v),
+            InvokeBody::Encoded(codec, v) => {
+                codec.deserialize(&v).map_err(serde::de::Error::custom)
+            }
         }
     }
 }
@@ -123,6 +145,17 @@ impl<'a, R: Runtime> CommandArg<'a, R> for Request<'a> {
 pub trait IpcResponse {
     /// Resolve the IPC response body.
     fn body(self) -> crate::Result<InvokeBody>;
+
+    /// Resolve the IPC response body with the codec negotiated for the request.
+    ///
+    /// Defaults to [`Self::body`], the protocol encodes JSON bodies with the codec afterwards.
+    fn encode(self, codec: BodyCodec) -> crate::Result<InvokeBody>
+    where
+        Self: Sized,
+    {
+        let _ = codec;
+        self.body()
+    }
 }
 
 impl<T: Serialize> IpcResponse for T {
@@ -131,6 +164,17 @@ impl<T: Serialize> IpcResponse for T {
             .map(Into::into)
             .map_err(Into::into)
     }
+
+    fn encode(self, codec: BodyCodec) -> crate::Result<InvokeBody> {
+        match codec {
+            BodyCodec::Json => self.body(),
+            #[allow(unreachable_patterns)]
+            codec => codec
+                .serialize(&self)
+                .map(|body| InvokeBody::Encoded(codec, body))
+                .map_err(|e| crate::Error::IpcEncode(codec.mime_type(), e)),
+        }
+    }
 }
 
 /// The IPC request.
@@ -180,6 +224,17 @@ impl InvokeError {
     pub fn from_anyhow(error: anyhow::Error) -> Self {
         Self(JsonValue::String(format!("{error:#}")))
     }
+
+    /// The error returned when an async command does not finish within its timeout.
+    ///
+    /// It is an [`IpcError`] with the [`codes::COMMAND_TIMEOUT`] code.
+    pub fn timeout(command: &str, timeout: Duration) -> Self {
+        IpcError::new(
+            codes::COMMAND_TIMEOUT,
+            format!("command {command} timed out after {timeout:?}"),
+        )
+        .into()
+    }
 }
 
 impl<T: Serialize> From<T> for InvokeError {
@@ -214,7 +269,9 @@ impl Serialize for InvokeResponse {
     {
         match self {
             Self::Ok(InvokeBody::Json(j)) => j.serialize(serializer),
-            Self::Ok(InvokeBody::Raw(b)) => b.serialize(serializer),
+            Self::Ok(InvokeBody::Raw(b)) | Self::Ok(InvokeBody::Encoded(_, b)) => {
+                b.serialize(serializer)
+            }
             Self::Err(e) => e.0.serialize(serializer),
         }
     }
@@ -223,8 +280,18 @@ impl Serialize for InvokeResponse {
 impl<T: IpcResponse, E: Into<InvokeError>> From<Result<T, E>> for InvokeResponse {
     #[inline]
     fn from(result: Result<T, E>) -> Self {
+        Self::encoded(result, BodyCodec::Json)
+    }
+}
+
+impl InvokeResponse {
+    /// Creates a response, encoding the successful value with the given codec.
+    fn encoded<T: IpcResponse, E: Into<InvokeError>>(
+        result: Result<T, E>,
+        codec: BodyCodec,
+    ) -> Self {
         match result {
-            Ok(ok) => match ok.body() {
+            Ok(ok) => match ok.encode(codec) {
                 Ok(value) => Self::Ok(value),
                 Err(err) => Self::Err(InvokeError::from_error(err)),
             },
@@ -239,6 +306,17 @@ impl From<InvokeError> for InvokeResponse {
     }
 }
 
+/// Whether the command is implemented by Tauri to support the IPC itself.
+fn is_internal_command(cmd: &str) -> bool {
+    [
+        channel::FETCH_CHANNEL_DATA_COMMAND,
+        stream::NEXT_STREAM_ITEM_COMMAND,
+        stream::CLOSE_STREAM_COMMAND,
+        cancel::CANCEL_INVOKE_COMMAND,
+    ]
+    .contains(&cmd)
+}
+
 /// Resolver of a invoke message.
 #[default_runtime(crate::Wry, wry)]
 pub struct InvokeResolver<R: Runtime> {
@@ -247,6 +325,9 @@ pub struct InvokeResolver<R: Runtime> {
     cmd: String,
     pub(crate) callback: CallbackFn,
     pub(crate) error: CallbackFn,
+    cancellation: CancellationToken,
+    timeout: Option<Duration>,
+    codec: BodyCodec,
 }
 
 impl<R: Runtime> Clone for InvokeResolver<R> {
@@ -257,6 +338,9 @@ impl<R: Runtime> Clone for InvokeResolver<R> {
             cmd: self.cmd.clone(),
             callback: self.callback,
             error: self.error,
+            cancellation: self.cancellation.clone(),
+            timeout: self.timeout,
+            codec: self.codec,
         }
     }
 }
@@ -268,42 +352,72 @@ impl<R: Runtime> InvokeResolver<R> {
         cmd: String,
         callback: CallbackFn,
         error: CallbackFn,
+        cancellation: CancellationToken,
+        codec: BodyCodec,
     ) -> Self {
+        // the internal commands wait for the webview or for long-lived streams,
+        // so the default timeout only applies to the app and plugin commands
+        let timeout = if is_internal_command(&cmd) {
+            None
+        } else {
+            webview.manager().webview.invoke_timeout
+        };
         Self {
             webview,
             responder,
             cmd,
             callback,
             error,
+            cancellation,
+            timeout,
+            codec,
         }
     }
 
+    /// Sets the maximum duration of the async task that replies to the invoke,
+    /// overriding the default set with [`crate::Builder::invoke_timeout`].
+    ///
+    /// The task is aborted when it expires and the invoke is rejected with [`InvokeError::timeout`].
+    /// Commands set it with the `timeout` attribute, e.g. `#[tauri::command(timeout = "30s")]`.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout.replace(timeout);
+        self
+    }
+
     /// Reply to the invoke promise with an async task.
+    ///
+    /// The task is aborted if the invoke is cancelled, see [`CancellationToken`], or if it exceeds its timeout.
     pub fn respond_async<T, F>(self, task: F)
     where
         T: IpcResponse,
         F: Future<Output = Result<T, InvokeError>> + Send + 'static,
     {
-        crate::async_runtime::spawn(async move {
-            Self::return_task(
+        let cancellation = self.cancellation.clone();
+        let handle = crate::async_runtime::spawn(async move {
+            let result = with_timeout(task, self.timeout, &self.cmd).await;
+            Self::return_result(
                 self.webview,
                 self.responder,
-                task,
+                InvokeResponse::encoded(result, self.codec),
                 self.cmd,
                 self.callback,
                 self.error,
-            )
-            .await;
+            );
         });
+        cancellation.set_task(handle.inner().abort_handle());
     }
 
     /// Reply to the invoke promise with an async task which is already serialized.
+    ///
+    /// The task is aborted if the invoke is cancelled, see [`CancellationToken`], or if it exceeds its timeout.
     pub fn respond_async_serialized<F>(self, task: F)
     where
         F: Future<Output = Result<InvokeBody, InvokeError>> + Send + 'static,
     {
-        crate::async_runtime::spawn(async move {
-            let response = match task.await {
+        let cancellation = self.cancellation.clone();
+        let handle = crate::async_runtime::spawn(async move {
+            let response = match with_timeout(task, self.timeout, &self.cmd).await {
                 Ok(ok) => InvokeResponse::Ok(ok),
                 Err(err) => InvokeResponse::Err(err),
             };
@@ -316,6 +430,7 @@ impl<R: Runtime> InvokeResolver<R> {
                 self.error,
             )
         });
+        cancellation.set_task(handle.inner().abort_handle());
     }
 
     /// Reply to the invoke promise with a serializable value.
@@ -323,7 +438,7 @@ impl<R: Runtime> InvokeResolver<R> {
         Self::return_result(
             self.webview,
             self.responder,
-            value.into(),
+            InvokeResponse::encoded(value, self.codec),
             self.cmd,
             self.callback,
             self.error,
@@ -422,6 +537,20 @@ impl<R: Runtime> InvokeResolver<R> {
     }
 }
 
+/// Runs the task, failing with [`InvokeError::timeout`] if it does not finish in time.
+async fn with_timeout<T, F>(task: F, timeout: Option<Duration>, cmd: &str) -> Result<T, InvokeError>
+where
+    F: Future<Output = Result<T, InvokeError>>,
+{
+    match timeout {
+        // dropping the task on expiry aborts it
+        Some(timeout) => tokio::time::timeout(timeout, task)
+            .await
+            .unwrap_or_else(|_| Err(InvokeError::timeout(cmd, timeout))),
+        None => task.await,
+    }
+}
+
 /// An invoke message.
 #[default_runtime(crate::Wry, wry)]
 #[derive(Debug)]
@@ -436,6 +565,12 @@ pub struct InvokeMessage<R: Runtime> {
     pub(crate) payload: InvokeBody,
     /// The request headers.
     pub(crate) headers: HeaderMap,
+    /// The codec negotiated for the response.
+    pub(crate) codec: BodyCodec,
+    /// The invoke cancellation token.
+    pub(crate) cancellation: CancellationToken,
+    /// The state managed by the webview and by its window.
+    pub(crate) scoped_state: Vec<Arc<StateManager>>,
 }
 
 impl<R: Runtime> Clone for InvokeMessage<R> {
@@ -446,6 +581,9 @@ impl<R: Runtime> Clone for InvokeMessage<R> {
             command: self.command.clone(),
             payload: self.payload.clone(),
             headers: self.headers.clone(),
+            codec: self.codec,
+            cancellation: self.cancellation.clone(),
+            scoped_state: self.scoped_state.clone(),
         }
     }
 }
@@ -458,13 +596,19 @@ impl<R: Runtime> InvokeMessage<R> {
         command: String,
         payload: InvokeBody,
         headers: HeaderMap,
+        cancellation: CancellationToken,
     ) -> Self {
+        let scoped_state = webview.scoped_state_managers();
+        let codec = BodyCodec::negotiate(&headers);
         Self {
             webview,
             state,
             command,
             payload,
             headers,
+            codec,
+            cancellation,
+            scoped_state,
         }
     }
 
@@ -509,8 +653,171 @@ impl<R: Runtime> InvokeMessage<R> {
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
+
+    /// The codec negotiated for the response, see [`BodyCodec::negotiate`].
+    #[inline(always)]
+    pub fn codec(&self) -> BodyCodec {
+        self.codec
+    }
 }
 
 /// The `Callback` type is the return value of the `transformCallback` JavaScript function.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct CallbackFn(pub u32);
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{CallbackFn, InvokeBody};
+    use crate::{
+        test::{get_ipc_response, mock_builder, mock_context, noop_assets},
+        webview::InvokeRequest,
+        Manager, State, WebviewWindowBuilder,
+    };
+
+    #[crate::command(root = "crate", timeout = "10ms")]
+    async fn hang() {
+        std::future::pending::<()>().await
+    }
+
+    #[crate::command(root = "crate")]
+    async fn slow() {
+        tokio::time::sleep(Duration::from_secs(5)).await
+    }
+
+    struct Scope(&'static str);
+
+    #[crate::command(root = "crate")]
+    fn scope(scope: State<'_, Scope>) -> &'static str {
+        scope.0
+    }
+
+    #[cfg(feature = "ipc-msgpack")]
+    #[crate::command(root = "crate")]
+    fn sum(values: Vec<u32>, offset: Option<u32>) -> u32 {
+        values.iter().sum::<u32>() + offset.unwrap_or_default()
+    }
+
+    #[cfg(feature = "ipc-msgpack")]
+    #[crate::command(root = "crate")]
+    async fn sum_async(values: Vec<u32>, offset: Option<u32>) -> u32 {
+        sum(values, offset)
+    }
+
+    #[cfg(feature = "ipc-msgpack")]
+    #[test]
+    fn msgpack_command() {
+        use super::BodyCodec;
+
+        let app = mock_builder()
+            .invoke_handler(crate::generate_handler![sum, sum_async])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/msgpack"),
+        );
+        for cmd in ["sum", "sum_async"] {
+            let body = rmp_serde::to_vec_named(&json!({ "values": [1, 2, 3] })).unwrap();
+            let response = get_ipc_response(
+                &webview,
+                InvokeRequest {
+                    cmd: cmd.into(),
+                    callback: CallbackFn(0),
+                    error: CallbackFn(1),
+                    url: "http://tauri.localhost".parse().unwrap(),
+                    body: InvokeBody::Encoded(BodyCodec::MsgPack, body),
+                    headers: headers.clone(),
+                },
+            )
+            .unwrap();
+            assert!(matches!(
+                response,
+                InvokeBody::Encoded(BodyCodec::MsgPack, _)
+            ));
+            assert_eq!(response.deserialize::<u32>().unwrap(), 6);
+        }
+    }
+
+    #[test]
+    fn command_timeout() {
+        let app = mock_builder()
+            .invoke_timeout(Duration::from_millis(10))
+            .invoke_handler(crate::generate_handler![hang, slow])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        for cmd in ["hang", "slow"] {
+            let response = get_ipc_response(
+                &webview,
+                InvokeRequest {
+                    cmd: cmd.into(),
+                    callback: CallbackFn(0),
+                    error: CallbackFn(1),
+                    url: "http://tauri.localhost".parse().unwrap(),
+                    body: InvokeBody::default(),
+                    headers: Default::default(),
+                },
+            )
+            .map(|body| body.deserialize::<JsonValue>().unwrap());
+            assert_eq!(
+                response,
+                Err(json!({
+                    "code": "COMMAND_TIMEOUT",
+                    "message": format!("command {cmd} timed out after 10ms"),
+                }))
+            );
+        }
+    }
+
+    #[test]
+    fn scoped_state() {
+        let app = mock_builder()
+            .invoke_handler(crate::generate_handler![scope])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        app.manage(Scope("app"));
+
+        let webview = WebviewWindowBuilder::new(&app, "webview", Default::default())
+            .build()
+            .unwrap();
+        assert!(webview.as_ref().window().manage_scoped(Scope("window")));
+        assert!(webview.as_ref().manage_scoped(Scope("webview")));
+        assert!(!webview.as_ref().manage_scoped(Scope("duplicate")));
+        let window = WebviewWindowBuilder::new(&app, "window", Default::default())
+            .build()
+            .unwrap();
+        assert!(window.as_ref().window().manage_scoped(Scope("window")));
+        let plain = WebviewWindowBuilder::new(&app, "plain", Default::default())
+            .build()
+            .unwrap();
+
+        for (webview, expected) in [(&webview, "webview"), (&window, "window"), (&plain, "app")] {
+            assert_eq!(webview.as_ref().scoped_state::<Scope>().0, expected);
+            let response = get_ipc_response(
+                webview,
+                InvokeRequest {
+                    cmd: "scope".into(),
+                    callback: CallbackFn(0),
+                    error: CallbackFn(1),
+                    url: "http://tauri.localhost".parse().unwrap(),
+                    body: InvokeBody::default(),
+                    headers: Default::default(),
+                },
+            )
+            .map(|body| body.deserialize::<JsonValue>().unwrap());
+            assert_eq!(response, Ok(json!(expected)));
+        }
+    }
+}
@@ -9,9 +9,10 @@ use http::{
     header::{ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_TYPE},
     HeaderValue, Method, Request, StatusCode,
 };
+use serde_json::Value as JsonValue;
 use url::Url;
 
-use super::{CallbackFn, InvokeBody, InvokeResponse};
+use super::{codes, BodyCodec, CallbackFn, InvokeBody, InvokeError, InvokeResponse, IpcError};
 
 const TAURI_CALLBACK_HEADER_NAME: &str = "Tauri-Callback";
 const TAURI_ERROR_HEADER_NAME: &str = "Tauri-Error";
@@ -47,12 +48,16 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
                 if let Some(webview) = manager.get_webview(&label) {
                     match parse_invoke_request(&manager, request) {
                         Ok(request) => {
+                            let codec = BodyCodec::negotiate(&request.headers);
+
                             #[cfg(feature = "tracing")]
                             span.record(
                                 "request",
                                 match &request.body {
                                     InvokeBody::Json(j) => serde_json::to_string(j).unwrap(),
-                                    InvokeBody::Raw(b) => serde_json::to_string(b).unwrap(),
+                                    InvokeBody::Raw(b) | InvokeBody::Encoded(_, b) => {
+                                        serde_json::to_string(b).unwrap()
+                                    }
                                 },
                             );
                             #[cfg(feature = "tracing")]
@@ -78,32 +83,27 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
                                     .entered();
 
                                     let (mut response, mime_type) = match response {
-                                        InvokeResponse::Ok(InvokeBody::Json(v)) => (
-                                            http::Response::new(
-                                                serde_json::to_vec(&v).unwrap().into(),
-                                            ),
-                                            mime::APPLICATION_JSON,
-                                        ),
+                                        InvokeResponse::Ok(InvokeBody::Json(v)) => {
+                                            encode_response(codec, &v, StatusCode::OK)
+                                        }
                                         InvokeResponse::Ok(InvokeBody::Raw(v)) => (
                                             http::Response::new(v.into()),
-                                            mime::APPLICATION_OCTET_STREAM,
+                                            mime::APPLICATION_OCTET_STREAM.essence_str(),
                                         ),
+                                        InvokeResponse::Ok(InvokeBody::Encoded(codec, v)) => {
+                                            (http::Response::new(v.into()), codec.mime_type())
+                                        }
                                         InvokeResponse::Err(e) => {
-                                            let mut response = http::Response::new(
-                                                serde_json::to_vec(&e.0).unwrap().into(),
-                                            );
-                                            *response.status_mut() = StatusCode::BAD_REQUEST;
-                                            (response, mime::APPLICATION_JSON)
+                                            encode_response(codec, &e.0, StatusCode::BAD_REQUEST)
                                         }
                                     };
 
                                     #[cfg(feature = "tracing")]
-                                    response_span.record("mime_type", mime_type.essence_str());
+                                    response_span.record("mime_type", mime_type);
 
-                                    response.headers_mut().insert(
-                                        CONTENT_TYPE,
-                                        HeaderValue::from_str(mime_type.essence_str()).unwrap(),
-                                    );
+                                    response
+                                        .headers_mut()
+                                        .insert(CONTENT_TYPE, HeaderValue::from_static(mime_type));
 
                                     respond(response);
                                 }),
@@ -140,7 +140,7 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
                 r.headers_mut().insert(
                     ACCESS_CONTROL_ALLOW_HEADERS,
                     HeaderValue::from_static(
-                        "Content-Type, Tauri-Callback, Tauri-Error, Tauri-Channel-Id",
+                        "Content-Type, Accept, Tauri-Callback, Tauri-Error, Tauri-Channel-Id",
                     ),
                 );
                 respond(r);
@@ -164,6 +164,74 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
     })
 }
 
+/// Encodes a response body with the negotiated codec.
+///
+/// If the body cannot be encoded, responds with an [`IpcError`] serialized as JSON
+/// and a `500 Internal Server Error` status so the invoke is rejected.
+fn encode_response(
+    codec: BodyCodec,
+    value: &JsonValue,
+    status: StatusCode,
+) -> (http::Response<Cow<'static, [u8]>>, &'static str) {
+    match codec.serialize(value) {
+        Ok(body) => {
+            let mut response = http::Response::new(body.into());
+            *response.status_mut() = status;
+            (response, codec.mime_type())
+        }
+        Err(e) => {
+            let error = IpcError::new(
+                codes::RESPONSE_ENCODING_FAILED,
+                format!(
+                    "failed to encode the response as {}: {e}",
+                    codec.mime_type()
+                ),
+            );
+            let mut response = http::Response::new(
+                serde_json::to_vec(&error)
+                    .expect("failed to serialize IPC error")
+                    .into(),
+            );
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            (response, mime::APPLICATION_JSON.essence_str())
+        }
+    }
+}
+
+/// Encodes a response sent through the postMessage interface with the negotiated codec.
+///
+/// Binary bodies are sent as bytes, so the rejections are encoded as an array of bytes as well.
+fn encode_post_message_response(codec: BodyCodec, response: InvokeResponse) -> InvokeResponse {
+    if codec == BodyCodec::Json {
+        return response;
+    }
+
+    let encoded = match response {
+        InvokeResponse::Ok(InvokeBody::Json(v)) => codec
+            .serialize(&v)
+            .map(|body| InvokeResponse::Ok(InvokeBody::Raw(body))),
+        InvokeResponse::Err(e) => codec.serialize(&e.0).map(|body| {
+            InvokeResponse::Err(InvokeError(JsonValue::Array(
+                body.into_iter().map(Into::into).collect(),
+            )))
+        }),
+        response => return response,
+    };
+
+    encoded.unwrap_or_else(|e| {
+        InvokeResponse::Err(
+            IpcError::new(
+                codes::RESPONSE_ENCODING_FAILED,
+                format!(
+                    "failed to encode the response as {}: {e}",
+                    codec.mime_type()
+                ),
+            )
+            .into(),
+        )
+    })
+}
+
 fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager<R>, label: &str) {
     if let Some(webview) = manager.get_webview(label) {
         #[cfg(feature = "tracing")]
@@ -259,15 +327,28 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
             serde_json::from_str::<Message>(request.body()).map_err(Into::into)
         });
 
+        let message = message.and_then(|message| {
+            let headers = message.options.map(|o| o.headers.0).unwrap_or_default();
+            let body = match (BodyCodec::from_content_type(&headers), message.payload) {
+                // postMessage only sends strings, so binary bodies are sent as arrays of bytes
+                (Some(codec), payload @ JsonValue::Array(_)) if codec != BodyCodec::Json => {
+                    InvokeBody::Encoded(codec, serde_json::from_value(payload)?)
+                }
+                (_, payload) => payload.into(),
+            };
+            Ok((message.cmd, message.callback, message.error, body, headers))
+        });
+
         match message {
-            Ok(message) => {
+            Ok((cmd, callback, error, body, headers)) => {
+                let codec = BodyCodec::negotiate(&headers);
                 let request = InvokeRequest {
-                    cmd: message.cmd,
-                    callback: message.callback,
-                    error: message.error,
+                    cmd,
+                    callback,
+                    error,
                     url: Url::parse(&request.uri().to_string()).expect("invalid IPC request URL"),
-                    body: message.payload.into(),
-                    headers: message.options.map(|o| o.headers.0).unwrap_or_default(),
+                    body,
+                    headers,
                 };
 
                 #[cfg(feature = "tracing")]
@@ -283,7 +364,6 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
                             Channel,
                         };
                         use crate::sealed::ManagerBase;
-                        use serde_json::Value as JsonValue;
 
                         #[cfg(feature = "tracing")]
                         let _respond_span = tracing::trace_span!(
@@ -292,6 +372,8 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
                         )
                         .entered();
 
+                        let response = encode_post_message_response(codec, response);
+
                         // the channel data command is the only command that uses a custom protocol on Linux
                         if webview.manager().webview.invoke_responder.is_none()
                             && cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
@@ -320,6 +402,8 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
                                         mime::APPLICATION_JSON,
                                     InvokeResponse::Ok(InvokeBody::Raw(_)) =>
                                         mime::APPLICATION_OCTET_STREAM,
+                                    InvokeResponse::Ok(InvokeBody::Encoded(..)) =>
+                                        mime::APPLICATION_OCTET_STREAM,
                                     InvokeResponse::Err(_) => mime::APPLICATION_JSON,
                                 }
                                 .essence_str()
@@ -345,7 +429,8 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
                                         )
                                     }
                                 }
-                                InvokeResponse::Ok(InvokeBody::Raw(v)) => {
+                                InvokeResponse::Ok(InvokeBody::Raw(v))
+                                | InvokeResponse::Ok(InvokeBody::Encoded(_, v)) => {
                                     if cfg!(target_os = "macos") || cfg!(target_os = "ios") {
                                         responder_eval(
                                             &webview,
@@ -461,12 +546,10 @@ fn parse_invoke_request<R: Runtime>(
 
     let body = if content_type == mime::APPLICATION_OCTET_STREAM {
         body.into()
-    } else if content_type == mime::APPLICATION_JSON {
+    } else if let Some(codec) = BodyCodec::from_mime(content_type.essence_str()) {
         // if the platform does not support request body, we ignore it
         if has_payload {
-            serde_json::from_slice::<serde_json::Value>(&body)
-                .map_err(|e| e.to_string())?
-                .into()
+            codec.read(body)?
         } else {
             serde_json::Value::Object(Default::default()).into()
         }
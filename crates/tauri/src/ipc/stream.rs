@@ -0,0 +1,242 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::{
+    command,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    Manager, Runtime, State, Webview,
+};
+
+use super::{InvokeBody, InvokeError, IpcResponse};
+
+pub const STREAM_PLUGIN_NAME: &str = "__TAURI_STREAM__";
+// TODO: ideally these consts reference STREAM_PLUGIN_NAME
+pub const NEXT_STREAM_ITEM_COMMAND: &str = "plugin:__TAURI_STREAM__|next";
+pub const CLOSE_STREAM_COMMAND: &str = "plugin:__TAURI_STREAM__|close";
+
+static STREAM_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+type BoxedStream = Pin<Box<dyn Stream<Item = Result<InvokeBody, InvokeError>> + Send>>;
+
+/// Maps a stream id to a stream returned by a command that the JavaScript side is iterating.
+#[derive(Default, Clone)]
+pub(crate) struct IpcStreams(Arc<Mutex<HashMap<u32, PendingStream>>>);
+
+struct PendingStream {
+    webview: String,
+    stream: Arc<tokio::sync::Mutex<BoxedStream>>,
+}
+
+impl IpcStreams {
+    fn get(&self, webview: &str, id: u32) -> Option<Arc<tokio::sync::Mutex<BoxedStream>>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&id)
+            .filter(|pending| pending.webview == webview)
+            .map(|pending| pending.stream.clone())
+    }
+
+    fn remove(&self, id: u32) {
+        self.0.lock().unwrap().remove(&id);
+    }
+
+    /// Drops all streams that were returned to the webview with the given label.
+    pub(crate) fn clear_webview(&self, label: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|_, pending| pending.webview != label);
+    }
+}
+
+/// The response of a command that returned a stream.
+///
+/// The JavaScript `invoke` function turns it into an async iterator.
+#[derive(Serialize)]
+pub(crate) struct StreamHandle {
+    #[serde(rename = "__TAURI_STREAM__")]
+    id: u32,
+}
+
+/// Stores the stream so the given webview can iterate it.
+pub(crate) fn register<R, S, T, E>(webview: &Webview<R>, stream: S) -> StreamHandle
+where
+    R: Runtime,
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: IpcResponse + 'static,
+    E: Into<InvokeError> + 'static,
+{
+    let id = STREAM_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let stream = stream.map(|item| {
+        item.map_err(Into::into)
+            .and_then(|value| value.body().map_err(InvokeError::from_error))
+    });
+
+    if let Some(streams) = webview.try_state::<IpcStreams>() {
+        streams.0.lock().unwrap().insert(
+            id,
+            PendingStream {
+                webview: webview.label().into(),
+                stream: Arc::new(tokio::sync::Mutex::new(Box::pin(stream))),
+            },
+        );
+    }
+
+    StreamHandle { id }
+}
+
+#[derive(Serialize)]
+struct StreamItem {
+    done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<JsonValue>,
+}
+
+#[command(root = "crate")]
+async fn next<R: Runtime>(
+    webview: Webview<R>,
+    streams: State<'_, IpcStreams>,
+    id: u32,
+) -> Result<StreamItem, InvokeError> {
+    let stream = streams
+        .get(webview.label(), id)
+        .ok_or_else(|| InvokeError::from("stream not found"))?;
+
+    let item = stream.lock().await.next().await;
+    match item {
+        Some(Ok(body)) => Ok(StreamItem {
+            done: false,
+            value: Some(body.into_json()),
+        }),
+        Some(Err(error)) => {
+            streams.remove(id);
+            Err(error)
+        }
+        None => {
+            streams.remove(id);
+            Ok(StreamItem {
+                done: true,
+                value: None,
+            })
+        }
+    }
+}
+
+#[command(root = "crate")]
+fn close<R: Runtime>(webview: Webview<R>, streams: State<'_, IpcStreams>, id: u32) {
+    if streams.get(webview.label(), id).is_some() {
+        streams.remove(id);
+    }
+}
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    PluginBuilder::new(STREAM_PLUGIN_NAME)
+        .invoke_handler(crate::generate_handler![next, close])
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::Stream;
+    use serde_json::{json, Value as JsonValue};
+
+    use crate::{
+        ipc::{CallbackFn, InvokeBody},
+        test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime},
+        webview::InvokeRequest,
+        WebviewWindow, WebviewWindowBuilder,
+    };
+
+    #[crate::command(root = "crate")]
+    fn count(to: u32) -> impl Stream<Item = Result<u32, String>> {
+        futures_util::stream::iter((1..=to).map(Ok))
+    }
+
+    #[crate::command(root = "crate")]
+    fn delayed() -> impl Stream<Item = Result<u32, String>> {
+        futures_util::stream::once(async {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok(1)
+        })
+    }
+
+    fn invoke(webview: &WebviewWindow<MockRuntime>, cmd: &str, body: JsonValue) -> JsonValue {
+        get_ipc_response(
+            webview,
+            InvokeRequest {
+                cmd: cmd.into(),
+                callback: CallbackFn(0),
+                error: CallbackFn(1),
+                url: "http://tauri.localhost".parse().unwrap(),
+                body: InvokeBody::Json(body),
+                headers: Default::default(),
+            },
+        )
+        .unwrap()
+        .deserialize()
+        .unwrap()
+    }
+
+    #[test]
+    fn iterates_stream() {
+        let app = mock_builder()
+            .invoke_handler(crate::generate_handler![count])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let handle = invoke(&webview, "count", json!({ "to": 2 }));
+        let id = handle["__TAURI_STREAM__"].clone();
+        assert!(id.is_number());
+
+        for expected in [
+            json!({ "done": false, "value": 1 }),
+            json!({ "done": false, "value": 2 }),
+            json!({ "done": true }),
+        ] {
+            assert_eq!(
+                invoke(
+                    &webview,
+                    super::NEXT_STREAM_ITEM_COMMAND,
+                    json!({ "id": id })
+                ),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn default_timeout_does_not_apply_to_items() {
+        let app = mock_builder()
+            .invoke_timeout(std::time::Duration::from_millis(10))
+            .invoke_handler(crate::generate_handler![delayed])
+            .build(mock_context(noop_assets()))
+            .unwrap();
+        let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let handle = invoke(&webview, "delayed", json!({}));
+        assert_eq!(
+            invoke(
+                &webview,
+                super::NEXT_STREAM_ITEM_COMMAND,
+                json!({ "id": handle["__TAURI_STREAM__"] })
+            ),
+            json!({ "done": false, "value": 1 })
+        );
+    }
+}
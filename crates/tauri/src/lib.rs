@@ -31,6 +31,9 @@
 //! - **image-ico**: Adds support to parse `.ico` image, see [`Image`].
 //! - **image-png**: Adds support to parse `.png` image, see [`Image`].
 //! - **macos-proxy**: Adds support for [`WebviewBuilder::proxy_url`] on macOS. Requires macOS 14+.
+//! - **ipc-msgpack**: Adds support to the `application/msgpack` content type on IPC request and response bodies, see [`ipc::BodyCodec`].
+//! - **ipc-cbor**: Adds support to the `application/cbor` content type on IPC request and response bodies, see [`ipc::BodyCodec`].
+//! - **ipc-bindings**: Enables [`generate_bindings!`] and the [`ipc::bindings`] module to generate TypeScript bindings for commands.
 //!
 //! ## Cargo allowlist features
 //!
@@ -64,10 +67,13 @@ pub use cocoa;
 pub use embed_plist;
 pub use error::{Error, Result};
 use ipc::{RuntimeAuthority, RuntimeCapability};
-pub use resources::{Resource, ResourceId, ResourceTable};
+pub use resources::{Resource, ResourceId, ResourceInfo, ResourceLimits, ResourceTable};
 #[cfg(target_os = "ios")]
 #[doc(hidden)]
 pub use swift_rs;
+#[cfg(feature = "ipc-bindings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ipc-bindings")))]
+pub use tauri_macros::generate_bindings;
 #[cfg(mobile)]
 pub use tauri_macros::mobile_entry_point;
 pub use tauri_macros::{command, generate_handler};
@@ -189,9 +195,10 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     fmt::{self, Debug},
+    future::Future,
     sync::MutexGuard,
 };
-use utils::assets::{AssetKey, CspHash, EmbeddedAssets};
+use utils::assets::{pack::PackedAssets, AssetEncoding, AssetKey, CspHash, EmbeddedAssets};
 
 #[cfg(feature = "wry")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
@@ -204,7 +211,7 @@ pub use runtime::ActivationPolicy;
 #[cfg(target_os = "macos")]
 pub use self::utils::TitleBarStyle;
 
-pub use self::event::{Event, EventId, EventTarget};
+pub use self::event::{Event, EventId, EventRequestId, EventStream, EventTarget, OnceEvent};
 pub use {
     self::app::{
         App, AppHandle, AssetResolver, Builder, CloseRequestApi, RunEvent, WebviewEvent,
@@ -219,7 +226,7 @@ pub use {
         window::{CursorIcon, DragDropEvent},
         DeviceEventFilter, Rect, UserAttentionType,
     },
-    self::state::{State, StateManager},
+    self::state::{ScopedState, State, StateChange, StateManager},
     self::utils::{
         config::{Config, WebviewUrl},
         Env, PackageInfo, Theme,
@@ -344,6 +351,25 @@ pub trait Assets<R: Runtime>: Send + Sync + 'static {
     /// Get the content of the passed [`AssetKey`].
     fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>>;
 
+    /// Get the content of the passed [`AssetKey`] as stored with the given encoding, without decoding it.
+    ///
+    /// Used to serve compressed assets as is when the webview accepts the encoding.
+    /// The borrowed contents are served without copying them, so they must live as long as the program,
+    /// e.g. be embedded in the binary; return [`Cow::Owned`] otherwise.
+    /// Returns `None` by default, falling back to [`Self::get`].
+    fn get_encoded(&self, key: &AssetKey, encoding: AssetEncoding) -> Option<Cow<'static, [u8]>> {
+        let _ = (key, encoding);
+        None
+    }
+
+    /// Gets a hash of the content of the passed [`AssetKey`], used as a strong `ETag` by the `tauri://` protocol.
+    ///
+    /// Returns `None` by default, in which case the asset is served without validators.
+    fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+        let _ = key;
+        None
+    }
+
     /// Iterator for the assets.
     fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_>;
 
@@ -356,6 +382,14 @@ impl<R: Runtime> Assets<R> for EmbeddedAssets {
         EmbeddedAssets::get(self, key)
     }
 
+    fn get_encoded(&self, key: &AssetKey, encoding: AssetEncoding) -> Option<Cow<'static, [u8]>> {
+        EmbeddedAssets::get_encoded(self, key, encoding)
+    }
+
+    fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+        EmbeddedAssets::content_hash(self, key)
+    }
+
     fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
         EmbeddedAssets::iter(self)
     }
@@ -365,6 +399,41 @@ impl<R: Runtime> Assets<R> for EmbeddedAssets {
     }
 }
 
+impl<R: Runtime> Assets<R> for PackedAssets {
+    fn setup(&self, app: &App<R>) {
+        let resource_dir = app
+            .path()
+            .resource_dir()
+            .expect("failed to resolve the resource directory");
+        if let Err(e) = self.load(&resource_dir) {
+            panic!(
+                "failed to load the asset pack from {}: {e}",
+                resource_dir.display()
+            );
+        }
+    }
+
+    fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+        PackedAssets::get(self, key)
+    }
+
+    fn get_encoded(&self, key: &AssetKey, encoding: AssetEncoding) -> Option<Cow<'static, [u8]>> {
+        PackedAssets::get_encoded(self, key, encoding)
+    }
+
+    fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+        PackedAssets::content_hash(self, key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
+        PackedAssets::iter(self)
+    }
+
+    fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+        PackedAssets::csp_hashes(self, html_path)
+    }
+}
+
 /// User supplied data required inside of a Tauri application.
 ///
 /// # Stability
@@ -607,6 +676,56 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
         self.manager().once(event.into(), EventTarget::Any, handler)
     }
 
+    /// Listen to an emitted event on the given [target](EventTarget) as a [`futures_util::Stream`].
+    ///
+    /// The listener is removed when the returned [`EventStream`] is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use futures_util::StreamExt;
+    /// use tauri::{EventTarget, Manager};
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let mut progress = app.listen_stream("download-progress", EventTarget::window("main"));
+    ///     tauri::async_runtime::spawn(async move {
+    ///       while let Some(event) = progress.next().await {
+    ///         println!("progress: {}", event.payload());
+    ///       }
+    ///     });
+    ///     Ok(())
+    ///   });
+    /// ```
+    fn listen_stream(
+        &self,
+        event: impl Into<String>,
+        target: impl Into<EventTarget>,
+    ) -> EventStream {
+        self.manager().listen_stream(event.into(), target.into())
+    }
+
+    /// Returns a future resolving to the next event emitted on the given [target](EventTarget).
+    ///
+    /// The listener is removed when the returned [`OnceEvent`] is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tauri::{EventTarget, Manager};
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let ready = app.once_async("ready", EventTarget::window("main"));
+    ///     tauri::async_runtime::spawn(async move {
+    ///       let event = ready.await;
+    ///       println!("main window is ready: {}", event.payload());
+    ///     });
+    ///     Ok(())
+    ///   });
+    /// ```
+    fn once_async(&self, event: impl Into<String>, target: impl Into<EventTarget>) -> OnceEvent {
+        self.manager().once_async(event.into(), target.into())
+    }
+
     /// Emits an event to all [targets](EventTarget).
     ///
     /// # Examples
@@ -666,18 +785,10 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
             // if targeting all, emit to all using emit without filter
             EventTarget::Any => self.manager().emit(event, payload),
 
-            // if targeting any label, emit using emit_filter and filter labels
-            EventTarget::AnyLabel {
-                label: target_label,
-            } => self.manager().emit_filter(event, payload, |t| match t {
-                EventTarget::Window { label }
-                | EventTarget::Webview { label }
-                | EventTarget::WebviewWindow { label } => label == &target_label,
-                _ => false,
-            }),
-
-            // otherwise match same target
-            _ => self.manager().emit_filter(event, payload, |t| t == &target),
+            // otherwise only emit to the listeners of the matching targets
+            _ => self
+                .manager()
+                .emit_filter(event, payload, |t| target.matches(t)),
         }
     }
 
@@ -711,6 +822,133 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
         self.manager().emit_filter(event, payload, filter)
     }
 
+    /// Emits an event to all [targets](EventTarget) and retains it,
+    /// so the Rust and JavaScript listeners registered later receive it right away.
+    ///
+    /// See [`Self::emit_retained_to`] for more information.
+    fn emit_retained<S: Serialize + Clone>(&self, event: &str, payload: S) -> Result<()> {
+        self.emit_retained_to(EventTarget::Any, event, payload)
+    }
+
+    /// Emits an event to all [targets](EventTarget) matching the given target and retains it,
+    /// so the Rust and JavaScript listeners registered later receive it right away.
+    ///
+    /// Only the last payload of each event name and target is retained.
+    /// When the capacity set with [`Builder::retained_events_capacity`] is reached
+    /// the least recently retained events are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tauri::{EventTarget, Manager};
+    ///
+    /// #[tauri::command]
+    /// fn login(app: tauri::AppHandle, user: String) {
+    ///   // webviews created after the login still get the current user when they listen to the event
+    ///   app.emit_retained("auth-changed", Some(user));
+    /// }
+    ///
+    /// #[tauri::command]
+    /// fn logout(app: tauri::AppHandle) {
+    ///   app.emit("auth-changed", None::<String>);
+    ///   app.clear_retained_event("auth-changed");
+    /// }
+    /// ```
+    fn emit_retained_to<I, S>(&self, target: I, event: &str, payload: S) -> Result<()>
+    where
+        I: Into<EventTarget>,
+        S: Serialize + Clone,
+    {
+        let target = target.into();
+        self.manager()
+            .retain_event(event, target.clone(), payload.clone())?;
+        self.emit_to(target, event, payload)
+    }
+
+    /// Emits an event to the listeners of the given target and waits for the first reply.
+    ///
+    /// Listeners answer with [`Self::reply`] in Rust, or with the `reply` function of the `@tauri-apps/api/event` module in JavaScript.
+    /// Fails with [`Error::NoEventListener`] if no listener of the target handles the event,
+    /// and with [`Error::EventRequestTimeout`] if it is not answered in 30 seconds.
+    /// See [`Self::request_with_timeout`] to use another timeout.
+    ///
+    /// # Examples
+    /// ```
+    /// use tauri::{EventTarget, Manager};
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let handle = app.handle().clone();
+    ///     app.listen_any("get-theme", move |event| {
+    ///       handle.reply(&event, "dark").unwrap();
+    ///     });
+    ///
+    ///     let request = app.request(EventTarget::app(), "get-theme", ());
+    ///     tauri::async_runtime::spawn(async move {
+    ///       let theme = request.await.unwrap();
+    ///       println!("the theme is {theme}");
+    ///     });
+    ///     Ok(())
+    ///   });
+    /// ```
+    fn request<I, S>(
+        &self,
+        target: I,
+        event: &str,
+        payload: S,
+    ) -> impl Future<Output = Result<serde_json::Value>> + Send
+    where
+        I: Into<EventTarget>,
+        S: Serialize,
+    {
+        self.request_with_timeout(target, event, payload, event::DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Same as [`Self::request`], failing with [`Error::EventRequestTimeout`] if the request is not answered within the given timeout.
+    fn request_with_timeout<I, S>(
+        &self,
+        target: I,
+        event: &str,
+        payload: S,
+        timeout: std::time::Duration,
+    ) -> impl Future<Output = Result<serde_json::Value>> + Send
+    where
+        I: Into<EventTarget>,
+        S: Serialize,
+    {
+        let event = event.to_string();
+        let request = self.manager().request(target.into(), &event, payload);
+        async move {
+            // the guard removes the pending request when it times out or the future is dropped
+            let (_guard, receiver) = request?;
+            match tokio::time::timeout(timeout, receiver).await {
+                Ok(Ok(reply)) => Ok(reply),
+                _ => Err(Error::EventRequestTimeout(event)),
+            }
+        }
+    }
+
+    /// Replies to an event emitted with [`Self::request`].
+    ///
+    /// Only the first reply is delivered, the replies to requests that were already answered or timed out are ignored.
+    /// Fails with [`Error::NotAnEventRequest`] if the event is not a request.
+    fn reply<S: Serialize>(&self, event: &Event, payload: S) -> Result<()> {
+        let request_id = event.request_id().ok_or(Error::NotAnEventRequest)?;
+        self.manager()
+            .listeners()
+            .reply(request_id, serde_json::to_value(payload)?);
+        Ok(())
+    }
+
+    /// Removes the retained events with the given name.
+    fn clear_retained_event(&self, event: &str) {
+        self.manager().listeners().clear_retained(Some(event))
+    }
+
+    /// Removes all retained events.
+    fn clear_retained_events(&self) {
+        self.manager().listeners().clear_retained(None)
+    }
+
     /// Fetch a single window from the manager.
     #[cfg(feature = "unstable")]
     #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
@@ -865,23 +1103,33 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
 
     /// Retrieves the managed state for the type `T`.
     ///
+    /// A state added with [`crate::Builder::manage_async`] or [`crate::Builder::manage_lazy`]
+    /// blocks until its initialization finishes.
+    ///
     /// # Panics
     ///
-    /// Panics if the state for the type `T` has not been previously [managed](Self::manage).
+    /// Panics if the state for the type `T` has not been previously [managed](Self::manage),
+    /// or if its initialization failed.
     /// Use [try_state](Self::try_state) for a non-panicking version.
     fn state<T>(&self) -> State<'_, T>
     where
         T: Send + Sync + 'static,
     {
-        self.manager()
-            .state
-            .try_get()
-            .expect("state() called before manage() for given type")
+        match self.manager().state.wait() {
+            Ok(state) => state,
+            Err(crate::state::StateError::InitFailed(error)) => {
+                panic!("state() failed to initialize the state for given type: {error}")
+            }
+            Err(_) => panic!("state() called before manage() for given type"),
+        }
     }
 
     /// Attempts to retrieve the managed state for the type `T`.
     ///
     /// Returns `Some` if the state has previously been [managed](Self::manage). Otherwise returns `None`.
+    ///
+    /// A state added with [`crate::Builder::manage_async`] or [`crate::Builder::manage_lazy`] is `None` until its initialization finishes;
+    /// this call starts the lazy initialization in the background and does not wait for it.
     fn try_state<T>(&self) -> Option<State<'_, T>>
     where
         T: Send + Sync + 'static,
@@ -889,6 +1137,85 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
         self.manager().state.try_get()
     }
 
+    /// Replaces the managed state for the type `T`, or manages it if it was not managed yet.
+    ///
+    /// Returns `true` if a previous value was replaced.
+    ///
+    /// The [`State`] guards and references obtained before the call keep pointing to the previous value,
+    /// so commands that are still running finish with it,
+    /// while the commands invoked after the call receive the new value.
+    /// Since those borrows can last until the application exits, the previous value is only dropped then;
+    /// release its resources explicitly if needed, e.g. by closing a connection pool before replacing it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tauri::Manager;
+    ///
+    /// struct Profile(String);
+    ///
+    /// #[tauri::command]
+    /// fn switch_profile(app: tauri::AppHandle, name: String) {
+    ///   app.replace_state(Profile(name));
+    /// }
+    ///
+    /// tauri::Builder::default()
+    ///   .manage(Profile("default".into()))
+    ///   .invoke_handler(tauri::generate_handler![switch_profile]);
+    /// ```
+    fn replace_state<T>(&self, state: T) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.manager().state().replace(state)
+    }
+
+    /// Removes the managed state for the type `T`.
+    ///
+    /// Returns `true` if the state was managed.
+    ///
+    /// Like [`Self::replace_state`], the existing [`State`] borrows keep pointing to the removed value,
+    /// which is only dropped when the application exits.
+    /// Commands that use the state fail with [`ipc::codes::STATE_NOT_MANAGED`] until it is managed again.
+    fn unmanage<T>(&self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.manager().state().unmanage::<T>()
+    }
+
+    /// Registers a handler called when the managed state for the type `T`
+    /// is managed, replaced or removed.
+    ///
+    /// Plugins can use it to react to changes of the state they depend on.
+    /// The handler is called on the thread that changed the state, after the change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tauri::{Manager, StateChange};
+    ///
+    /// struct Profile(String);
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let handle = app.handle().clone();
+    ///     app.on_state_change::<Profile, _>(move |change| {
+    ///       if change == StateChange::Replaced {
+    ///         println!("switched to profile {}", handle.state::<Profile>().0);
+    ///       }
+    ///     });
+    ///     Ok(())
+    ///   });
+    /// ```
+    fn on_state_change<T, F>(&self, handler: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(StateChange) + Send + Sync + 'static,
+    {
+        self.manager().state().on_change::<T, F>(handler)
+    }
+
     /// Get a reference to the resources table of this manager.
     fn resources_table(&self) -> MutexGuard<'_, ResourceTable>;
 
@@ -910,6 +1237,11 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
 
     /// Adds a capability to the app.
     ///
+    /// A capability built with [`CapabilityBuilder::expires_after`](crate::ipc::CapabilityBuilder::expires_after)
+    /// or [`CapabilityBuilder::max_uses`](crate::ipc::CapabilityBuilder::max_uses) is removed
+    /// once it expires or reaches its maximum number of uses,
+    /// emitting the `tauri://capability-expired` event with its identifier.
+    ///
     /// # Examples
     /// ```
     /// use tauri::Manager;
@@ -922,11 +1254,53 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     ///   });
     /// ```
     fn add_capability(&self, capability: impl RuntimeCapability) -> Result<()> {
+        let expires_after = capability.limits().expires_after;
+        self.manager()
+            .runtime_authority
+            .lock()
+            .unwrap()
+            .add_capability(capability)?;
+
+        if let Some(duration) = expires_after {
+            let manager = self.manager_owned();
+            crate::async_runtime::spawn(async move {
+                tokio::time::sleep(duration).await;
+                manager.remove_expired_capabilities();
+            });
+        }
+        Ok(())
+    }
+
+    /// Removes a capability that was added with [`Self::add_capability`].
+    ///
+    /// The commands and scopes it granted are no longer available to the windows and webviews it targeted,
+    /// unless they are granted by another capability.
+    /// The capabilities defined at build time cannot be removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use tauri::{ipc::CapabilityBuilder, Manager};
+    ///
+    /// #[tauri::command]
+    /// fn unlock_admin(app: tauri::AppHandle) -> tauri::Result<()> {
+    ///   app.add_capability(
+    ///     CapabilityBuilder::new("admin")
+    ///       .window("main")
+    ///       .permission("fs:allow-write-text-file"),
+    ///   )
+    /// }
+    ///
+    /// #[tauri::command]
+    /// fn lock_admin(app: tauri::AppHandle) -> tauri::Result<()> {
+    ///   app.remove_capability("admin")
+    /// }
+    /// ```
+    fn remove_capability(&self, identifier: &str) -> Result<()> {
         self.manager()
             .runtime_authority
             .lock()
             .unwrap()
-            .add_capability(capability)
+            .remove_capability(identifier)
     }
 }
 
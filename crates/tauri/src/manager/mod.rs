@@ -3,22 +3,33 @@ use std::{
     collections::HashMap,
     fmt,
     sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
 };
 
+use http::StatusCode;
 use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tokio::sync::oneshot;
 use url::Url;
 
 use tauri_macros::default_runtime;
 use tauri_utils::{
-    assets::{AssetKey, CspHash},
+    assets::{AssetEncoding, AssetKey, CspHash},
     config::{Csp, CspDirectiveSources},
     html::{SCRIPT_NONCE_TOKEN, STYLE_NONCE_TOKEN},
+    routing::{Route, Routes},
 };
 
 use crate::{
     app::{AppHandle, GlobalWebviewEventListener, GlobalWindowEventListener, OnPageLoad},
-    event::{assert_event_name_is_valid, Event, EventId, EventTarget, Listeners},
-    ipc::{Invoke, InvokeHandler, InvokeResponder, RuntimeAuthority},
+    event::{
+        assert_event_name_is_valid, assert_event_pattern_is_valid, Event, EventId, EventStream,
+        EventTarget, Listeners, OnceEvent, RequestGuard,
+    },
+    ipc::{
+        cancel::PendingInvokes, channel::ChannelDataIpcQueue, stream::IpcStreams, Invoke,
+        InvokeHandler, InvokeResponder, IpcMiddleware, RuntimeAuthority,
+    },
     plugin::PluginStore,
     utils::{config::Config, PackageInfo},
     Assets, Context, Pattern, Runtime, StateManager, Window,
@@ -32,6 +43,10 @@ mod tray;
 pub mod webview;
 pub mod window;
 
+/// Emitted with the capability identifier when a capability added at runtime
+/// expires or reaches its maximum number of uses.
+pub(crate) const CAPABILITY_EXPIRED_EVENT: &str = "tauri://capability-expired";
+
 #[derive(Default)]
 /// Spaced and quoted Content-Security-Policy hash values.
 struct CspHashStrings {
@@ -152,13 +167,37 @@ fn replace_csp_nonce(
 }
 
 /// A resolved asset.
+#[non_exhaustive]
 pub struct Asset {
-    /// The asset bytes.
-    pub bytes: Vec<u8>,
+    /// The asset bytes, borrowed when the asset is served as stored, e.g. compressed.
+    pub bytes: Cow<'static, [u8]>,
     /// The asset's mime type.
     pub mime_type: String,
     /// The `Content-Security-Policy` header value.
     pub csp_header: Option<String>,
+    /// The encoding of [`Self::bytes`], for the `Content-Encoding` header.
+    pub content_encoding: Option<AssetEncoding>,
+    /// The strong `ETag` header value, derived from the asset content hash.
+    pub etag: Option<String>,
+    /// The response status, see [`tauri_utils::config::RoutingConfig`].
+    pub status: StatusCode,
+    /// The additional response headers, see [`tauri_utils::config::RoutingConfig::headers`].
+    pub headers: http::HeaderMap,
+}
+
+impl Asset {
+    /// Creates an unencoded asset served with a `200 OK` status.
+    pub fn new(bytes: impl Into<Cow<'static, [u8]>>, mime_type: impl Into<String>) -> Self {
+        Self {
+            bytes: bytes.into(),
+            mime_type: mime_type.into(),
+            csp_header: None,
+            content_encoding: None,
+            etag: None,
+            status: StatusCode::OK,
+            headers: http::HeaderMap::new(),
+        }
+    }
 }
 
 #[default_runtime(crate::Wry, wry)]
@@ -175,6 +214,8 @@ pub struct AppManager<R: Runtime> {
     pub listeners: Listeners,
     pub state: Arc<StateManager>,
     pub config: Config,
+    /// The compiled [`tauri_utils::config::RoutingConfig`].
+    pub(crate) routes: Routes,
     pub assets: Box<dyn Assets<R>>,
 
     pub app_icon: Option<Vec<u8>>,
@@ -218,6 +259,8 @@ impl<R: Runtime> AppManager<R> {
         #[allow(unused_mut)] mut context: Context<R>,
         plugins: PluginStore<R>,
         invoke_handler: Box<InvokeHandler<R>>,
+        ipc_middlewares: Vec<Arc<dyn IpcMiddleware<R>>>,
+        invoke_timeout: Option<Duration>,
         on_page_load: Option<Arc<OnPageLoad<R>>>,
         uri_scheme_protocols: HashMap<String, Arc<webview::UriSchemeProtocol<R>>>,
         state: StateManager,
@@ -245,6 +288,8 @@ impl<R: Runtime> AppManager<R> {
             webview: webview::WebviewManager {
                 webviews: Mutex::default(),
                 invoke_handler,
+                ipc_middlewares: Mutex::new(ipc_middlewares),
+                invoke_timeout,
                 on_page_load,
                 uri_scheme_protocols: Mutex::new(uri_scheme_protocols),
                 event_listeners: Arc::new(webiew_event_listeners),
@@ -268,6 +313,7 @@ impl<R: Runtime> AppManager<R> {
             plugins: Mutex::new(plugins),
             listeners: Listeners::default(),
             state: Arc::new(state),
+            routes: Routes::new(&context.config.app.routing),
             config: context.config,
             assets: context.assets,
             app_icon: context.app_icon,
@@ -332,61 +378,148 @@ impl<R: Runtime> AppManager<R> {
         }
     }
 
-    pub fn get_asset(&self, mut path: String) -> Result<Asset, Box<dyn std::error::Error>> {
+    pub fn get_asset(&self, path: String) -> Result<Asset, Box<dyn std::error::Error>> {
+        self.get_encoded_asset(path, &[], &http::HeaderMap::new())
+    }
+
+    /// Gets the asset on the given path, keeping it in one of the accepted encodings if the assets store it that way.
+    ///
+    /// HTML assets are always decoded to inject the CSP.
+    /// When the request validators match the content hash of the asset,
+    /// it is not read and a `304 Not Modified` asset is returned instead.
+    pub fn get_encoded_asset(
+        &self,
+        mut path: String,
+        accepted_encodings: &[AssetEncoding],
+        request_headers: &http::HeaderMap,
+    ) -> Result<Asset, Box<dyn std::error::Error>> {
         let assets = &self.assets;
-        if path.ends_with('/') {
-            path.pop();
-        }
+        let get = |key: &AssetKey| {
+            let encoded = if key.as_ref().ends_with(".html") {
+                None
+            } else {
+                accepted_encodings.iter().find_map(|encoding| {
+                    assets
+                        .get_encoded(key, *encoding)
+                        .map(|bytes| (bytes, Some(*encoding)))
+                })
+            };
+            encoded.or_else(|| {
+                assets
+                    .get(key)
+                    .map(|bytes| (Cow::Owned(bytes.into_owned()), None))
+            })
+        };
         path = percent_encoding::percent_decode(path.as_bytes())
             .decode_utf8_lossy()
             .to_string();
-        let path = if path.is_empty() {
-            // if the url is `tauri://localhost`, we should load `index.html`
-            "index.html".to_string()
-        } else {
-            // skip leading `/`
-            path.chars().skip(1).collect::<String>()
-        };
 
-        let mut asset_path = AssetKey::from(path.as_str());
+        let headers = self.routes.headers(&path);
 
-        let asset_response = assets
-            .get(&path.as_str().into())
-            .or_else(|| {
-                log::debug!("Asset `{path}` not found; fallback to {path}.html");
-                let fallback = format!("{}.html", path.as_str()).into();
-                let asset = assets.get(&fallback);
-                asset_path = fallback;
-                asset
-            })
-            .or_else(|| {
-                log::debug!(
-                    "Asset `{}` not found; fallback to {}/index.html",
-                    path,
-                    path
-                );
-                let fallback = format!("{}/index.html", path.as_str()).into();
-                let asset = assets.get(&fallback);
-                asset_path = fallback;
-                asset
-            })
-            .or_else(|| {
-                log::debug!("Asset `{}` not found; fallback to index.html", path);
-                let fallback = AssetKey::from("index.html");
-                let asset = assets.get(&fallback);
-                asset_path = fallback;
-                asset
-            })
-            .ok_or_else(|| crate::Error::AssetNotFound(path.clone()))
-            .map(Cow::into_owned);
+        // assets with a content hash are only read after the request validators are checked
+        let lookup = |key: &str| {
+            let key = AssetKey::from(key);
+            match assets.content_hash(&key) {
+                Some(_) => Some(None),
+                None => get(&key).map(Some),
+            }
+        };
+        let (asset_path, asset_response, status) = match self.routes.resolve(&path, lookup) {
+            Route::Asset { key, asset } => (AssetKey::from(key), asset, StatusCode::OK),
+            Route::NotFoundPage { key, asset } => {
+                log::debug!("Asset `{path}` not found; serving the not found page {key}");
+                (AssetKey::from(key), asset, StatusCode::NOT_FOUND)
+            }
+            Route::Redirect {
+                location,
+                permanent,
+            } => {
+                let mut headers = headers;
+                headers.insert(http::header::LOCATION, location.try_into()?);
+                return Ok(Asset {
+                    status: if permanent {
+                        StatusCode::PERMANENT_REDIRECT
+                    } else {
+                        StatusCode::TEMPORARY_REDIRECT
+                    },
+                    headers,
+                    ..Asset::new(Vec::new(), mime::TEXT_PLAIN.essence_str())
+                });
+            }
+            Route::NotFound => {
+                log::debug!("Asset `{path}` not found");
+                return Ok(Asset {
+                    status: StatusCode::NOT_FOUND,
+                    headers,
+                    ..Asset::new(Vec::new(), mime::TEXT_PLAIN.essence_str())
+                });
+            }
+        };
 
         let mut csp_header = None;
         let is_html = asset_path.as_ref().ends_with(".html");
+        let etag = |encoding: Option<AssetEncoding>| {
+            // the CSP nonces injected in HTML change on every response
+            if is_html && self.csp().is_some() {
+                return None;
+            }
+            let hash = assets.content_hash(&asset_path)?;
+            Some(match encoding {
+                Some(encoding) => format!("\"{hash}-{}\"", encoding.as_str()),
+                None => format!("\"{hash}\""),
+            })
+        };
+
+        if status == StatusCode::OK {
+            let encodings = if is_html { &[][..] } else { accepted_encodings };
+            if let Some(etag) = std::iter::once(None)
+                .chain(encodings.iter().copied().map(Some))
+                .filter_map(etag)
+                .find(|etag| {
+                    crate::protocol::cache::is_not_modified(request_headers, Some(etag), None)
+                })
+            {
+                return Ok(Asset {
+                    etag: Some(etag),
+                    status: StatusCode::NOT_MODIFIED,
+                    headers,
+                    ..Asset::new(
+                        Vec::new(),
+                        tauri_utils::mime_type::MimeType::parse(&[], asset_path.as_ref()),
+                    )
+                });
+            }
+        }
+
+        let Some(asset_response) = asset_response.or_else(|| get(&asset_path)) else {
+            log::error!("Asset `{}` could not be read", asset_path.as_ref());
+            return Ok(Asset {
+                status: StatusCode::NOT_FOUND,
+                headers,
+                ..Asset::new(Vec::new(), mime::TEXT_PLAIN.essence_str())
+            });
+        };
 
         match asset_response {
-            Ok(asset) => {
+            (asset, Some(encoding)) => {
+                let mime_type = tauri_utils::mime_type::MimeType::parse(
+                    &encoding.decode_prefix(&asset, 512).unwrap_or_default(),
+                    asset_path.as_ref(),
+                );
+                Ok(Asset {
+                    bytes: asset,
+                    mime_type,
+                    csp_header,
+                    content_encoding: Some(encoding),
+                    etag: etag(Some(encoding)),
+                    status,
+                    headers,
+                })
+            }
+            (asset, None) => {
                 let final_data = if is_html {
-                    let mut asset = String::from_utf8_lossy(&asset).into_owned();
+                    let mut asset = String::from_utf8(asset.into_owned())
+                        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
                     if let Some(csp) = self.csp() {
                         #[allow(unused_mut)]
                         let mut csp_map = set_csp(&mut asset, &self.assets, &asset_path, self, csp);
@@ -401,21 +534,22 @@ impl<R: Runtime> AppManager<R> {
                         csp_header.replace(Csp::DirectiveMap(csp_map).to_string());
                     }
 
-                    asset.as_bytes().to_vec()
+                    Cow::Owned(asset.into_bytes())
                 } else {
                     asset
                 };
-                let mime_type = tauri_utils::mime_type::MimeType::parse(&final_data, &path);
+                let mime_type =
+                    tauri_utils::mime_type::MimeType::parse(&final_data, asset_path.as_ref());
                 Ok(Asset {
-                    bytes: final_data.to_vec(),
+                    bytes: final_data,
                     mime_type,
                     csp_header,
+                    content_encoding: None,
+                    etag: etag(None),
+                    status,
+                    headers,
                 })
             }
-            Err(e) => {
-                log::error!("{:?}", e);
-                Err(Box::new(e))
-            }
         }
     }
 
@@ -455,7 +589,7 @@ impl<R: Runtime> AppManager<R> {
         target: EventTarget,
         handler: F,
     ) -> EventId {
-        assert_event_name_is_valid(&event);
+        assert_event_pattern_is_valid(&event);
         self.listeners().listen(event, target, handler)
     }
 
@@ -469,10 +603,20 @@ impl<R: Runtime> AppManager<R> {
         target: EventTarget,
         handler: F,
     ) -> EventId {
-        assert_event_name_is_valid(&event);
+        assert_event_pattern_is_valid(&event);
         self.listeners().once(event, target, handler)
     }
 
+    pub fn listen_stream(&self, event: String, target: EventTarget) -> EventStream {
+        assert_event_pattern_is_valid(&event);
+        EventStream::new(self.listeners(), event, target)
+    }
+
+    pub fn once_async(&self, event: String, target: EventTarget) -> OnceEvent {
+        assert_event_pattern_is_valid(&event);
+        OnceEvent::new(self.listeners(), event, target)
+    }
+
     pub fn emit_filter<S, F>(&self, event: &str, payload: S, filter: F) -> crate::Result<()>
     where
         S: Serialize + Clone,
@@ -498,6 +642,51 @@ impl<R: Runtime> AppManager<R> {
         Ok(())
     }
 
+    pub fn retain_event<S: Serialize>(
+        &self,
+        event: &str,
+        target: EventTarget,
+        payload: S,
+    ) -> crate::Result<()> {
+        assert_event_name_is_valid(event);
+        self.listeners()
+            .retain(target, EmitArgs::new(event, payload)?);
+        Ok(())
+    }
+
+    /// Emits the event as a request, returning the guard of the pending request and the receiver of its reply.
+    pub(crate) fn request<S: Serialize>(
+        &self,
+        target: EventTarget,
+        event: &str,
+        payload: S,
+    ) -> crate::Result<(RequestGuard, oneshot::Receiver<JsonValue>)> {
+        assert_event_name_is_valid(event);
+
+        let listeners = self.listeners();
+        let filter = |t: &EventTarget| target.matches(t);
+        if !listeners.has_listener(event, filter) && !listeners.has_js_listener(event, filter) {
+            return Err(crate::Error::NoEventListener(event.into()));
+        }
+
+        let mut emit_args = EmitArgs::new(event, payload)?;
+        let (request_id, receiver) =
+            listeners.register_request(listeners.js_listener_webviews(event, filter));
+        // removes the request if it cannot be emitted
+        let guard = RequestGuard::new(listeners.clone(), request_id);
+        emit_args.request_id.replace(request_id);
+
+        listeners.emit_js_filter(
+            self.webview.webviews_lock().values(),
+            event,
+            &emit_args,
+            Some(&filter),
+        )?;
+        listeners.emit_filter(emit_args, Some(filter))?;
+
+        Ok((guard, receiver))
+    }
+
     pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) -> crate::Result<()> {
         assert_event_name_is_valid(event);
 
@@ -530,12 +719,18 @@ impl<R: Runtime> AppManager<R> {
         if let Some(window) = window {
             for webview in window.webviews() {
                 self.webview.webviews_lock().remove(webview.label());
+                self.clear_ipc_data(webview.label());
+                webview.state.clear();
             }
+            window.state.clear();
         }
     }
 
     pub(crate) fn on_webview_close(&self, label: &str) {
-        self.webview.webviews_lock().remove(label);
+        if let Some(webview) = self.webview.webviews_lock().remove(label) {
+            webview.state.clear();
+        }
+        self.clear_ipc_data(label);
 
         if let Ok(webview_labels_array) = serde_json::to_string(&self.webview.labels()) {
             let _ = self.webview.eval_script_all(format!(
@@ -544,6 +739,33 @@ impl<R: Runtime> AppManager<R> {
         }
     }
 
+    /// Removes the expired runtime capabilities and emits [`CAPABILITY_EXPIRED_EVENT`] for each one.
+    pub(crate) fn remove_expired_capabilities(&self) {
+        let expired = self
+            .runtime_authority
+            .lock()
+            .unwrap()
+            .remove_expired_capabilities();
+        for identifier in expired {
+            let _ = self.emit(CAPABILITY_EXPIRED_EVENT, identifier);
+        }
+    }
+
+    /// Discards the IPC channel data the given webview did not fetch yet
+    /// and the command streams it did not finish iterating,
+    /// and cancels its pending invokes.
+    pub(crate) fn clear_ipc_data(&self, label: &str) {
+        if let Some(pending) = self.state.try_get::<PendingInvokes>() {
+            pending.cancel_webview(label);
+        }
+        if let Some(queue) = self.state.try_get::<ChannelDataIpcQueue>() {
+            queue.clear_webview(label);
+        }
+        if let Some(streams) = self.state.try_get::<IpcStreams>() {
+            streams.clear_webview(label);
+        }
+    }
+
     pub fn windows(&self) -> HashMap<String, Window<R>> {
         self.window.windows_lock().clone()
     }
@@ -618,7 +840,7 @@ mod test {
         App, Manager, StateManager, Webview, WebviewWindow, WebviewWindowBuilder, Window, Wry,
     };
 
-    use super::AppManager;
+    use super::{AppManager, AssetKey, CspHash, StatusCode};
 
     const APP_LISTEN_ID: &str = "App::listen";
     const APP_LISTEN_ANY_ID: &str = "App::listen_any";
@@ -637,6 +859,8 @@ mod test {
             context,
             PluginStore::default(),
             Box::new(|_| false),
+            Vec::new(),
+            None,
             None,
             Default::default(),
             StateManager::new(),
@@ -888,4 +1112,70 @@ mod test {
         }
         assert_events("emit_to", &received, &[other_webview_listen_id]);
     }
+
+    #[derive(Default)]
+    struct HashedAssets(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+    impl<R: crate::Runtime> crate::Assets<R> for HashedAssets {
+        fn get(&self, key: &AssetKey) -> Option<std::borrow::Cow<'_, [u8]>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            (key.as_ref() == "/app.js").then_some(b"console.log('tauri')".as_slice().into())
+        }
+
+        fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+            (key.as_ref() == "/app.js").then_some("abc")
+        }
+
+        fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
+            Box::new(std::iter::empty())
+        }
+
+        fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+            Box::new(std::iter::empty())
+        }
+    }
+
+    #[test]
+    fn checks_validators_before_reading_assets() {
+        use crate::sealed::ManagerBase;
+
+        let assets = HashedAssets::default();
+        let reads = assets.0.clone();
+        let app = crate::test::mock_builder()
+            .build(crate::test::mock_context(assets))
+            .unwrap();
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::IF_NONE_MATCH, "\"abc\"".parse().unwrap());
+        let asset = app
+            .manager()
+            .get_encoded_asset("/app.js".into(), &[], &headers)
+            .unwrap();
+        assert_eq!(asset.status, StatusCode::NOT_MODIFIED);
+        assert_eq!(asset.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        let asset = app
+            .manager()
+            .get_encoded_asset("/app.js".into(), &[], &http::HeaderMap::new())
+            .unwrap();
+        assert_eq!(asset.status, StatusCode::OK);
+        assert_eq!(&*asset.bytes, b"console.log('tauri')");
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn missing_assets_are_not_found() {
+        use crate::sealed::ManagerBase;
+
+        let app = crate::test::mock_builder()
+            .build(crate::test::mock_context(HashedAssets::default()))
+            .unwrap();
+        let asset = app
+            .manager()
+            .get_encoded_asset("/missing.js".into(), &[], &http::HeaderMap::new())
+            .unwrap();
+        assert_eq!(asset.status, StatusCode::NOT_FOUND);
+        assert!(app.asset_resolver().get("/missing.js".into()).is_none());
+    }
 }
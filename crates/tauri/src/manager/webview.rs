@@ -4,6 +4,7 @@ use std::{
     fmt,
     fs::create_dir_all,
     sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
 };
 
 use serde::Serialize;
@@ -17,10 +18,10 @@ use url::Url;
 
 use crate::{
     app::{GlobalWebviewEventListener, OnPageLoad, UriSchemeResponder, WebviewEvent},
-    ipc::{InvokeHandler, InvokeResponder},
+    ipc::{InvokeHandler, InvokeResponder, IpcMiddleware},
     pattern::PatternJavascript,
     sealed::ManagerBase,
-    webview::PageLoadPayload,
+    webview::{PageLoadEvent, PageLoadPayload},
     AppHandle, EventLoopMessage, EventTarget, Manager, Runtime, Scopes, Webview, Window,
 };
 
@@ -81,6 +82,12 @@ pub struct WebviewManager<R: Runtime> {
     /// Webview event listeners to all webviews.
     pub event_listeners: Arc<Vec<GlobalWebviewEventListener<R>>>,
 
+    /// Middlewares that run around every invoke, in registration order.
+    pub ipc_middlewares: Mutex<Vec<Arc<dyn IpcMiddleware<R>>>>,
+
+    /// The default timeout of async commands.
+    pub invoke_timeout: Option<Duration>,
+
     /// Responder for invoke calls.
     pub invoke_responder: Option<Arc<InvokeResponder<R>>>,
     /// The script that initializes the invoke system.
@@ -111,6 +118,15 @@ impl<R: Runtime> WebviewManager<R> {
             .insert(uri_scheme, protocol);
     }
 
+    pub(crate) fn register_ipc_middleware(&self, middleware: Arc<dyn IpcMiddleware<R>>) {
+        self.ipc_middlewares.lock().unwrap().push(middleware);
+    }
+
+    /// The registered IPC middlewares.
+    pub(crate) fn ipc_middlewares(&self) -> Vec<Arc<dyn IpcMiddleware<R>>> {
+        self.ipc_middlewares.lock().unwrap().clone()
+    }
+
     /// Get a locked handle to the webviews.
     pub(crate) fn webviews_lock(&self) -> MutexGuard<'_, HashMap<String, Webview<R>>> {
         self.webviews.lock().expect("poisoned webview manager")
@@ -281,7 +297,27 @@ impl<R: Runtime> WebviewManager<R> {
             .replace(Box::new(move |url, event| {
                 let payload = PageLoadPayload { url: &url, event };
 
+                // the new page cannot consume the IPC data queued for the previous one
+                if event == PageLoadEvent::Started {
+                    app_manager_.clear_ipc_data(&label);
+                }
+
                 if let Some(w) = app_manager_.get_webview(&label) {
+                    if event == PageLoadEvent::Started {
+                        let resources_table = w.resources_table();
+                        if !resources_table.is_empty() {
+                            log::warn!(
+                                "webview `{label}` navigated to {url} with {} open resources: {}",
+                                resources_table.len(),
+                                resources_table
+                                    .names()
+                                    .map(|(rid, name)| format!("{name} ({rid})"))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            );
+                        }
+                    }
+
                     if let Some(on_page_load) = &app_manager_.webview.on_page_load {
                         on_page_load(&w, &payload);
                     }
@@ -305,7 +341,15 @@ impl<R: Runtime> WebviewManager<R> {
                 .get::<crate::Scopes>()
                 .asset_protocol
                 .clone();
-            let protocol = crate::protocol::asset::get(asset_scope.clone(), window_origin.clone());
+            let asset_protocol_config = &app_manager.config.app.security.asset_protocol;
+            let cache_policies =
+                crate::protocol::asset::CachePolicies::new(&asset_protocol_config.cache_policies);
+            let protocol = crate::protocol::asset::get(
+                asset_scope.clone(),
+                window_origin.clone(),
+                cache_policies,
+                asset_protocol_config.max_chunk_size,
+            );
             pending.register_uri_scheme_protocol("asset", move |request, responder| {
                 protocol(request, UriSchemeResponder(responder))
             });
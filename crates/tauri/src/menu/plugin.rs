@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use serde::{Deserialize, Serialize};
 use tauri_runtime::dpi::Position;
@@ -9,7 +12,7 @@ use crate::{
     image::JsImage,
     ipc::{channel::JavaScriptChannelId, Channel},
     plugin::{Builder, TauriPlugin},
-    resources::ResourceId,
+    resources::{Resource, ResourceId, ResourceTable},
     sealed::ManagerBase,
     Manager, RunEvent, Runtime, State, Webview, Window,
 };
@@ -341,10 +344,12 @@ fn new<R: Runtime>(
     kind: ItemKind,
     options: Option<NewOptions>,
     channels: State<'_, MenuChannels>,
-    handler: Channel,
+    handler: Channel<MenuId>,
 ) -> crate::Result<(ResourceId, MenuId)> {
     let options = options.unwrap_or_default();
     let mut resources_table = app.resources_table();
+    // check the limit before creating the native menu or item
+    resources_table.check_capacity(1)?;
 
     let (rid, id) = match kind {
         ItemKind::Menu => {
@@ -359,7 +364,7 @@ fn new<R: Runtime>(
             }
             let menu = builder.build()?;
             let id = menu.id().clone();
-            let rid = resources_table.add(menu);
+            let rid = resources_table.try_add(menu)?;
 
             (rid, id)
         }
@@ -373,7 +378,7 @@ fn new<R: Runtime>(
             }
             .create_item(&webview)?;
             let id = submenu.id().clone();
-            let rid = resources_table.add(submenu);
+            let rid = resources_table.try_add(submenu)?;
 
             (rid, id)
         }
@@ -389,7 +394,7 @@ fn new<R: Runtime>(
             }
             .create_item(&webview)?;
             let id = item.id().clone();
-            let rid = resources_table.add(item);
+            let rid = resources_table.try_add(item)?;
             (rid, id)
         }
 
@@ -400,7 +405,7 @@ fn new<R: Runtime>(
             }
             .create_item(&webview)?;
             let id = item.id().clone();
-            let rid = resources_table.add(item);
+            let rid = resources_table.try_add(item)?;
             (rid, id)
         }
 
@@ -416,7 +421,7 @@ fn new<R: Runtime>(
             }
             .create_item(&webview)?;
             let id = item.id().clone();
-            let rid = resources_table.add(item);
+            let rid = resources_table.try_add(item)?;
             (rid, id)
         }
 
@@ -432,7 +437,7 @@ fn new<R: Runtime>(
             }
             .create_item(&webview)?;
             let id = item.id().clone();
-            let rid = resources_table.add(item);
+            let rid = resources_table.try_add(item)?;
             (rid, id)
         }
     };
@@ -551,18 +556,37 @@ fn remove<R: Runtime>(
     Ok(())
 }
 
-macro_rules! make_item_resource {
-    ($resources_table:ident, $item:ident) => {{
-        let id = $item.id().clone();
-        let (rid, kind) = match $item {
-            MenuItemKind::MenuItem(i) => ($resources_table.add(i), ItemKind::MenuItem),
-            MenuItemKind::Submenu(i) => ($resources_table.add(i), ItemKind::Submenu),
-            MenuItemKind::Predefined(i) => ($resources_table.add(i), ItemKind::Predefined),
-            MenuItemKind::Check(i) => ($resources_table.add(i), ItemKind::Check),
-            MenuItemKind::Icon(i) => ($resources_table.add(i), ItemKind::Icon),
-        };
-        (rid, id, kind)
-    }};
+/// The resource of a menu item, with its ID and kind.
+fn item_resource<R: Runtime>(item: MenuItemKind<R>) -> (Arc<dyn Resource>, MenuId, ItemKind) {
+    let id = item.id().clone();
+    let (resource, kind) = match item {
+        MenuItemKind::MenuItem(i) => (Arc::new(i) as Arc<dyn Resource>, ItemKind::MenuItem),
+        MenuItemKind::Submenu(i) => (Arc::new(i) as Arc<dyn Resource>, ItemKind::Submenu),
+        MenuItemKind::Predefined(i) => (Arc::new(i) as Arc<dyn Resource>, ItemKind::Predefined),
+        MenuItemKind::Check(i) => (Arc::new(i) as Arc<dyn Resource>, ItemKind::Check),
+        MenuItemKind::Icon(i) => (Arc::new(i) as Arc<dyn Resource>, ItemKind::Icon),
+    };
+    (resource, id, kind)
+}
+
+/// Adds the menu items to the resources table, either all of them or none if they exceed its limits.
+fn add_item_resources<R: Runtime>(
+    resources_table: &mut ResourceTable,
+    items: Vec<MenuItemKind<R>>,
+) -> crate::Result<Vec<(ResourceId, MenuId, ItemKind)>> {
+    let (resources, items): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .map(|item| {
+            let (resource, id, kind) = item_resource(item);
+            (resource, (id, kind))
+        })
+        .unzip();
+    let rids = resources_table.try_add_all_dyn(resources)?;
+    Ok(rids
+        .into_iter()
+        .zip(items)
+        .map(|(rid, (id, kind))| (rid, id, kind))
+        .collect())
 }
 
 #[command(root = "crate")]
@@ -577,13 +601,13 @@ fn remove_at<R: Runtime>(
         ItemKind::Menu => {
             let menu = resources_table.get::<Menu<R>>(rid)?;
             if let Some(item) = menu.remove_at(position)? {
-                return Ok(Some(make_item_resource!(resources_table, item)));
+                return Ok(add_item_resources(&mut resources_table, vec![item])?.pop());
             }
         }
         ItemKind::Submenu => {
             let submenu = resources_table.get::<Submenu<R>>(rid)?;
             if let Some(item) = submenu.remove_at(position)? {
-                return Ok(Some(make_item_resource!(resources_table, item)));
+                return Ok(add_item_resources(&mut resources_table, vec![item])?.pop());
             }
         }
         _ => return Err(anyhow::anyhow!("unexpected menu item kind").into()),
@@ -605,10 +629,7 @@ fn items<R: Runtime>(
         _ => return Err(anyhow::anyhow!("unexpected menu item kind").into()),
     };
 
-    Ok(items
-        .into_iter()
-        .map(|i| make_item_resource!(resources_table, i))
-        .collect::<Vec<_>>())
+    add_item_resources(&mut resources_table, items)
 }
 
 #[command(root = "crate")]
@@ -623,13 +644,13 @@ fn get<R: Runtime>(
         ItemKind::Menu => {
             let menu = resources_table.get::<Menu<R>>(rid)?;
             if let Some(item) = menu.get(&id) {
-                return Ok(Some(make_item_resource!(resources_table, item)));
+                return Ok(add_item_resources(&mut resources_table, vec![item])?.pop());
             }
         }
         ItemKind::Submenu => {
             let submenu = resources_table.get::<Submenu<R>>(rid)?;
             if let Some(item) = submenu.get(&id) {
-                return Ok(Some(make_item_resource!(resources_table, item)));
+                return Ok(add_item_resources(&mut resources_table, vec![item])?.pop());
             }
         }
         _ => return Err(anyhow::anyhow!("unexpected menu item kind").into()),
@@ -677,7 +698,7 @@ fn create_default<R: Runtime>(
     let mut resources_table = webview.resources_table();
     let menu = Menu::default(&app)?;
     let id = menu.id().clone();
-    let rid = resources_table.add(menu);
+    let rid = resources_table.try_add(menu)?;
     Ok((rid, id))
 }
 
@@ -690,7 +711,7 @@ async fn set_as_app_menu<R: Runtime>(
     let menu = resources_table.get::<Menu<R>>(rid)?;
     if let Some(menu) = menu.set_as_app_menu()? {
         let id = menu.id().clone();
-        let rid = resources_table.add(menu);
+        let rid = resources_table.try_add(menu)?;
         return Ok(Some((rid, id)));
     }
     Ok(None)
@@ -712,7 +733,7 @@ async fn set_as_window_menu<R: Runtime>(
         let menu = resources_table.get::<Menu<R>>(rid)?;
         if let Some(menu) = menu.set_as_window_menu(&window)? {
             let id = menu.id().clone();
-            let rid = resources_table.add(menu);
+            let rid = resources_table.try_add(menu)?;
             return Ok(Some((rid, id)));
         }
     }
@@ -855,7 +876,7 @@ fn set_icon<R: Runtime>(
     }
 }
 
-struct MenuChannels(Mutex<HashMap<MenuId, Channel>>);
+struct MenuChannels(Mutex<HashMap<MenuId, Channel<MenuId>>>);
 
 pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("menu")
@@ -866,7 +887,7 @@ pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
         .on_event(|app, e| {
             if let RunEvent::MenuEvent(e) = e {
                 if let Some(channel) = app.state::<MenuChannels>().0.lock().unwrap().get(&e.id) {
-                    let _ = channel.send(&e.id);
+                    let _ = channel.send(e.id.clone());
                 }
             }
         })
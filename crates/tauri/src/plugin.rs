@@ -2,7 +2,7 @@
 
 use crate::{
     app::UriSchemeResponder,
-    ipc::{Invoke, InvokeHandler, ScopeObject, ScopeValue},
+    ipc::{codes, Invoke, InvokeHandler, IpcError, IpcMiddleware, ScopeObject, ScopeValue},
     manager::webview::UriSchemeProtocol,
     utils::config::PluginConfig,
     webview::PageLoadPayload,
@@ -230,6 +230,7 @@ pub struct Builder<R: Runtime, C: DeserializeOwned = ()> {
     on_event: Box<OnEvent<R>>,
     on_drop: Option<Box<OnDrop<R>>>,
     uri_scheme_protocols: HashMap<String, Arc<UriSchemeProtocol<R>>>,
+    ipc_middlewares: Vec<Arc<dyn IpcMiddleware<R>>>,
 }
 
 impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
@@ -247,6 +248,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
             on_event: Box::new(|_, _| ()),
             on_drop: None,
             uri_scheme_protocols: Default::default(),
+            ipc_middlewares: Vec::new(),
         }
     }
 
@@ -611,6 +613,39 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
         self
     }
 
+    /// Adds a middleware that runs around every IPC invoke, not only the ones targeting this plugin.
+    ///
+    /// The middleware is registered when the plugin is initialized,
+    /// after the application middlewares and the ones from previously initialized plugins.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri::{ipc::{InvokeError, InvokeMessage, IpcMiddleware}, plugin::{Builder, TauriPlugin}, Runtime};
+    ///
+    /// struct ReadOnly;
+    ///
+    /// impl<R: Runtime> IpcMiddleware<R> for ReadOnly {
+    ///   fn on_invoke(&self, message: &InvokeMessage<R>) -> Result<(), InvokeError> {
+    ///     if message.command().starts_with("write_") {
+    ///       return Err(InvokeError::from("the application is read-only"));
+    ///     }
+    ///     Ok(())
+    ///   }
+    /// }
+    ///
+    /// fn init<R: Runtime>() -> TauriPlugin<R> {
+    ///   Builder::new("read-only")
+    ///     .ipc_middleware(ReadOnly)
+    ///     .build()
+    /// }
+    /// ```
+    #[must_use]
+    pub fn ipc_middleware<M: IpcMiddleware<R>>(mut self, middleware: M) -> Self {
+        self.ipc_middlewares.push(Arc::new(middleware));
+        self
+    }
+
     /// Builds the [TauriPlugin].
     pub fn build(self) -> TauriPlugin<R, C> {
         TauriPlugin {
@@ -626,6 +661,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
             on_event: self.on_event,
             on_drop: self.on_drop,
             uri_scheme_protocols: self.uri_scheme_protocols,
+            ipc_middlewares: self.ipc_middlewares,
         }
     }
 }
@@ -644,6 +680,7 @@ pub struct TauriPlugin<R: Runtime, C: DeserializeOwned = ()> {
     on_event: Box<OnEvent<R>>,
     on_drop: Option<Box<OnDrop<R>>>,
     uri_scheme_protocols: HashMap<String, Arc<UriSchemeProtocol<R>>>,
+    ipc_middlewares: Vec<Arc<dyn IpcMiddleware<R>>>,
 }
 
 impl<R: Runtime, C: DeserializeOwned> Drop for TauriPlugin<R, C> {
@@ -682,6 +719,12 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
                 .webview
                 .register_uri_scheme_protocol(uri_scheme, protocol.clone())
         }
+
+        for middleware in &self.ipc_middlewares {
+            app.manager
+                .webview
+                .register_ipc_middleware(middleware.clone())
+        }
         Ok(())
     }
 
@@ -842,7 +885,10 @@ impl<R: Runtime> PluginStore<R> {
                 return p.extend_api(invoke);
             }
         }
-        invoke.resolver.reject(format!("plugin {plugin} not found"));
+        invoke.resolver.reject(IpcError::new(
+            codes::PLUGIN_NOT_FOUND,
+            format!("plugin {plugin} not found"),
+        ));
         true
     }
 }
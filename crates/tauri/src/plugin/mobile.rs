@@ -113,7 +113,7 @@ pub fn send_channel_data(
         .unwrap()
         .get(&(channel_id as u32))
     {
-        let _ = channel.send(data);
+        let _ = channel.send(data.into());
     }
 }
 
@@ -368,7 +368,7 @@ pub(crate) fn run_command<R: Runtime, C: AsRef<str>, F: FnOnce(PluginResponse) +
             {
                 let payload: serde_json::Value =
                     serde_json::from_str(payload.to_str().unwrap()).unwrap();
-                let _ = channel.send(payload);
+                let _ = channel.send(payload.into());
             }
         }
 
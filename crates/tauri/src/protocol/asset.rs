@@ -1,219 +1,269 @@
-use crate::{path::SafePathBuf, scope, webview::UriSchemeProtocolHandler};
-use http::{header::*, status::StatusCode, Request, Response};
+use crate::{path::SafePathBuf, protocol::cache, scope, webview::UriSchemeProtocolHandler};
+use http::{header::*, status::StatusCode, HeaderMap, Request, Response};
 use http_range::HttpRange;
-use std::{borrow::Cow, io::SeekFrom};
-use tauri_utils::mime_type::MimeType;
+use std::{
+    io::{self, SeekFrom},
+    sync::Arc,
+    time::UNIX_EPOCH,
+};
+use tauri_utils::{config::AssetCachePolicy, mime_type::MimeType};
 use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
-
-pub fn get(scope: scope::fs::Scope, window_origin: String) -> UriSchemeProtocolHandler {
-    Box::new(
-        move |request, responder| match get_response(request, &scope, &window_origin) {
-            Ok(response) => responder.respond(response),
-            Err(e) => responder.respond(
-                http::Response::builder()
-                    .status(http::StatusCode::BAD_REQUEST)
-                    .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
-                    .header("Access-Control-Allow-Origin", &window_origin)
-                    .body(e.to_string().as_bytes().to_vec())
-                    .unwrap(),
-            ),
-        },
-    )
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// The `Cache-Control` policies of the asset protocol, see [`AssetCachePolicy`].
+pub struct CachePolicies(Vec<(glob::Pattern, String)>);
+
+impl CachePolicies {
+    pub fn new(policies: &[AssetCachePolicy]) -> Self {
+        Self(
+            policies
+                .iter()
+                .filter_map(|policy| match glob::Pattern::new(&policy.path) {
+                    Ok(pattern) => Some((pattern, policy.cache_control.clone())),
+                    Err(e) => {
+                        log::error!(
+                            "invalid asset protocol cache policy path `{}`: {e}",
+                            policy.path
+                        );
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// The `Cache-Control` value of the given file path, `no-cache` if no policy matches.
+    fn cache_control(&self, path: &str) -> &str {
+        self.0
+            .iter()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, cache_control)| cache_control.as_str())
+            .unwrap_or(cache::NO_CACHE)
+    }
 }
 
-fn get_response(
+pub fn get(
+    scope: scope::fs::Scope,
+    window_origin: String,
+    cache_policies: CachePolicies,
+    max_chunk_size: u64,
+) -> UriSchemeProtocolHandler {
+    let cache_policies = Arc::new(cache_policies);
+    Box::new(move |request, responder| {
+        let scope = scope.clone();
+        let window_origin = window_origin.clone();
+        let cache_policies = cache_policies.clone();
+        // files are read on the async runtime so the protocol thread is never blocked
+        crate::async_runtime::spawn(async move {
+            match get_response(
+                request,
+                &scope,
+                &window_origin,
+                &cache_policies,
+                max_chunk_size,
+            )
+            .await
+            {
+                Ok(response) => responder.respond(response),
+                Err(e) => responder.respond(
+                    http::Response::builder()
+                        .status(http::StatusCode::BAD_REQUEST)
+                        .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
+                        .header("Access-Control-Allow-Origin", &window_origin)
+                        .body(e.to_string().as_bytes().to_vec())
+                        .unwrap(),
+                ),
+            }
+        });
+    })
+}
+
+async fn get_response(
     request: Request<Vec<u8>>,
     scope: &scope::fs::Scope,
     window_origin: &str,
-) -> Result<Response<Cow<'static, [u8]>>, Box<dyn std::error::Error>> {
+    cache_policies: &CachePolicies,
+    max_chunk_size: u64,
+) -> Result<Response<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
     // skip leading `/`
     let path = percent_encoding::percent_decode(request.uri().path()[1..].as_bytes())
         .decode_utf8_lossy()
         .to_string();
 
-    let mut resp = Response::builder().header("Access-Control-Allow-Origin", window_origin);
+    let resp = Response::builder().header("Access-Control-Allow-Origin", window_origin);
 
     if let Err(e) = SafePathBuf::new(path.clone().into()) {
         log::error!("asset protocol path \"{}\" is not valid: {}", path, e);
-        return resp.status(403).body(Vec::new().into()).map_err(Into::into);
+        return resp.status(403).body(Vec::new()).map_err(Into::into);
     }
 
     if !scope.is_allowed(&path) {
         log::error!("asset protocol not configured to allow the path: {}", path);
-        return resp.status(403).body(Vec::new().into()).map_err(Into::into);
-    }
-
-    let (mut file, len, mime_type, read_bytes) = crate::async_runtime::safe_block_on(async move {
-        let mut file = File::open(&path).await?;
-
-        // get file length
-        let len = {
-            let old_pos = file.stream_position().await?;
-            let len = file.seek(SeekFrom::End(0)).await?;
-            file.seek(SeekFrom::Start(old_pos)).await?;
-            len
-        };
-
-        // get file mime type
-        let (mime_type, read_bytes) = {
-            let nbytes = len.min(8192);
-            let mut magic_buf = Vec::with_capacity(nbytes as usize);
-            let old_pos = file.stream_position().await?;
-            (&mut file).take(nbytes).read_to_end(&mut magic_buf).await?;
-            file.seek(SeekFrom::Start(old_pos)).await?;
-            (
-                MimeType::parse(&magic_buf, &path),
-                // return the `magic_bytes` if we read the whole file
-                // to avoid reading it again later if this is not a range request
-                if len < 8192 { Some(magic_buf) } else { None },
-            )
-        };
+        return resp.status(403).body(Vec::new()).map_err(Into::into);
+    }
 
-        Ok::<(File, u64, String, Option<Vec<u8>>), anyhow::Error>((
-            file, len, mime_type, read_bytes,
-        ))
-    })?;
+    let resp = resp.header(CACHE_CONTROL, cache_policies.cache_control(&path));
+
+    serve_file(&path, request.headers(), resp, max_chunk_size).await
+}
+
+/// Responds with the file at `path`, honoring the conditional and range request headers.
+///
+/// Requests without a range are answered with the whole file.
+/// Range responses send at most `max_chunk_size` bytes, and the webview requests the remaining bytes
+/// with following range requests.
+async fn serve_file(
+    path: &str,
+    headers: &HeaderMap,
+    mut resp: http::response::Builder,
+    max_chunk_size: u64,
+) -> Result<Response<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut file = File::open(path).await?;
+    let metadata = file.metadata().await?;
+    let len = metadata.len();
+    let modified = metadata.modified().ok();
+
+    // get file mime type
+    let mut magic_buf = Vec::with_capacity(len.min(8192) as usize);
+    (&mut file).take(8192).read_to_end(&mut magic_buf).await?;
+    let mime_type = MimeType::parse(&magic_buf, path);
 
-    resp = resp.header(CONTENT_TYPE, &mime_type);
+    // the validators change whenever the file is modified
+    let etag = modified
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| format!("\"{len:x}-{:x}\"", modified.as_nanos()));
+    let last_modified = modified.and_then(cache::http_date);
+
+    if let Some(etag) = &etag {
+        resp = resp.header(ETAG, etag);
+    }
+    if let Some(last_modified) = &last_modified {
+        resp = resp.header(LAST_MODIFIED, last_modified);
+    }
+    if cache::is_not_modified(headers, etag.as_deref(), last_modified.as_deref()) {
+        return resp
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Vec::new())
+            .map_err(Into::into);
+    }
+
+    resp = resp.header(ACCEPT_RANGES, "bytes");
+
+    // a range is only valid for the representation named by `If-Range`, otherwise the whole file is sent
+    let if_range_matches = headers
+        .get(IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |if_range| {
+            Some(if_range) == etag.as_deref() || Some(if_range) == last_modified.as_deref()
+        });
 
     // handle 206 (partial range) http requests
-    let response = if let Some(range_header) = request
-        .headers()
-        .get("range")
-        .and_then(|r| r.to_str().map(|r| r.to_string()).ok())
-    {
-        resp = resp.header(ACCEPT_RANGES, "bytes");
-
-        let not_satisfiable = || {
-            Response::builder()
-                .status(StatusCode::RANGE_NOT_SATISFIABLE)
-                .header(CONTENT_RANGE, format!("bytes */{len}"))
-                .body(vec![].into())
-                .map_err(Into::into)
-        };
-
-        // parse range header
-        let ranges = if let Ok(ranges) = HttpRange::parse(&range_header, len) {
+    let range_header = headers
+        .get(RANGE)
+        .filter(|_| if_range_matches)
+        .and_then(|r| r.to_str().ok());
+    let ranges = match range_header {
+        Some(range_header) => {
+            let ranges = HttpRange::parse(range_header, len)
+                .map(|ranges| limit_ranges(&ranges, max_chunk_size))
+                .unwrap_or_default();
+            if ranges.is_empty() {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(CONTENT_RANGE, format!("bytes */{len}"))
+                    .body(Vec::new())
+                    .map_err(Into::into);
+            }
             ranges
-                .iter()
-                // map the output to spec range <start-end>, example: 0-499
-                .map(|r| (r.start, r.start + r.length - 1))
-                .collect::<Vec<_>>()
-        } else {
-            return not_satisfiable();
-        };
-
-        /// The Maximum bytes we send in one range
-        const MAX_LEN: u64 = 1000 * 1024;
-
-        // single-part range header
-        if ranges.len() == 1 {
-            let &(start, mut end) = ranges.first().unwrap();
-
-            // check if a range is not satisfiable
-            //
-            // this should be already taken care of by the range parsing library
-            // but checking here again for extra assurance
-            if start >= len || end >= len || end < start {
-                return not_satisfiable();
+        }
+        None => {
+            let mut body = Vec::with_capacity(len as usize);
+            if len > 0 {
+                read_range(&mut file, 0, len - 1, &mut body).await?;
             }
-
-            // adjust end byte for MAX_LEN
-            end = start + (end - start).min(len - start).min(MAX_LEN - 1);
-
-            // calculate number of bytes needed to be read
-            let nbytes = end + 1 - start;
-
-            let buf = crate::async_runtime::safe_block_on(async move {
-                let mut buf = Vec::with_capacity(nbytes as usize);
-                file.seek(SeekFrom::Start(start)).await?;
-                file.take(nbytes).read_to_end(&mut buf).await?;
-                Ok::<Vec<u8>, anyhow::Error>(buf)
-            })?;
-
-            resp = resp.header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"));
-            resp = resp.header(CONTENT_LENGTH, end + 1 - start);
-            resp = resp.status(StatusCode::PARTIAL_CONTENT);
-            resp.body(buf.into())
-        } else {
-            let ranges = ranges
-                .iter()
-                .filter_map(|&(start, mut end)| {
-                    // filter out unsatisfiable ranges
-                    //
-                    // this should be already taken care of by the range parsing library
-                    // but checking here again for extra assurance
-                    if start >= len || end >= len || end < start {
-                        None
-                    } else {
-                        // adjust end byte for MAX_LEN
-                        end = start + (end - start).min(len - start).min(MAX_LEN - 1);
-                        Some((start, end))
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            let boundary = random_boundary();
-            let boundary_sep = format!("\r\n--{boundary}\r\n");
-            let boundary_closer = format!("\r\n--{boundary}\r\n");
-
-            resp = resp.header(
-                CONTENT_TYPE,
-                format!("multipart/byteranges; boundary={boundary}"),
-            );
-
-            let buf = crate::async_runtime::safe_block_on(async move {
-                // multi-part range header
-                let mut buf = Vec::new();
-
-                for (end, start) in ranges {
-                    // a new range is being written, write the range boundary
-                    buf.write_all(boundary_sep.as_bytes()).await?;
-
-                    // write the needed headers `Content-Type` and `Content-Range`
-                    buf.write_all(format!("{CONTENT_TYPE}: {mime_type}\r\n").as_bytes())
-                        .await?;
-                    buf.write_all(
-                        format!("{CONTENT_RANGE}: bytes {start}-{end}/{len}\r\n").as_bytes(),
-                    )
-                    .await?;
-
-                    // write the separator to indicate the start of the range body
-                    buf.write_all("\r\n".as_bytes()).await?;
-
-                    // calculate number of bytes needed to be read
-                    let nbytes = end + 1 - start;
-
-                    let mut local_buf = Vec::with_capacity(nbytes as usize);
-                    file.seek(SeekFrom::Start(start)).await?;
-                    (&mut file).take(nbytes).read_to_end(&mut local_buf).await?;
-                    buf.extend_from_slice(&local_buf);
-                }
-                // all ranges have been written, write the closing boundary
-                buf.write_all(boundary_closer.as_bytes()).await?;
-
-                Ok::<Vec<u8>, anyhow::Error>(buf)
-            })?;
-            resp.body(buf.into())
+            return resp
+                .header(CONTENT_TYPE, &mime_type)
+                .header(CONTENT_LENGTH, body.len())
+                .body(body)
+                .map_err(Into::into);
         }
-    } else {
-        // avoid reading the file if we already read it
-        // as part of mime type detection
-        let buf = if let Some(b) = read_bytes {
-            b
-        } else {
-            crate::async_runtime::safe_block_on(async move {
-                let mut local_buf = Vec::with_capacity(len as usize);
-                file.read_to_end(&mut local_buf).await?;
-                Ok::<Vec<u8>, anyhow::Error>(local_buf)
-            })?
-        };
-        resp = resp.header(CONTENT_LENGTH, len);
-        resp.body(buf.into())
     };
 
-    response.map_err(Into::into)
+    resp = resp.status(StatusCode::PARTIAL_CONTENT);
+
+    // single-part range header
+    if let [(start, end)] = ranges[..] {
+        let mut body = Vec::with_capacity((end + 1 - start) as usize);
+        read_range(&mut file, start, end, &mut body).await?;
+        return resp
+            .header(CONTENT_TYPE, &mime_type)
+            .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+            .header(CONTENT_LENGTH, body.len())
+            .body(body)
+            .map_err(Into::into);
+    }
+
+    // multi-part range header, see https://www.rfc-editor.org/rfc/rfc9110#name-media-type-multipart-byteran
+    let boundary = random_boundary();
+    let part_headers = ranges
+        .iter()
+        .map(|(start, end)| {
+            format!(
+                "--{boundary}\r\n{CONTENT_TYPE}: {mime_type}\r\n{CONTENT_RANGE}: bytes {start}-{end}/{len}\r\n\r\n"
+            )
+        })
+        .collect::<Vec<_>>();
+    let closer = format!("--{boundary}--\r\n");
+
+    let body_len = part_headers.iter().map(String::len).sum::<usize>()
+        + ranges
+            .iter()
+            .map(|(start, end)| (end + 1 - start) as usize + "\r\n".len())
+            .sum::<usize>()
+        + closer.len();
+    let mut body = Vec::with_capacity(body_len);
+    for (part_header, &(start, end)) in part_headers.iter().zip(&ranges) {
+        body.extend_from_slice(part_header.as_bytes());
+        read_range(&mut file, start, end, &mut body).await?;
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(closer.as_bytes());
+
+    resp.header(
+        CONTENT_TYPE,
+        format!("multipart/byteranges; boundary={boundary}"),
+    )
+    .header(CONTENT_LENGTH, body.len())
+    .body(body)
+    .map_err(Into::into)
+}
+
+/// Maps the requested ranges to inclusive `(start, end)` byte positions,
+/// truncating them so at most `max_chunk_size` bytes are sent.
+fn limit_ranges(ranges: &[HttpRange], max_chunk_size: u64) -> Vec<(u64, u64)> {
+    let mut remaining = max_chunk_size.max(1);
+    let mut limited = Vec::with_capacity(ranges.len());
+    for range in ranges.iter().filter(|range| range.length > 0) {
+        if remaining == 0 {
+            break;
+        }
+        let length = range.length.min(remaining);
+        remaining -= length;
+        limited.push((range.start, range.start + length - 1));
+    }
+    limited
+}
+
+/// Appends the inclusive `start..=end` byte range of the file to `buf`.
+///
+/// The range is streamed from the file in small reads instead of being read in a single buffer.
+async fn read_range(file: &mut File, start: u64, end: u64, buf: &mut Vec<u8>) -> io::Result<()> {
+    let len = end + 1 - start;
+    file.seek(SeekFrom::Start(start)).await?;
+    if tokio::io::copy(&mut (&mut *file).take(len), buf).await? < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
 }
 
 fn random_boundary() -> String {
@@ -227,3 +277,137 @@ fn random_boundary() -> String {
             a
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use http::{header::*, HeaderMap, HeaderValue, Response, StatusCode};
+
+    use super::serve_file;
+
+    const CONTENTS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    fn serve(name: &str, headers: &[(HeaderName, &str)], max_chunk_size: u64) -> Response<Vec<u8>> {
+        let path = std::env::temp_dir().join(format!("tauri-asset-protocol-{name}.txt"));
+        std::fs::write(&path, CONTENTS).unwrap();
+
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+
+        let response = crate::async_runtime::block_on(serve_file(
+            path.to_str().unwrap(),
+            &header_map,
+            Response::builder(),
+            max_chunk_size,
+        ))
+        .unwrap();
+        std::fs::remove_file(path).unwrap();
+        response
+    }
+
+    fn header(response: &Response<Vec<u8>>, name: HeaderName) -> &str {
+        response.headers().get(name).unwrap().to_str().unwrap()
+    }
+
+    #[test]
+    fn serves_whole_file() {
+        let response = serve("whole", &[], 1024);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), CONTENTS);
+        assert_eq!(
+            header(&response, CONTENT_LENGTH),
+            CONTENTS.len().to_string()
+        );
+        assert_eq!(header(&response, ACCEPT_RANGES), "bytes");
+    }
+
+    #[test]
+    fn serves_whole_file_larger_than_max_chunk_size() {
+        let response = serve("whole-large", &[], 8);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), CONTENTS);
+        assert_eq!(
+            header(&response, CONTENT_LENGTH),
+            CONTENTS.len().to_string()
+        );
+        assert!(response.headers().get(CONTENT_RANGE).is_none());
+    }
+
+    #[test]
+    fn serves_single_range() {
+        let response = serve("single", &[(RANGE, "bytes=10-15")], 1024);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body(), b"abcdef");
+        assert_eq!(header(&response, CONTENT_RANGE), "bytes 10-15/36");
+        assert_eq!(header(&response, CONTENT_LENGTH), "6");
+
+        let response = serve("suffix", &[(RANGE, "bytes=-3")], 1024);
+        assert_eq!(response.body(), b"xyz");
+        assert_eq!(header(&response, CONTENT_RANGE), "bytes 33-35/36");
+    }
+
+    #[test]
+    fn limits_range_to_max_chunk_size() {
+        let response = serve("limited", &[(RANGE, "bytes=4-")], 8);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body(), b"456789ab");
+        assert_eq!(header(&response, CONTENT_RANGE), "bytes 4-11/36");
+    }
+
+    #[test]
+    fn serves_multipart_ranges() {
+        let response = serve("multipart", &[(RANGE, "bytes=0-1, 10-12, 30-")], 1024);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+
+        let content_type = header(&response, CONTENT_TYPE);
+        let boundary = content_type
+            .strip_prefix("multipart/byteranges; boundary=")
+            .unwrap();
+        let expected = format!(
+            "--{boundary}\r\ncontent-type: text/plain\r\ncontent-range: bytes 0-1/36\r\n\r\n01\r\n\
+             --{boundary}\r\ncontent-type: text/plain\r\ncontent-range: bytes 10-12/36\r\n\r\nabc\r\n\
+             --{boundary}\r\ncontent-type: text/plain\r\ncontent-range: bytes 30-35/36\r\n\r\nuvwxyz\r\n\
+             --{boundary}--\r\n"
+        );
+        assert_eq!(
+            String::from_utf8(response.body().clone()).unwrap(),
+            expected
+        );
+        assert_eq!(
+            header(&response, CONTENT_LENGTH),
+            expected.len().to_string()
+        );
+    }
+
+    #[test]
+    fn limits_multipart_ranges_to_max_chunk_size() {
+        let response = serve(
+            "multipart-limited",
+            &[(RANGE, "bytes=0-3, 10-15, 20-25")],
+            6,
+        );
+        let body = String::from_utf8(response.body().clone()).unwrap();
+        assert!(body.contains("content-range: bytes 0-3/36\r\n\r\n0123\r\n"));
+        assert!(body.contains("content-range: bytes 10-11/36\r\n\r\nab\r\n"));
+        assert!(!body.contains("bytes 20-"));
+    }
+
+    #[test]
+    fn rejects_unsatisfiable_ranges() {
+        let response = serve("unsatisfiable", &[(RANGE, "bytes=100-200")], 1024);
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(header(&response, CONTENT_RANGE), "bytes */36");
+    }
+
+    #[test]
+    fn ignores_range_for_other_representation() {
+        let response = serve(
+            "if-range",
+            &[(RANGE, "bytes=0-1"), (IF_RANGE, "\"outdated\"")],
+            1024,
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), CONTENTS);
+    }
+}
@@ -0,0 +1,104 @@
+//! HTTP caching helpers shared by the `tauri://` and `asset://` protocols.
+
+#[cfg(any(test, feature = "protocol-asset"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::{
+    header::{IF_MODIFIED_SINCE, IF_NONE_MATCH},
+    HeaderMap,
+};
+
+/// The `Cache-Control` value of responses that must be revalidated before being reused.
+pub const NO_CACHE: &str = "no-cache";
+
+/// Whether the request validators match the current representation, so `304 Not Modified` can be sent.
+///
+/// `If-None-Match` takes precedence over `If-Modified-Since`,
+/// which is compared as is with the `Last-Modified` value the webview got before.
+pub fn is_not_modified(
+    headers: &HeaderMap,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> bool {
+    if let Some(if_none_match) = headers.get(IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        return etag.is_some_and(|etag| etag_matches(if_none_match, etag));
+    }
+    match (
+        headers.get(IF_MODIFIED_SINCE).and_then(|v| v.to_str().ok()),
+        last_modified,
+    ) {
+        (Some(since), Some(last_modified)) => since.trim() == last_modified,
+        _ => false,
+    }
+}
+
+/// Weak comparison of an `If-None-Match` value with an entity tag.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Formats a time as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+#[cfg(any(test, feature = "protocol-asset"))]
+pub fn http_date(time: SystemTime) -> Option<String> {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let secs = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let days = secs / 86400;
+    let (hours, minutes, seconds) = (secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+
+    // converts the days since the epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    Some(format!(
+        "{}, {day:02} {} {year} {hours:02}:{minutes:02}:{seconds:02} GMT",
+        WEEKDAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use http::{header::IF_NONE_MATCH, HeaderMap};
+
+    use super::{http_date, is_not_modified};
+
+    #[test]
+    fn formats_http_dates() {
+        assert_eq!(
+            http_date(UNIX_EPOCH + Duration::from_secs(784111777)).unwrap(),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert_eq!(
+            http_date(UNIX_EPOCH + Duration::from_secs(951782400)).unwrap(),
+            "Tue, 29 Feb 2000 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn matches_validators() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_not_modified(&headers, Some("\"a\""), None));
+
+        headers.insert(IF_NONE_MATCH, "\"b\", W/\"a\"".parse().unwrap());
+        assert!(is_not_modified(&headers, Some("\"a\""), None));
+        assert!(!is_not_modified(&headers, Some("\"c\""), None));
+        assert!(!is_not_modified(&headers, None, None));
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(feature = "protocol-asset")]
 pub mod asset;
+pub mod cache;
 #[cfg(feature = "isolation")]
 pub mod isolation;
 pub mod tauri;
@@ -2,11 +2,17 @@ use std::{borrow::Cow, sync::Arc};
 
 use http::{header::CONTENT_TYPE, Request, Response as HttpResponse, StatusCode};
 
+#[cfg(not(all(dev, mobile)))]
+use crate::protocol::cache;
+#[cfg(not(all(dev, mobile)))]
+use crate::utils::assets::AssetEncoding;
 use crate::{
     manager::{webview::PROXY_DEV_SERVER, AppManager},
     webview::{UriSchemeProtocolHandler, WebResourceRequestHandler},
     Runtime,
 };
+#[cfg(not(all(dev, mobile)))]
+use http::header::{ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, ETAG, VARY};
 
 #[cfg(all(dev, mobile))]
 use std::{collections::HashMap, sync::Mutex};
@@ -150,12 +156,38 @@ fn get_response<R: Runtime>(
 
     #[cfg(not(all(dev, mobile)))]
     let mut response = {
-        let asset = manager.get_asset(path)?;
-        builder = builder.header(CONTENT_TYPE, &asset.mime_type);
-        if let Some(csp) = &asset.csp_header {
-            builder = builder.header("Content-Security-Policy", csp);
+        let accepted_encodings = request
+            .headers()
+            .get(ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(AssetEncoding::parse_accepted)
+            .unwrap_or_default();
+        let asset = manager.get_encoded_asset(path, &accepted_encodings, request.headers())?;
+        builder = builder
+            .header(VARY, ACCEPT_ENCODING.as_str())
+            .header(CACHE_CONTROL, cache::NO_CACHE);
+        if let Some(etag) = &asset.etag {
+            builder = builder.header(ETAG, etag);
+        }
+        for (name, value) in &asset.headers {
+            builder = builder.header(name, value);
+        }
+        if asset.status == StatusCode::NOT_MODIFIED {
+            builder
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Vec::new().into())?
+        } else {
+            builder = builder
+                .status(asset.status)
+                .header(CONTENT_TYPE, &asset.mime_type);
+            if let Some(encoding) = asset.content_encoding {
+                builder = builder.header(CONTENT_ENCODING, encoding.as_str());
+            }
+            if let Some(csp) = &asset.csp_header {
+                builder = builder.header("Content-Security-Policy", csp);
+            }
+            builder.body(asset.bytes)?
         }
-        builder.body(asset.bytes.into())?
     };
     if let Some(handler) = &web_resource_request_handler {
         handler(request, &mut response);
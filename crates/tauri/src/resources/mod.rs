@@ -7,6 +7,7 @@ use std::{
     borrow::Cow,
     collections::BTreeMap,
     sync::Arc,
+    time::SystemTime,
 };
 
 /// Resources are Rust objects that are stored in [ResourceTable] and managed by tauri.
@@ -26,6 +27,13 @@ pub trait Resource: Any + 'static + Send + Sync {
         type_name::<Self>().into()
     }
 
+    /// An estimate of the memory used by the resource, in bytes,
+    /// counted against the [`ResourceLimits::max_size`] of the resource table when it is added.
+    /// The default implementation returns 0.
+    fn size_hint(&self) -> usize {
+        0
+    }
+
     /// Resources may implement the `close()` trait method if they need to do
     /// resource specific clean-ups, such as cancelling pending futures, after a
     /// resource has been removed from the resource table.
@@ -57,6 +65,44 @@ impl dyn Resource {
 /// operating systems.
 pub type ResourceId = u32;
 
+/// The limits of a [`ResourceTable`], see [`ResourceTable::set_limits`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The maximum number of resources in the table.
+    pub max_count: Option<usize>,
+    /// The maximum sum of the [`Resource::size_hint`] of the resources in the table.
+    pub max_size: Option<usize>,
+}
+
+/// Information about a resource of a [`ResourceTable`], see [`ResourceTable::resources`].
+#[derive(Debug, Clone)]
+pub struct ResourceInfo {
+    /// The resource ID.
+    pub rid: ResourceId,
+    /// The [`Resource::name`].
+    pub name: String,
+    /// The time the resource was added to the table.
+    pub created_at: SystemTime,
+    /// The [`Resource::size_hint`] when the resource was added to the table.
+    pub size: usize,
+}
+
+struct ResourceEntry {
+    resource: Arc<dyn Resource>,
+    created_at: SystemTime,
+    size: usize,
+}
+
+impl ResourceEntry {
+    fn new(resource: Arc<dyn Resource>) -> Self {
+        Self {
+            size: resource.size_hint(),
+            resource,
+            created_at: SystemTime::now(),
+        }
+    }
+}
+
 /// Map-like data structure storing Tauri's resources (equivalent to file
 /// descriptors).
 ///
@@ -68,7 +114,9 @@ pub type ResourceId = u32;
 /// the key in the map.
 #[derive(Default)]
 pub struct ResourceTable {
-    index: BTreeMap<ResourceId, Arc<dyn Resource>>,
+    index: BTreeMap<ResourceId, ResourceEntry>,
+    limits: ResourceLimits,
+    size: usize,
 }
 
 impl ResourceTable {
@@ -78,10 +126,96 @@ impl ResourceTable {
         u32::from_ne_bytes(bytes)
     }
 
+    /// Sets the limits of the table, checked by [`Self::try_add`].
+    ///
+    /// The resources already in the table are kept even if they exceed the new limits.
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+    }
+
+    /// The limits of the table.
+    pub fn limits(&self) -> ResourceLimits {
+        self.limits
+    }
+
+    /// The number of resources in the table.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns true if the table has no resources.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// The sum of the [`Resource::size_hint`] of the resources in the table.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Inserts resource into the resource table, which takes ownership of it.
+    ///
+    /// Returns [`Error::ResourceLimitExceeded`](crate::Error::ResourceLimitExceeded)
+    /// if the resource would exceed the [limits](Self::set_limits) of the table.
+    /// Prefer this method over [`Self::add`] for resources created on behalf of a webview.
+    pub fn try_add<T: Resource>(&mut self, resource: T) -> crate::Result<ResourceId> {
+        self.try_add_arc(Arc::new(resource))
+    }
+
+    /// Inserts a `Arc`-wrapped resource into the resource table, checking the limits of the table.
+    ///
+    /// See [`Self::try_add`].
+    pub fn try_add_arc<T: Resource>(&mut self, resource: Arc<T>) -> crate::Result<ResourceId> {
+        self.try_add_arc_dyn(resource as Arc<dyn Resource>)
+    }
+
+    /// Inserts a `Arc`-wrapped resource into the resource table, checking the limits of the table.
+    ///
+    /// See [`Self::try_add`].
+    pub fn try_add_arc_dyn(&mut self, resource: Arc<dyn Resource>) -> crate::Result<ResourceId> {
+        self.try_add_all_dyn([resource]).map(|rids| rids[0])
+    }
+
+    /// Inserts `Arc`-wrapped resources into the resource table, checking the limits of the table
+    /// for all of them upfront, so either every resource is added or none is.
+    ///
+    /// Returns the resource IDs in the order of the resources.
+    pub fn try_add_all_dyn(
+        &mut self,
+        resources: impl IntoIterator<Item = Arc<dyn Resource>>,
+    ) -> crate::Result<Vec<ResourceId>> {
+        let entries = resources
+            .into_iter()
+            .map(ResourceEntry::new)
+            .collect::<Vec<_>>();
+        self.check_capacity(entries.len())?;
+        if let Some(max) = self.limits.max_size {
+            if self.size + entries.iter().map(|entry| entry.size).sum::<usize>() > max {
+                return Err(crate::Error::ResourceLimitExceeded("size", max));
+            }
+        }
+        Ok(entries
+            .into_iter()
+            .map(|entry| self.insert(entry))
+            .collect())
+    }
+
+    /// Checks that `count` more resources fit in the count limit of the table,
+    /// to not create resources that cannot be added.
+    pub fn check_capacity(&self, count: usize) -> crate::Result<()> {
+        match self.limits.max_count {
+            Some(max) if self.index.len() + count > max => {
+                Err(crate::Error::ResourceLimitExceeded("count", max))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Inserts resource into the resource table, which takes ownership of it.
     ///
     /// The resource type is erased at runtime and must be statically known
     /// when retrieving it through `get()`.
+    /// This method does not check the limits of the table, see [`Self::try_add`].
     ///
     /// Returns a unique resource ID, which acts as a key for this resource.
     pub fn add<T: Resource>(&mut self, resource: T) -> ResourceId {
@@ -106,12 +240,23 @@ impl ResourceTable {
     ///
     /// Returns a unique resource ID, which acts as a key for this resource.
     pub fn add_arc_dyn(&mut self, resource: Arc<dyn Resource>) -> ResourceId {
+        self.insert(ResourceEntry::new(resource))
+    }
+
+    fn insert(&mut self, entry: ResourceEntry) -> ResourceId {
         let rid = Self::new_random_rid();
-        let removed_resource = self.index.insert(rid, resource);
+        self.size += entry.size;
+        let removed_resource = self.index.insert(rid, entry);
         assert!(removed_resource.is_none());
         rid
     }
 
+    fn remove(&mut self, rid: ResourceId) -> Option<Arc<dyn Resource>> {
+        let entry = self.index.remove(&rid)?;
+        self.size -= entry.size;
+        Some(entry.resource)
+    }
+
     /// Returns true if any resource with the given `rid` exists.
     pub fn has(&self, rid: ResourceId) -> bool {
         self.index.contains_key(&rid)
@@ -123,7 +268,7 @@ impl ResourceTable {
     pub fn get<T: Resource>(&self, rid: ResourceId) -> crate::Result<Arc<T>> {
         self.index
             .get(&rid)
-            .and_then(|rc| rc.downcast_arc::<T>())
+            .and_then(|entry| entry.resource.downcast_arc::<T>())
             .cloned()
             .ok_or_else(|| crate::Error::BadResourceId(rid))
     }
@@ -133,18 +278,18 @@ impl ResourceTable {
     pub fn get_any(&self, rid: ResourceId) -> crate::Result<Arc<dyn Resource>> {
         self.index
             .get(&rid)
+            .map(|entry| entry.resource.clone())
             .ok_or_else(|| crate::Error::BadResourceId(rid))
-            .cloned()
     }
 
     /// Replaces a resource with a new resource.
     ///
     /// Panics if the resource does not exist.
     pub fn replace<T: Resource>(&mut self, rid: ResourceId, resource: T) {
-        let result = self
-            .index
-            .insert(rid, Arc::new(resource) as Arc<dyn Resource>);
-        assert!(result.is_some());
+        let entry = ResourceEntry::new(Arc::new(resource));
+        self.size += entry.size;
+        let result = self.index.insert(rid, entry);
+        self.size -= result.expect("resource does not exist").size;
     }
 
     /// Removes a resource of type `T` from the resource table and returns it.
@@ -159,7 +304,7 @@ impl ResourceTable {
     /// type `T` from `Arc<T>`.
     pub fn take<T: Resource>(&mut self, rid: ResourceId) -> crate::Result<Arc<T>> {
         let resource = self.get::<T>(rid)?;
-        self.index.remove(&rid);
+        self.remove(rid);
         Ok(resource)
     }
 
@@ -172,8 +317,7 @@ impl ResourceTable {
     /// on success. In particular, be really careful when you want to extract the
     /// inner value of type `T` from `Arc<T>`.
     pub fn take_any(&mut self, rid: ResourceId) -> crate::Result<Arc<dyn Resource>> {
-        self.index
-            .remove(&rid)
+        self.remove(rid)
             .ok_or_else(|| crate::Error::BadResourceId(rid))
     }
 
@@ -184,7 +328,19 @@ impl ResourceTable {
     pub fn names(&self) -> impl Iterator<Item = (ResourceId, Cow<'_, str>)> {
         self.index
             .iter()
-            .map(|(&id, resource)| (id, resource.name()))
+            .map(|(&id, entry)| (id, entry.resource.name()))
+    }
+
+    /// Returns an iterator that yields the [`ResourceInfo`] of every resource
+    /// that's currently in the resource table, ordered by resource ID.
+    /// This can be used to find leaked resources.
+    pub fn resources(&self) -> impl Iterator<Item = ResourceInfo> + '_ {
+        self.index.iter().map(|(&rid, entry)| ResourceInfo {
+            rid,
+            name: entry.resource.name().into_owned(),
+            created_at: entry.created_at,
+            size: entry.size,
+        })
     }
 
     /// Removes the resource with the given `rid` from the resource table. If the
@@ -194,9 +350,69 @@ impl ResourceTable {
     /// may implement the `close()` method to perform clean-ups such as canceling
     /// ops.
     pub fn close(&mut self, rid: ResourceId) -> crate::Result<()> {
-        self.index
-            .remove(&rid)
+        self.remove(rid)
             .ok_or_else(|| crate::Error::BadResourceId(rid))
             .map(|resource| resource.close())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Resource, ResourceLimits, ResourceTable};
+
+    struct Buffer(Vec<u8>);
+
+    impl Resource for Buffer {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let mut table = ResourceTable::default();
+        table.set_limits(ResourceLimits {
+            max_count: Some(2),
+            max_size: Some(10),
+        });
+
+        let rid = table.try_add(Buffer(vec![0; 6])).unwrap();
+        assert!(matches!(
+            table.try_add(Buffer(vec![0; 6])),
+            Err(crate::Error::ResourceLimitExceeded("size", 10))
+        ));
+        table.try_add(Buffer(vec![0; 4])).unwrap();
+        assert!(matches!(
+            table.try_add(Buffer(Vec::new())),
+            Err(crate::Error::ResourceLimitExceeded("count", 2))
+        ));
+        assert_eq!(table.size(), 10);
+
+        table.close(rid).unwrap();
+        assert_eq!((table.len(), table.size()), (1, 4));
+        let resources = table.resources().collect::<Vec<_>>();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].size, 4);
+        assert!(resources[0].name.ends_with("Buffer"));
+
+        // a batch is added entirely or not at all
+        assert!(matches!(
+            table.try_add_all_dyn([
+                Arc::new(Buffer(vec![0; 2])) as Arc<dyn Resource>,
+                Arc::new(Buffer(vec![0; 6])),
+            ]),
+            Err(crate::Error::ResourceLimitExceeded("count", 2))
+        ));
+        assert!(table.check_capacity(1).is_ok());
+        assert_eq!((table.len(), table.size()), (1, 4));
+        assert_eq!(
+            table
+                .try_add_all_dyn([Arc::new(Buffer(vec![0; 6])) as Arc<dyn Resource>])
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+
 use crate::{
     command,
     plugin::{Builder, TauriPlugin},
@@ -11,8 +15,36 @@ fn close<R: Runtime>(webview: Webview<R>, rid: ResourceId) -> crate::Result<()>
     webview.resources_table().close(rid)
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourcePayload {
+    rid: ResourceId,
+    name: String,
+    /// Milliseconds since the Unix epoch.
+    created_at: u64,
+    size: usize,
+}
+
+#[command(root = "crate")]
+fn list<R: Runtime>(webview: Webview<R>) -> Vec<ResourcePayload> {
+    webview
+        .resources_table()
+        .resources()
+        .map(|resource| ResourcePayload {
+            rid: resource.rid,
+            name: resource.name,
+            created_at: resource
+                .created_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            size: resource.size,
+        })
+        .collect()
+}
+
 pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("resources")
-        .invoke_handler(crate::generate_handler![close])
+        .invoke_handler(crate::generate_handler![close, list])
         .build()
 }
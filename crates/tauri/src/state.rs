@@ -1,21 +1,36 @@
+use std::{
+    any::{Any, TypeId},
+    cell::Cell,
+    collections::{hash_map::Entry, HashMap},
+    marker::PhantomData,
+    sync::{Arc, Condvar, Mutex, RwLock},
+};
+
+use tokio::sync::watch;
+
 use crate::{
-    ipc::{CommandArg, CommandItem, InvokeError},
+    ipc::{codes, CommandArg, CommandItem, InvokeError, IpcError},
     Runtime,
 };
 use state::TypeMap;
 
 /// A guard for a state value.
 ///
+/// A value that is [replaced](crate::Manager::replace_state) or [removed](crate::Manager::unmanage)
+/// is kept alive until its state manager is dropped, since [`State::inner`] borrows it for `'r`.
+///
 /// See [`Manager::manage`](`crate::Manager::manage`) for usage examples.
-pub struct State<'r, T: Send + Sync + 'static>(&'r T);
+pub struct State<'r, T: Send + Sync + 'static>(Arc<T>, PhantomData<&'r T>);
 
 impl<'r, T: Send + Sync + 'static> State<'r, T> {
-    /// Retrieve a borrow to the underlying value with a lifetime of `'r`.
+    /// Retrieve a borrow to the underlying value.
     /// Using this method is typically unnecessary as `State` implements
     /// [`std::ops::Deref`] with a [`std::ops::Deref::Target`] of `T`.
     #[inline(always)]
     pub fn inner(&self) -> &'r T {
-        self.0
+        // SAFETY: the guard is only created by borrowing its state manager for `'r`,
+        // and the state manager keeps every value it held alive until it is dropped
+        unsafe { &*Arc::as_ptr(&self.0) }
     }
 }
 
@@ -24,13 +39,13 @@ impl<T: Send + Sync + 'static> std::ops::Deref for State<'_, T> {
 
     #[inline(always)]
     fn deref(&self) -> &T {
-        self.0
+        &self.0
     }
 }
 
 impl<T: Send + Sync + 'static> Clone for State<'_, T> {
     fn clone(&self) -> Self {
-        State(self.0)
+        State(self.0.clone(), PhantomData)
     }
 }
 
@@ -41,41 +56,678 @@ impl<'r, T: Send + Sync + std::fmt::Debug> std::fmt::Debug for State<'r, T> {
 }
 
 impl<'r, 'de: 'r, T: Send + Sync + 'static, R: Runtime> CommandArg<'de, R> for State<'r, T> {
-    /// Grabs the [`State`] from the [`CommandItem`]. This will never fail.
+    /// Grabs the [`State`] from the [`CommandItem`].
+    ///
+    /// The state managed by the invoking webview is used first, then the one managed by its window,
+    /// and then the application state.
+    ///
+    /// If the state is initialized asynchronously or lazily, this waits for the initialization:
+    /// synchronous commands block on it and async commands wait for it asynchronously.
+    ///
+    /// Fails with [`codes::STATE_NOT_MANAGED`] if the state was not managed
+    /// and with [`codes::STATE_INIT_FAILED`] if its initialization failed.
     fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
-        Ok(command.message.state_ref().try_get().unwrap_or_else(|| {
-      panic!(
-        "state not managed for field `{}` on command `{}`. You must call `.manage()` before using this command",
-        command.key, command.name
-      )
-    }))
+        if let Some(state) = find_scoped(&command) {
+            return Ok(state);
+        }
+        let state = command.message.state_ref();
+        let result = match PENDING_ACCESS.get() {
+            None => state.wait(),
+            Some(_) => state.resolve(),
+        };
+        if result.as_ref().err() == Some(&StateError::NotReady) {
+            PENDING_ACCESS.set(Some(true));
+        }
+        result.map_err(|error| error.into_invoke_error(&command))
+    }
+}
+
+thread_local! {
+    /// Set by [`without_waiting`], and to `Some(true)` when a state was not ready.
+    static PENDING_ACCESS: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Runs `f` without blocking on the pending states it accesses as [`State`] command arguments,
+/// so the async commands can wait for them asynchronously instead.
+///
+/// Also returns whether one of those states was [not ready](StateError::NotReady).
+pub(crate) fn without_waiting<T>(f: impl FnOnce() -> T) -> (T, bool) {
+    struct Reset(Option<bool>);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            PENDING_ACCESS.set(self.0);
+        }
+    }
+
+    let reset = Reset(PENDING_ACCESS.replace(Some(false)));
+    let value = f();
+    let not_ready = PENDING_ACCESS.get() == Some(true);
+    drop(reset);
+    (value, not_ready)
+}
+
+fn find_scoped<'de, T: Send + Sync + 'static, R: Runtime>(
+    command: &CommandItem<'de, R>,
+) -> Option<State<'de, T>> {
+    command
+        .message
+        .scoped_state
+        .iter()
+        .find_map(|state| state.get_value())
+}
+
+/// The reason a [`State`] could not be retrieved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StateError {
+    /// The state is not managed.
+    NotManaged,
+    /// The asynchronous or lazy initialization of the state is not finished.
+    NotReady,
+    /// The initialization of the state failed with the given error.
+    InitFailed(String),
+}
+
+impl StateError {
+    fn into_invoke_error<R: Runtime>(self, command: &CommandItem<'_, R>) -> InvokeError {
+        let (code, message) = match self {
+            Self::NotManaged => (
+                codes::STATE_NOT_MANAGED,
+                format!(
+                    "state not managed for field `{}` on command `{}`. You must call `.manage()` before using this command",
+                    command.key, command.name
+                ),
+            ),
+            Self::NotReady => (
+                codes::STATE_NOT_READY,
+                format!(
+                    "state for field `{}` on command `{}` is still being initialized",
+                    command.key, command.name
+                ),
+            ),
+            Self::InitFailed(error) => (
+                codes::STATE_INIT_FAILED,
+                format!(
+                    "failed to initialize state for field `{}` on command `{}`: {error}",
+                    command.key, command.name
+                ),
+            ),
+        };
+        IpcError::new(code, message).into()
+    }
+}
+
+/// The message of a panic that failed the initialization of a state.
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    format!("the initialization panicked: {message}")
+}
+
+/// A change of a managed state, see [`Manager::on_state_change`](crate::Manager::on_state_change).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StateChange {
+    /// The state was managed.
+    Managed,
+    /// The state was replaced with a new value.
+    Replaced,
+    /// The state was removed.
+    Unmanaged,
+}
+
+type StateChangeHandler = Arc<dyn Fn(StateChange) + Send + Sync>;
+
+/// The storage of a state type, swapped by [`StateManager::replace`] and [`StateManager::unmanage`].
+struct Slot<T>(RwLock<Option<Arc<T>>>);
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self(RwLock::new(None))
+    }
+}
+
+impl<T> Slot<T> {
+    fn get(&self) -> Option<Arc<T>> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn is_set(&self) -> bool {
+        self.0.read().unwrap().is_some()
+    }
+
+    /// Sets the value, returning the previous one.
+    fn swap(&self, value: Option<T>) -> Option<Arc<T>> {
+        std::mem::replace(&mut *self.0.write().unwrap(), value.map(Arc::new))
+    }
+}
+
+type StateInitializer = Arc<dyn Fn() + Send + Sync>;
+
+/// The initialization status of a pending state.
+#[derive(Debug, Clone)]
+enum InitStatus {
+    /// The lazy initializer was not called yet.
+    Idle,
+    Running,
+    /// The initialization failed, the lazy initializer is called again on the next access.
+    Failed(String),
+}
+
+/// A state that is initialized asynchronously or on first access.
+struct PendingState {
+    /// The lazy initializer, called on the blocking thread pool.
+    init: Mutex<Option<StateInitializer>>,
+    status: Mutex<InitStatus>,
+}
+
+impl Default for PendingState {
+    fn default() -> Self {
+        Self {
+            init: Default::default(),
+            status: Mutex::new(InitStatus::Running),
+        }
+    }
+}
+
+impl PendingState {
+    /// Gets the status, then starts the lazy initialization unless it is running.
+    fn access(&self) -> InitStatus {
+        let mut status = self.status.lock().unwrap();
+        let previous = status.clone();
+        if let (InitStatus::Idle | InitStatus::Failed(_), Some(init)) =
+            (&previous, self.init.lock().unwrap().clone())
+        {
+            *status = InitStatus::Running;
+            crate::async_runtime::spawn_blocking(move || init());
+        }
+        previous
     }
 }
 
 /// The Tauri state manager.
-#[derive(Debug)]
-pub struct StateManager(pub(crate) TypeMap![Send + Sync]);
+pub struct StateManager {
+    slots: TypeMap![Send + Sync],
+    pending: Mutex<HashMap<TypeId, Arc<PendingState>>>,
+    /// Notified when the initialization of a pending state finishes.
+    initialized: watch::Sender<()>,
+    /// The number of finished initializations, to block on them with `initialized_changed`.
+    initialized_count: Mutex<u64>,
+    initialized_changed: Condvar,
+    handlers: Mutex<HashMap<TypeId, Vec<StateChangeHandler>>>,
+    /// The replaced and removed values, kept alive for the [`State::inner`] borrows.
+    retired: Mutex<Vec<Arc<dyn Any + Send + Sync>>>,
+}
+
+impl std::fmt::Debug for StateManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateManager")
+            .field("len", &self.slots.len())
+            .finish()
+    }
+}
 
 impl StateManager {
     pub(crate) fn new() -> Self {
-        Self(<TypeMap![Send + Sync]>::new())
+        Self {
+            slots: <TypeMap![Send + Sync]>::new(),
+            pending: Default::default(),
+            initialized: watch::Sender::new(()),
+            initialized_count: Default::default(),
+            initialized_changed: Default::default(),
+            handlers: Default::default(),
+            retired: Default::default(),
+        }
+    }
+
+    fn slot<T: Send + Sync + 'static>(&self) -> &Slot<T> {
+        if let Some(slot) = self.slots.try_get() {
+            return slot;
+        }
+        // the slots are never removed, a concurrent call may have set it first
+        self.slots.set(Slot::<T>::default());
+        self.slots.get()
     }
 
+    /// Sets the state for the type, returning `false` if it is already managed.
+    ///
+    /// A state whose initialization failed can be set again.
     pub(crate) fn set<T: Send + Sync + 'static>(&self, state: T) -> bool {
-        self.0.set(state)
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if let Entry::Occupied(entry) = pending.entry(TypeId::of::<T>()) {
+                if !matches!(*entry.get().status.lock().unwrap(), InitStatus::Failed(_)) {
+                    return false;
+                }
+                entry.remove();
+            }
+        }
+        self.insert(state)
+    }
+
+    fn insert<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        let inserted = {
+            let mut value = self.slot::<T>().0.write().unwrap();
+            if value.is_some() {
+                false
+            } else {
+                *value = Some(Arc::new(state));
+                true
+            }
+        };
+        if inserted {
+            self.notify::<T>(StateChange::Managed);
+        }
+        inserted
+    }
+
+    /// Sets the state for the type, returning `true` if a previous value was replaced.
+    ///
+    /// The previous value is dropped with the state manager.
+    pub(crate) fn replace<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        self.remove_pending::<T>();
+        let replaced = self.retire(self.slot::<T>().swap(Some(state)));
+        self.notify::<T>(if replaced {
+            StateChange::Replaced
+        } else {
+            StateChange::Managed
+        });
+        replaced
+    }
+
+    /// Removes the state for the type, returning `true` if it was managed.
+    pub(crate) fn unmanage<T: Send + Sync + 'static>(&self) -> bool {
+        self.remove_pending::<T>();
+        let removed = self.retire(
+            self.slots
+                .try_get::<Slot<T>>()
+                .and_then(|slot| slot.swap(None)),
+        );
+        if removed {
+            self.notify::<T>(StateChange::Unmanaged);
+        }
+        removed
+    }
+
+    /// Keeps a previous value alive until the state manager is dropped, returning `true` if there was one.
+    fn retire<T: Send + Sync + 'static>(&self, value: Option<Arc<T>>) -> bool {
+        match value {
+            Some(value) => {
+                self.retired.lock().unwrap().push(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pending<T: 'static>(&self) -> Option<Arc<PendingState>> {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .cloned()
+    }
+
+    /// Stops tracking the initialization of the state, waking up the accesses waiting for it.
+    fn remove_pending<T: 'static>(&self) {
+        if self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&TypeId::of::<T>())
+            .is_some()
+        {
+            self.notify_initialized();
+        }
+    }
+
+    fn notify_initialized(&self) {
+        *self.initialized_count.lock().unwrap() += 1;
+        self.initialized_changed.notify_all();
+        self.initialized.send_replace(());
+    }
+
+    /// Reserves the state for a value set later with [`Self::finish`],
+    /// returning `false` if the state is already managed.
+    pub(crate) fn set_pending<T: Send + Sync + 'static>(&self) -> bool {
+        if self.slot::<T>().is_set() {
+            return false;
+        }
+        match self.pending.lock().unwrap().entry(TypeId::of::<T>()) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(Default::default());
+                true
+            }
+        }
+    }
+
+    /// Sets the initializer of a pending state, called on the blocking thread pool on its first access
+    /// and on the next access after a failure.
+    /// The initializer must call [`Self::finish`].
+    pub(crate) fn set_lazy<T: Send + Sync + 'static, F: Fn() + Send + Sync + 'static>(
+        &self,
+        init: F,
+    ) {
+        if let Some(pending) = self.pending::<T>() {
+            pending.init.lock().unwrap().replace(Arc::new(init));
+            *pending.status.lock().unwrap() = InitStatus::Idle;
+        }
+    }
+
+    /// Sets the value of a pending state and wakes up the accesses waiting for it.
+    pub(crate) fn finish<T: Send + Sync + 'static>(&self, result: Result<T, String>) {
+        let Some(pending) = self.pending::<T>() else {
+            return;
+        };
+        match result {
+            Ok(state) => {
+                self.insert(state);
+                self.remove_pending::<T>();
+            }
+            // keep the failed states so their accesses get the error
+            Err(error) => {
+                *pending.status.lock().unwrap() = InitStatus::Failed(error);
+                self.notify_initialized();
+            }
+        }
+    }
+
+    pub(crate) fn on_change<
+        T: Send + Sync + 'static,
+        F: Fn(StateChange) + Send + Sync + 'static,
+    >(
+        &self,
+        handler: F,
+    ) {
+        self.handlers
+            .lock()
+            .unwrap()
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(Arc::new(handler));
+    }
+
+    fn notify<T: 'static>(&self, change: StateChange) {
+        // clone the handlers so they can use the state manager
+        let handlers = self
+            .handlers
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .unwrap_or_default();
+        for handler in handlers {
+            handler(change);
+        }
     }
 
     /// Gets the state associated with the specified type.
+    ///
+    /// Blocks until the state is ready if it is initialized asynchronously or lazily.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state was not set or if its initialization failed.
     pub fn get<T: Send + Sync + 'static>(&self) -> State<'_, T> {
-        State(
-            self.0
-                .try_get()
-                .expect("state: get() called before set() for given type"),
-        )
+        match self.wait() {
+            Ok(state) => state,
+            Err(StateError::InitFailed(error)) => {
+                panic!("state: failed to initialize the state for given type: {error}")
+            }
+            Err(_) => panic!("state: get() called before set() for given type"),
+        }
     }
 
     /// Gets the state associated with the specified type.
+    ///
+    /// Returns `None` if the state is initialized asynchronously or lazily and is not ready yet,
+    /// starting its lazy initialization in the background if needed.
     pub fn try_get<T: Send + Sync + 'static>(&self) -> Option<State<'_, T>> {
-        self.0.try_get().map(State)
+        self.resolve().ok()
+    }
+
+    /// Gets the state without waiting for its initialization.
+    pub(crate) fn resolve<T: Send + Sync + 'static>(&self) -> Result<State<'_, T>, StateError> {
+        if let Some(state) = self.get_value() {
+            return Ok(state);
+        }
+        let Some(pending) = self.pending::<T>() else {
+            // the initialization may have finished in the meantime
+            return self.get_value().ok_or(StateError::NotManaged);
+        };
+        match pending.access() {
+            InitStatus::Failed(error) => Err(StateError::InitFailed(error)),
+            InitStatus::Idle | InitStatus::Running => Err(StateError::NotReady),
+        }
+    }
+
+    /// Gets the state, blocking until its initialization finishes if it is pending.
+    pub(crate) fn wait<T: Send + Sync + 'static>(&self) -> Result<State<'_, T>, StateError> {
+        loop {
+            // read the count before the lookup to not miss an initialization that finishes in between
+            let count = *self.initialized_count.lock().unwrap();
+            match self.resolve() {
+                Err(StateError::NotReady) => {
+                    let _count = self
+                        .initialized_changed
+                        .wait_while(self.initialized_count.lock().unwrap(), |c| *c == count)
+                        .unwrap();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns a receiver notified when the initialization of a pending state finishes,
+    /// used to wait for a state that is [not ready](StateError::NotReady).
+    pub(crate) fn subscribe_initialized(&self) -> watch::Receiver<()> {
+        self.initialized.subscribe()
+    }
+
+    fn get_value<T: Send + Sync + 'static>(&self) -> Option<State<'_, T>> {
+        let value = self.slots.try_get::<Slot<T>>()?.get()?;
+        Some(State(value, PhantomData))
+    }
+}
+
+/// The state managed by a window or webview with `manage_scoped`, dropped when it is destroyed.
+#[derive(Debug)]
+pub(crate) struct ScopedStateManager(Mutex<Option<Arc<StateManager>>>);
+
+impl Default for ScopedStateManager {
+    fn default() -> Self {
+        Self(Mutex::new(Some(Arc::new(StateManager::new()))))
+    }
+}
+
+impl ScopedStateManager {
+    /// The state manager, or `None` if the window or webview was destroyed.
+    pub(crate) fn get(&self) -> Option<Arc<StateManager>> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        self.get()
+            .map(|manager| manager.set(state))
+            .unwrap_or(false)
+    }
+
+    /// Drops the state. The pending commands that use it keep it alive until they finish.
+    pub(crate) fn clear(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+/// A guard for a state value managed by a window or webview, or by the application.
+///
+/// It is returned by the scoped lookups such as [`crate::Webview::scoped_state`]
+/// and keeps the value alive even if its window or webview is destroyed.
+pub struct ScopedState<T: Send + Sync + 'static>(Arc<T>);
+
+impl<T: Send + Sync + 'static> ScopedState<T> {
+    /// Finds the state in the first manager that has it.
+    pub(crate) fn find(managers: impl IntoIterator<Item = Arc<StateManager>>) -> Option<Self> {
+        managers
+            .into_iter()
+            .find_map(|manager| manager.try_get::<T>().map(|state| Self(state.0)))
+    }
+}
+
+impl<T: Send + Sync + 'static> std::ops::Deref for ScopedState<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Send + Sync + std::fmt::Debug + 'static> std::fmt::Debug for ScopedState<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScopedState").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{State, StateChange, StateError, StateManager};
+
+    struct Profile(&'static str);
+
+    struct Connection(Arc<Mutex<bool>>);
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            *self.0.lock().unwrap() = true;
+        }
+    }
+
+    #[test]
+    fn replaces_and_unmanages_state() {
+        let manager = StateManager::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let changes_ = changes.clone();
+        manager.on_change::<Profile, _>(move |change| changes_.lock().unwrap().push(change));
+
+        assert!(manager.set(Profile("default")));
+        let previous = manager.get::<Profile>();
+
+        assert!(manager.replace(Profile("work")));
+        assert_eq!(previous.inner().0, "default");
+        assert_eq!(manager.get::<Profile>().inner().0, "work");
+
+        assert!(manager.unmanage::<Profile>());
+        assert!(!manager.unmanage::<Profile>());
+        assert!(manager.try_get::<Profile>().is_none());
+        assert_eq!(previous.inner().0, "default");
+
+        assert!(!manager.replace(Profile("personal")));
+        assert_eq!(manager.get::<Profile>().inner().0, "personal");
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                StateChange::Managed,
+                StateChange::Replaced,
+                StateChange::Unmanaged,
+                StateChange::Managed,
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_replaced_state_with_the_manager() {
+        let manager = StateManager::new();
+        let closed = Arc::new(Mutex::new(false));
+        manager.set(Connection(closed.clone()));
+
+        // the reference outlives the guard
+        let connection = manager.get::<Connection>().inner();
+        manager.replace(Connection(Default::default()));
+        assert!(!*connection.0.lock().unwrap());
+
+        let replaced_closed = manager.get::<Connection>().inner().0.clone();
+        manager.unmanage::<Connection>();
+        assert!(!*replaced_closed.lock().unwrap());
+
+        drop(manager);
+        assert!(*closed.lock().unwrap());
+        assert!(*replaced_closed.lock().unwrap());
+    }
+
+    /// Waits for the state like the async commands do.
+    async fn wait<T: Send + Sync + 'static>(
+        manager: &StateManager,
+    ) -> Result<State<'_, T>, StateError> {
+        loop {
+            let mut initialized = manager.subscribe_initialized();
+            match manager.resolve() {
+                Err(StateError::NotReady) => initialized.changed().await.unwrap(),
+                result => return result,
+            }
+        }
+    }
+
+    #[test]
+    fn waits_for_pending_state() {
+        let manager = Arc::new(StateManager::new());
+
+        assert!(manager.set_pending::<Profile>());
+        assert!(!manager.set(Profile("default")));
+        assert_eq!(
+            manager.resolve::<Profile>().map(|p| p.inner().0),
+            Err(StateError::NotReady)
+        );
+        let manager_ = manager.clone();
+        let waiter = crate::async_runtime::spawn(async move {
+            wait::<Profile>(&manager_).await.map(|p| p.inner().0)
+        });
+        manager.finish(Ok(Profile("async")));
+        assert_eq!(crate::async_runtime::block_on(waiter).unwrap(), Ok("async"));
+
+        struct Keystore(usize);
+        assert!(manager.set_pending::<Keystore>());
+        let manager_ = manager.clone();
+        let attempts = Arc::new(Mutex::new(0));
+        let attempts_ = attempts.clone();
+        manager.set_lazy::<Keystore, _>(move || {
+            let mut attempts = attempts_.lock().unwrap();
+            *attempts += 1;
+            manager_.finish(if *attempts == 1 {
+                Err("locked".into())
+            } else {
+                Ok(Keystore(3))
+            });
+        });
+        // the first access starts the initialization without waiting for it
+        assert!(manager.try_get::<Keystore>().is_none());
+        assert_eq!(
+            crate::async_runtime::block_on(wait::<Keystore>(&manager)).map(|k| k.inner().0),
+            Err(StateError::InitFailed("locked".into()))
+        );
+        // the access that got the error started a new attempt
+        assert_eq!(
+            crate::async_runtime::block_on(wait::<Keystore>(&manager)).map(|k| k.inner().0),
+            Ok(3)
+        );
+        assert_eq!(*attempts.lock().unwrap(), 2);
+
+        struct Database(&'static str);
+        assert!(manager.set_pending::<Database>());
+        manager.finish::<Database>(Err("connection refused".into()));
+        assert_eq!(
+            manager.resolve::<Database>().map(|db| db.inner().0),
+            Err(StateError::InitFailed("connection refused".into()))
+        );
+        assert!(manager.try_get::<Database>().is_none());
+        // a failed state can be managed again
+        assert!(manager.set(Database("fallback")));
+        assert_eq!(manager.get::<Database>().inner().0, "fallback");
     }
 }
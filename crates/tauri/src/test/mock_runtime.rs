@@ -4,7 +4,7 @@
 use tauri_runtime::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     monitor::Monitor,
-    webview::{DetachedWebview, PendingWebview},
+    webview::{DetachedWebview, PageLoadEvent, PendingWebview},
     window::{CursorIcon, DetachedWindow, PendingWindow, RawWindow, WindowEvent, WindowId},
     window::{WindowBuilder, WindowBuilderBase},
     DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, ProgressBarState,
@@ -153,15 +153,18 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
             },
         );
 
-        let webview = webview_id.map(|id| DetachedWebview {
-            label: pending.label.clone(),
-            dispatcher: MockWebviewDispatcher {
-                id,
-                context: self.context.clone(),
-                url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-                last_evaluated_script: Default::default(),
-            },
-        });
+        let webview = webview_id
+            .zip(pending.webview)
+            .map(|(id, webview)| DetachedWebview {
+                label: pending.label.clone(),
+                dispatcher: MockWebviewDispatcher {
+                    id,
+                    context: self.context.clone(),
+                    url: Arc::new(Mutex::new(webview.url)),
+                    last_evaluated_script: Default::default(),
+                    on_page_load_handler: PageLoadHandler::new(webview.on_page_load_handler),
+                },
+            });
 
         Ok(DetachedWindow {
             id,
@@ -192,6 +195,7 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
                 context: self.context.clone(),
                 last_evaluated_script: Default::default(),
                 url: Arc::new(Mutex::new(pending.url)),
+                on_page_load_handler: PageLoadHandler::new(pending.on_page_load_handler),
             },
         })
     }
@@ -272,12 +276,30 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     }
 }
 
+type OnPageLoadHandler = dyn Fn(Url, PageLoadEvent) + Send;
+
+#[derive(Clone)]
+struct PageLoadHandler(Arc<Mutex<Option<Box<OnPageLoadHandler>>>>);
+
+impl PageLoadHandler {
+    fn new(handler: Option<Box<OnPageLoadHandler>>) -> Self {
+        Self(Arc::new(Mutex::new(handler)))
+    }
+}
+
+impl fmt::Debug for PageLoadHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageLoadHandler").finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MockWebviewDispatcher {
     id: u32,
     context: RuntimeContext,
     url: Arc<Mutex<String>>,
     last_evaluated_script: Arc<Mutex<Option<String>>>,
+    on_page_load_handler: PageLoadHandler,
 }
 
 impl MockWebviewDispatcher {
@@ -524,6 +546,10 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
 
     fn navigate(&self, url: Url) -> Result<()> {
         *self.url.lock().unwrap() = url.to_string();
+        if let Some(handler) = &*self.on_page_load_handler.0.lock().unwrap() {
+            handler(url.clone(), PageLoadEvent::Started);
+            handler(url, PageLoadEvent::Finished);
+        }
         Ok(())
     }
 
@@ -742,15 +768,18 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
             },
         );
 
-        let webview = webview_id.map(|id| DetachedWebview {
-            label: pending.label.clone(),
-            dispatcher: MockWebviewDispatcher {
-                id,
-                context: self.context.clone(),
-                url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-                last_evaluated_script: Default::default(),
-            },
-        });
+        let webview = webview_id
+            .zip(pending.webview)
+            .map(|(id, webview)| DetachedWebview {
+                label: pending.label.clone(),
+                dispatcher: MockWebviewDispatcher {
+                    id,
+                    context: self.context.clone(),
+                    url: Arc::new(Mutex::new(webview.url)),
+                    last_evaluated_script: Default::default(),
+                    on_page_load_handler: PageLoadHandler::new(webview.on_page_load_handler),
+                },
+            });
 
         Ok(DetachedWindow {
             id,
@@ -780,6 +809,7 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
                 context: self.context.clone(),
                 last_evaluated_script: Default::default(),
                 url: Arc::new(Mutex::new(pending.url)),
+                on_page_load_handler: PageLoadHandler::new(pending.on_page_load_handler),
             },
         })
     }
@@ -1011,15 +1041,18 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
             },
         );
 
-        let webview = webview_id.map(|id| DetachedWebview {
-            label: pending.label.clone(),
-            dispatcher: MockWebviewDispatcher {
-                id,
-                context: self.context.clone(),
-                url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-                last_evaluated_script: Default::default(),
-            },
-        });
+        let webview = webview_id
+            .zip(pending.webview)
+            .map(|(id, webview)| DetachedWebview {
+                label: pending.label.clone(),
+                dispatcher: MockWebviewDispatcher {
+                    id,
+                    context: self.context.clone(),
+                    url: Arc::new(Mutex::new(webview.url)),
+                    last_evaluated_script: Default::default(),
+                    on_page_load_handler: PageLoadHandler::new(webview.on_page_load_handler),
+                },
+            });
 
         Ok(DetachedWindow {
             id,
@@ -1050,6 +1083,7 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
                 context: self.context.clone(),
                 last_evaluated_script: Default::default(),
                 url: Arc::new(Mutex::new(pending.url)),
+                on_page_load_handler: PageLoadHandler::new(pending.on_page_load_handler),
             },
         })
     }
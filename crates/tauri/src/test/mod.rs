@@ -104,6 +104,7 @@ pub fn mock_context<R: Runtime, A: Assets<R>>(assets: A) -> crate::Context<R> {
                 security: Default::default(),
                 tray_icon: None,
                 macos_private_api: false,
+                routing: Default::default(),
             },
             bundle: Default::default(),
             build: Default::default(),
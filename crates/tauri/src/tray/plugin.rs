@@ -9,7 +9,7 @@ use crate::{
     menu::{plugin::ItemKind, Menu, Submenu},
     plugin::{Builder, TauriPlugin},
     resources::ResourceId,
-    tray::TrayIconBuilder,
+    tray::{TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager, Runtime, Webview,
 };
 
@@ -32,7 +32,7 @@ struct TrayIconOptions {
 fn new<R: Runtime>(
     webview: Webview<R>,
     options: TrayIconOptions,
-    handler: Channel,
+    handler: Channel<TrayIconEvent>,
 ) -> crate::Result<(ResourceId, String)> {
     let mut builder = if let Some(id) = options.id {
         TrayIconBuilder::<R>::with_id(id)
@@ -80,7 +80,7 @@ fn new<R: Runtime>(
 
     let tray = builder.build(&webview)?;
     let id = tray.id().as_ref().to_string();
-    let rid = resources_table.add(tray);
+    let rid = resources_table.try_add(tray)?;
 
     Ok((rid, id))
 }
@@ -92,11 +92,11 @@ fn get_by_id<R: Runtime>(
     id: &str,
 ) -> crate::Result<Option<ResourceId>> {
     let tray = app.tray_by_id(id);
-    let maybe_rid = tray.map(|tray| {
+    tray.map(|tray| {
         let mut resources_table = webview.resources_table();
-        resources_table.add(tray)
-    });
-    Ok(maybe_rid)
+        resources_table.try_add(tray)
+    })
+    .transpose()
 }
 
 #[command(root = "crate")]
@@ -25,11 +25,13 @@ use crate::{
     app::{UriSchemeResponder, WebviewEvent},
     event::{EmitArgs, EventTarget},
     ipc::{
-        CallbackFn, CommandArg, CommandItem, Invoke, InvokeBody, InvokeError, InvokeMessage,
-        InvokeResolver, Origin, OwnedInvokeResponder,
+        cancel::PendingInvokes, codes, BodyCodec, CallbackFn, CancellationToken, CommandArg,
+        CommandItem, Invoke, InvokeBody, InvokeError, InvokeMessage, InvokeResolver, IpcError,
+        Origin, OwnedInvokeResponder,
     },
     manager::{webview::WebviewLabelDef, AppManager},
     sealed::{ManagerBase, RuntimeOrDispatch},
+    state::{ScopedState, ScopedStateManager, StateManager},
     AppHandle, Event, EventId, EventLoopMessage, Manager, ResourceTable, Runtime, Window,
 };
 
@@ -38,6 +40,7 @@ use std::{
     hash::{Hash, Hasher},
     path::PathBuf,
     sync::{Arc, Mutex, MutexGuard},
+    time::Instant,
 };
 
 pub(crate) type WebResourceRequestHandler =
@@ -799,6 +802,7 @@ pub struct Webview<R: Runtime> {
     /// The webview created by the runtime.
     pub(crate) webview: DetachedWebview<EventLoopMessage, R>,
     pub(crate) resources_table: Arc<Mutex<ResourceTable>>,
+    pub(crate) state: Arc<ScopedStateManager>,
 }
 
 impl<R: Runtime> std::fmt::Debug for Webview<R> {
@@ -818,6 +822,7 @@ impl<R: Runtime> Clone for Webview<R> {
             app_handle: self.app_handle.clone(),
             webview: self.webview.clone(),
             resources_table: self.resources_table.clone(),
+            state: self.state.clone(),
         }
     }
 }
@@ -847,6 +852,7 @@ impl<R: Runtime> Webview<R> {
             app_handle: window.app_handle.clone(),
             webview,
             resources_table: Default::default(),
+            state: Default::default(),
         }
     }
 
@@ -1107,12 +1113,29 @@ fn main() {
         let is_local = self.is_local_url(&request.url);
 
         let custom_responder = self.manager().webview.invoke_responder.clone();
+        let middlewares = self.manager().webview.ipc_middlewares();
+        let response_middlewares = middlewares.clone();
+        let started_at = Instant::now();
+
+        let cancellation = CancellationToken::default();
+        if let Some(pending) = self.try_state::<PendingInvokes>() {
+            pending.insert(self.label(), request.callback.0, cancellation.clone());
+        }
 
         let resolver = InvokeResolver::new(
             self.clone(),
             Arc::new(Mutex::new(Some(Box::new(
                 #[allow(unused_variables)]
-                move |webview: Webview<R>, cmd, response, callback, error| {
+                move |webview: Webview<R>, cmd, mut response, callback, error| {
+                    if let Some(pending) = webview.try_state::<PendingInvokes>() {
+                        pending.remove(webview.label(), callback.0);
+                    }
+
+                    let elapsed = started_at.elapsed();
+                    for middleware in response_middlewares.iter().rev() {
+                        middleware.on_response(&webview, &cmd, elapsed, &mut response);
+                    }
+
                     if let Some(responder) = &custom_responder {
                         (responder)(&webview, &cmd, &response, callback, error);
                     }
@@ -1123,6 +1146,8 @@ fn main() {
             request.cmd.clone(),
             request.callback,
             request.error,
+            cancellation.clone(),
+            BodyCodec::negotiate(&request.headers),
         );
 
         #[cfg(mobile)]
@@ -1134,8 +1159,16 @@ fn main() {
             request.cmd.to_string(),
             request.body,
             request.headers,
+            cancellation,
         );
 
+        for middleware in &middlewares {
+            if let Err(e) = middleware.on_invoke(&message) {
+                resolver.invoke_error(e);
+                return;
+            }
+        }
+
         let acl_origin = if is_local {
             Origin::Local
         } else {
@@ -1145,6 +1178,13 @@ fn main() {
         };
         let (resolved_acl, has_app_acl_manifest) = {
             let runtime_authority = manager.runtime_authority.lock().unwrap();
+            // logged first since resolving the access takes a use of a limited capability
+            runtime_authority.log_access(
+                &request.cmd,
+                message.webview.window().label(),
+                message.webview.label(),
+                &acl_origin,
+            );
             let acl = runtime_authority.resolve_access(
                 &request.cmd,
                 message.webview.window().label(),
@@ -1153,6 +1193,7 @@ fn main() {
             );
             (acl, runtime_authority.has_app_manifest())
         };
+        manager.remove_expired_capabilities();
 
         let mut invoke = Invoke {
             message,
@@ -1171,6 +1212,11 @@ fn main() {
         // we only check ACL on plugin commands or if the app defined its ACL manifest
         if (plugin_command.is_some() || has_app_acl_manifest)
             && request.cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
+            // streams are only available to the webview that received them from an allowed command
+            && request.cmd != crate::ipc::stream::NEXT_STREAM_ITEM_COMMAND
+            && request.cmd != crate::ipc::stream::CLOSE_STREAM_COMMAND
+            // a webview can only cancel its own invokes
+            && request.cmd != crate::ipc::cancel::CANCEL_INVOKE_COMMAND
             && invoke.acl.is_none()
         {
             #[cfg(debug_assertions)]
@@ -1178,7 +1224,8 @@ fn main() {
                 let (key, command_name) = plugin_command
                     .clone()
                     .unwrap_or_else(|| (tauri_utils::acl::APP_ACL_KEY, request.cmd.clone()));
-                invoke.resolver.reject(
+                invoke.resolver.reject(IpcError::new(
+                    codes::ACL_DENIED,
                     manager
                         .runtime_authority
                         .lock()
@@ -1190,12 +1237,13 @@ fn main() {
                             invoke.message.webview.label(),
                             &acl_origin,
                         ),
-                );
+                ));
             }
             #[cfg(not(debug_assertions))]
-            invoke
-                .resolver
-                .reject(format!("Command {} not allowed by ACL", request.cmd));
+            invoke.resolver.reject(IpcError::new(
+                codes::ACL_DENIED,
+                format!("Command {} not allowed by ACL", request.cmd),
+            ));
             return;
         }
 
@@ -1253,13 +1301,19 @@ fn main() {
             }
 
             if !handled {
-                resolver.reject(format!("Command {command} not found"));
+                resolver.reject(IpcError::new(
+                    codes::COMMAND_NOT_FOUND,
+                    format!("Command {command} not found"),
+                ));
             }
         } else {
             let command = invoke.message.command.clone();
             let handled = manager.run_invoke_handler(invoke);
             if !handled {
-                resolver.reject(format!("Command {command} not found"));
+                resolver.reject(IpcError::new(
+                    codes::COMMAND_NOT_FOUND,
+                    format!("Command {command} not found"),
+                ));
             }
         }
     }
@@ -1288,8 +1342,13 @@ fn main() {
             &format!("window['_{}']", handler.0),
         ))?;
 
+        let retained = listeners.retained(event, &target);
         listeners.listen_js(event, self.label(), target, id);
 
+        for emit_args in retained {
+            self.emit_js(&emit_args, event, &[id])?;
+        }
+
         Ok(id)
     }
 
@@ -1308,10 +1367,16 @@ fn main() {
         Ok(())
     }
 
-    pub(crate) fn emit_js(&self, emit_args: &EmitArgs, ids: &[u32]) -> crate::Result<()> {
+    pub(crate) fn emit_js(
+        &self,
+        emit_args: &EmitArgs,
+        listener_event: &str,
+        ids: &[u32],
+    ) -> crate::Result<()> {
         self.eval(&crate::event::emit_js_script(
             self.manager().listeners().function_name(),
             emit_args,
+            listener_event,
             &serde_json::to_string(ids)?,
         )?)?;
         Ok(())
@@ -1525,6 +1590,74 @@ tauri::Builder::default()
     }
 }
 
+/// Webview state.
+impl<R: Runtime> Webview<R> {
+    /// Adds a state to this webview, dropped when the webview is destroyed.
+    ///
+    /// The [`crate::State`] arguments of the commands invoked by this webview are resolved
+    /// against the webview state first, then the state of its window and finally the application state.
+    /// Returns `false` if the state for the type is already set or if the webview was destroyed.
+    ///
+    /// Unlike [`Manager::manage`], which always manages application state,
+    /// this state is only visible to the commands invoked by this webview.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use tauri::Manager;
+    ///
+    /// #[derive(Default)]
+    /// struct History(Mutex<Vec<String>>);
+    ///
+    /// #[tauri::command]
+    /// fn navigate(history: tauri::State<'_, History>, url: String) {
+    ///   history.0.lock().unwrap().push(url);
+    /// }
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let webview = app.get_webview_window("main").unwrap();
+    ///     webview.as_ref().manage_scoped(History::default());
+    ///     Ok(())
+    ///   })
+    ///   .invoke_handler(tauri::generate_handler![navigate]);
+    /// ```
+    pub fn manage_scoped<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        self.state.set(state)
+    }
+
+    /// Retrieves the state managed by this webview, its window or the application.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is not managed.
+    /// Use [`Self::try_scoped_state`] for a non-panicking version.
+    pub fn scoped_state<T: Send + Sync + 'static>(&self) -> ScopedState<T> {
+        self.try_scoped_state()
+            .expect("state() called before manage() for given type")
+    }
+
+    /// Attempts to retrieve the state managed by this webview, its window or the application.
+    pub fn try_scoped_state<T: Send + Sync + 'static>(&self) -> Option<ScopedState<T>> {
+        ScopedState::find(
+            self.scoped_state_managers()
+                .into_iter()
+                .chain([self.manager.state()]),
+        )
+    }
+
+    /// The state managers of this webview and its window, in lookup order.
+    pub(crate) fn scoped_state_managers(&self) -> Vec<Arc<StateManager>> {
+        let window = self.manager.get_window(&self.window_label());
+        self.state
+            .get()
+            .into_iter()
+            .chain(window.and_then(|window| window.state.get()))
+            .collect()
+    }
+}
+
 impl<R: Runtime> Manager<R> for Webview<R> {
     fn resources_table(&self) -> MutexGuard<'_, ResourceTable> {
         self.resources_table
@@ -22,6 +22,7 @@ use crate::{
         RuntimeHandle, WindowDispatch,
     },
     sealed::{ManagerBase, RuntimeOrDispatch},
+    state::{ScopedState, ScopedStateManager},
     utils::config::{WindowConfig, WindowEffectsConfig},
     webview::WebviewBuilder,
     EventLoopMessage, Manager, ResourceTable, Runtime, Theme, Webview, WindowEvent,
@@ -881,6 +882,7 @@ pub struct Window<R: Runtime> {
     #[cfg(desktop)]
     pub(crate) menu: Arc<Mutex<Option<WindowMenu<R>>>>,
     pub(crate) resources_table: Arc<Mutex<ResourceTable>>,
+    pub(crate) state: Arc<ScopedStateManager>,
 }
 
 impl<R: Runtime> std::fmt::Debug for Window<R> {
@@ -920,6 +922,7 @@ impl<R: Runtime> Clone for Window<R> {
             #[cfg(desktop)]
             menu: self.menu.clone(),
             resources_table: self.resources_table.clone(),
+            state: self.state.clone(),
         }
     }
 }
@@ -988,6 +991,7 @@ impl<R: Runtime> Window<R> {
             #[cfg(desktop)]
             menu: Arc::new(std::sync::Mutex::new(menu)),
             resources_table: Default::default(),
+            state: Default::default(),
         }
     }
 
@@ -2041,6 +2045,60 @@ tauri::Builder::default()
     }
 }
 
+/// Window state.
+impl<R: Runtime> Window<R> {
+    /// Adds a state to this window, dropped when the window is destroyed.
+    ///
+    /// It is used by the [`crate::State`] arguments of the commands invoked by the webviews of this window,
+    /// unless the webview manages a state of the same type.
+    /// Returns `false` if the state for the type is already set or if the window was destroyed.
+    ///
+    /// Unlike [`Manager::manage`], which always manages application state,
+    /// this state is only visible to the commands invoked by the webviews of this window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use tauri::Manager;
+    ///
+    /// #[derive(Default)]
+    /// struct Document(Mutex<String>);
+    ///
+    /// #[tauri::command]
+    /// fn edit(document: tauri::State<'_, Document>, text: String) {
+    ///   *document.0.lock().unwrap() = text;
+    /// }
+    ///
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     let window = app.get_webview_window("main").unwrap();
+    ///     window.as_ref().window().manage_scoped(Document::default());
+    ///     Ok(())
+    ///   })
+    ///   .invoke_handler(tauri::generate_handler![edit]);
+    /// ```
+    pub fn manage_scoped<T: Send + Sync + 'static>(&self, state: T) -> bool {
+        self.state.set(state)
+    }
+
+    /// Retrieves the state managed by this window, or the application state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is not managed.
+    /// Use [`Self::try_scoped_state`] for a non-panicking version.
+    pub fn scoped_state<T: Send + Sync + 'static>(&self) -> ScopedState<T> {
+        self.try_scoped_state()
+            .expect("state() called before manage() for given type")
+    }
+
+    /// Attempts to retrieve the state managed by this window, or the application state.
+    pub fn try_scoped_state<T: Send + Sync + 'static>(&self) -> Option<ScopedState<T>> {
+        ScopedState::find(self.state.get().into_iter().chain([self.manager.state()]))
+    }
+}
+
 /// The [`WindowEffectsConfig`] object builder
 #[derive(Default)]
 pub struct EffectsBuilder(WindowEffectsConfig);
@@ -50,6 +50,20 @@ pub fn bundle_project(settings: Settings) -> crate::Result<Vec<Bundle>> {
 
   package_types.sort_by_key(|a| a.priority());
 
+  if settings.asset_pack_enabled() {
+    if let Some(package_type) = package_types.iter().find(|p| {
+      !matches!(
+        p,
+        PackageType::Deb | PackageType::Rpm | PackageType::AppImage | PackageType::Updater
+      )
+    }) {
+      return Err(crate::Error::GenericError(format!(
+        "`build > assetPack` is only supported by the deb, rpm and appimage bundles, {} cannot be bundled with it",
+        package_type.short_name()
+      )));
+    }
+  }
+
   let mut bundles: Vec<Bundle> = Vec::new();
 
   let target_os = settings
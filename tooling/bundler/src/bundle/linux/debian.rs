@@ -29,6 +29,7 @@ use anyhow::Context;
 use flate2::{write::GzEncoder, Compression};
 use heck::AsKebabCase;
 use tar::HeaderMode;
+use tauri_utils::assets::pack::ASSET_PACK_FILE_NAME;
 use walkdir::WalkDir;
 
 use std::{
@@ -296,6 +297,9 @@ fn generate_md5sums(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
 /// `data_dir`.
 fn copy_resource_files(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
   let resource_dir = data_dir.join("usr/lib").join(settings.main_binary_name());
+  if let Some(asset_pack) = settings.asset_pack()? {
+    common::copy_file(&asset_pack, resource_dir.join(ASSET_PACK_FILE_NAME))?;
+  }
   settings.copy_resources(&resource_dir)
 }
 
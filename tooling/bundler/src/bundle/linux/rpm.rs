@@ -12,6 +12,7 @@ use std::{
   fs::{self, File},
   path::{Path, PathBuf},
 };
+use tauri_utils::assets::pack::ASSET_PACK_FILE_NAME;
 
 use super::freedesktop;
 
@@ -133,7 +134,8 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   }
 
   // Add resources
-  if settings.resource_files().count() > 0 {
+  let asset_pack = settings.asset_pack()?;
+  if settings.resource_files().count() > 0 || asset_pack.is_some() {
     let resource_dir = Path::new("/usr/lib").join(settings.main_binary_name());
     // Create an empty file, needed to add a directory to the RPM package
     // (cf https://github.com/rpm-rs/rpm/issues/177)
@@ -150,6 +152,11 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
       let dest = resource_dir.join(tauri_utils::resources::resource_relpath(&src));
       builder = builder.with_file(&src, FileOptions::new(dest.to_string_lossy()))?;
     }
+    // Then add the asset pack at the root of the resource directory
+    if let Some(asset_pack) = asset_pack {
+      let dest = resource_dir.join(ASSET_PACK_FILE_NAME);
+      builder = builder.with_file(&asset_pack, FileOptions::new(dest.to_string_lossy()))?;
+    }
   }
 
   // Add Desktop entry file
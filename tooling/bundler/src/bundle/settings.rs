@@ -7,6 +7,7 @@ use super::category::AppCategory;
 use crate::bundle::{common, platform::target_triple};
 pub use tauri_utils::config::WebviewInstallMode;
 use tauri_utils::{
+  assets::pack::ASSET_PACK_FILE_NAME,
   config::{BundleType, DeepLinkProtocol, FileAssociation, NSISInstallerMode, NsisCompression},
   resources::{external_binaries, ResourcePaths},
 };
@@ -525,6 +526,10 @@ pub struct BundleSettings {
   pub external_bin: Option<Vec<String>>,
   /// Deep-link protocols.
   pub deep_link_protocols: Option<Vec<DeepLinkProtocol>>,
+  /// Whether the app build writes its frontend assets to an asset pack (`build > assetPack`).
+  ///
+  /// Only the deb, rpm and AppImage bundles support it.
+  pub asset_pack: bool,
   /// Debian-specific settings.
   pub deb: DebianSettings,
   /// AppImage-specific settings.
@@ -894,6 +899,29 @@ impl Settings {
     Ok(paths)
   }
 
+  /// Whether the app build writes its frontend assets to an asset pack (`build > assetPack`).
+  pub fn asset_pack_enabled(&self) -> bool {
+    self.bundle_settings.asset_pack
+  }
+
+  /// Returns the path of the asset pack written by the app build when `build > assetPack` is enabled.
+  ///
+  /// The pack must be placed at the root of the resource directory.
+  pub fn asset_pack(&self) -> crate::Result<Option<PathBuf>> {
+    if !self.bundle_settings.asset_pack {
+      return Ok(None);
+    }
+    let path = self.project_out_directory.join(ASSET_PACK_FILE_NAME);
+    if path.is_file() {
+      Ok(Some(path))
+    } else {
+      Err(crate::Error::GenericError(format!(
+        "`build > assetPack` is enabled but the asset pack was not found at {}",
+        path.display()
+      )))
+    }
+  }
+
   /// Copies resources to a path.
   pub fn copy_resources(&self, path: &Path) -> crate::Result<()> {
     for resource in self.resource_files().iter() {
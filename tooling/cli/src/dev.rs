@@ -336,7 +336,8 @@ pub fn setup(
         } else {
           Ipv4Addr::new(127, 0, 0, 1).into()
         };
-        let server_url = builtin_dev_server::start(path, ip, options.port)?;
+        let routing = config.lock().unwrap().as_ref().unwrap().app.routing.clone();
+        let server_url = builtin_dev_server::start(path, ip, options.port, routing)?;
         let server_url = format!("http://{server_url}");
         dev_url = Some(server_url.parse().unwrap());
 
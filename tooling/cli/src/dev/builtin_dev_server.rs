@@ -12,10 +12,15 @@ use kuchiki::{traits::TendrilSink, NodeRef};
 use std::{
   net::{IpAddr, SocketAddr},
   path::{Path, PathBuf},
+  sync::Arc,
   thread,
   time::Duration,
 };
-use tauri_utils::mime_type::MimeType;
+use tauri_utils::{
+  config::RoutingConfig,
+  mime_type::MimeType,
+  routing::{Route, Routes},
+};
 use tokio::sync::broadcast::{channel, Sender};
 
 const RELOAD_SCRIPT: &str = include_str!("./auto-reload.js");
@@ -25,9 +30,15 @@ struct ServerState {
   dir: PathBuf,
   address: SocketAddr,
   tx: Sender<()>,
+  routes: Arc<Routes>,
 }
 
-pub fn start<P: AsRef<Path>>(dir: P, ip: IpAddr, port: Option<u16>) -> crate::Result<SocketAddr> {
+pub fn start<P: AsRef<Path>>(
+  dir: P,
+  ip: IpAddr,
+  port: Option<u16>,
+  routing: RoutingConfig,
+) -> crate::Result<SocketAddr> {
   let dir = dir.as_ref();
   let dir = dunce::canonicalize(dir)?;
 
@@ -56,7 +67,12 @@ pub fn start<P: AsRef<Path>>(dir: P, ip: IpAddr, port: Option<u16>) -> crate::Re
     let _ = tx_c.send(());
   });
 
-  let state = ServerState { dir, tx, address };
+  let state = ServerState {
+    dir,
+    tx,
+    address,
+    routes: Arc::new(Routes::new(&routing)),
+  };
 
   // start router thread
   std::thread::spawn(move || {
@@ -78,35 +94,53 @@ pub fn start<P: AsRef<Path>>(dir: P, ip: IpAddr, port: Option<u16>) -> crate::Re
   Ok(address)
 }
 
-async fn handler(uri: Uri, state: State<ServerState>) -> impl IntoResponse {
+async fn handler(uri: Uri, state: State<ServerState>) -> Response {
   // Frontend files should not contain query parameters. This seems to be how vite handles it.
   let uri = uri.path();
 
-  let uri = if uri == "/" {
-    uri
-  } else {
-    uri.strip_prefix('/').unwrap_or(uri)
-  };
-
-  let bytes = fs_read_scoped(state.dir.join(uri), &state.dir)
-    .or_else(|_| fs_read_scoped(state.dir.join(format!("{}.html", &uri)), &state.dir))
-    .or_else(|_| fs_read_scoped(state.dir.join(format!("{}/index.html", &uri)), &state.dir))
-    .or_else(|_| std::fs::read(state.dir.join("index.html")));
+  let headers = state.routes.headers(uri);
+  let route = state.routes.resolve(uri, |key| {
+    fs_read_scoped(state.dir.join(key), &state.dir).ok()
+  });
 
-  match bytes {
-    Ok(mut bytes) => {
-      let mime_type = MimeType::parse_with_fallback(&bytes, uri, MimeType::OctetStream);
-      if mime_type == MimeType::Html.to_string() {
-        bytes = inject_address(bytes, &state.address);
-      }
-      (StatusCode::OK, [(header::CONTENT_TYPE, mime_type)], bytes)
+  let mut response = match route {
+    Route::Asset { key, asset } => asset_response(StatusCode::OK, &key, asset, &state.address),
+    Route::NotFoundPage { key, asset } => {
+      asset_response(StatusCode::NOT_FOUND, &key, asset, &state.address)
+    }
+    Route::Redirect {
+      location,
+      permanent,
+    } => {
+      let status = if permanent {
+        StatusCode::PERMANENT_REDIRECT
+      } else {
+        StatusCode::TEMPORARY_REDIRECT
+      };
+      (status, [(header::LOCATION, location)]).into_response()
     }
-    Err(_) => (
+    Route::NotFound => (
       StatusCode::NOT_FOUND,
-      [(header::CONTENT_TYPE, "text/plain".into())],
-      vec![],
-    ),
+      [(header::CONTENT_TYPE, "text/plain")],
+    )
+      .into_response(),
+  };
+
+  response.headers_mut().extend(headers);
+  response
+}
+
+fn asset_response(
+  status: StatusCode,
+  key: &str,
+  mut bytes: Vec<u8>,
+  address: &SocketAddr,
+) -> Response {
+  let mime_type = MimeType::parse_with_fallback(&bytes, key, MimeType::OctetStream);
+  if mime_type == MimeType::Html.to_string() {
+    bytes = inject_address(bytes, address);
   }
+  (status, [(header::CONTENT_TYPE, mime_type)], bytes).into_response()
 }
 
 async fn ws_handler(ws: WebSocketUpgrade, state: State<ServerState>) -> Response {
@@ -827,6 +827,8 @@ impl AppSettings for RustAppSettings {
       });
     }
 
+    settings.asset_pack = config.build.asset_pack;
+
     Ok(settings)
   }
 